@@ -1,6 +1,5 @@
 use super::error::Error;
 
-use std::slice;
 use std::str;
 
 use byteorder::{ByteOrder, LittleEndian};
@@ -11,6 +10,8 @@ pub struct Decoder<'a> {
     bytes: &'a [u8],
     offset: usize,
     limit: Option<usize>,
+    // offset of `bytes` in the outermost stream, used for error reporting
+    base: usize,
 }
 
 impl<'a> Decoder<'a> {
@@ -19,96 +20,134 @@ impl<'a> Decoder<'a> {
             bytes,
             offset: 0,
             limit: None,
+            base: 0,
         }
     }
 
-    pub fn seek_mut(&mut self, offset: usize) {
+    pub fn seek_mut(&mut self, offset: usize) -> DecoderResult<()> {
+        if offset > self.bytes.len() {
+            return Err(Error::SeekOutOfRange(self.base + offset));
+        }
+
         self.offset = offset;
+
+        Ok(())
     }
 
-    pub fn seek(&self, offset: usize) -> Self {
-        Decoder {
+    pub fn seek(&self, offset: usize) -> DecoderResult<Self> {
+        if offset > self.bytes.len() {
+            return Err(Error::SeekOutOfRange(self.base + offset));
+        }
+
+        Ok(Decoder {
             bytes: self.bytes,
             offset: offset,
-            limit: self.limit
-        }
+            limit: self.limit,
+            base: self.base,
+        })
     }
 
     pub fn eof(&self) -> bool {
         self.offset >= self.bytes.len()
     }
 
-    pub fn scoped_decoder(&self, len: usize) -> Self {
-        Decoder {
-            bytes: &self.bytes[self.offset..(self.offset + len)],
+    /// Restricts all following reads to end before `limit`, which is an
+    /// offset into the stream.
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = Some(limit);
+    }
+
+    pub fn clear_limit(&mut self) {
+        self.limit = None;
+    }
+
+    pub fn scoped_decoder(&self, len: usize) -> DecoderResult<Self> {
+        let end = self.ensure(len)?;
+
+        Ok(Decoder {
+            bytes: &self.bytes[self.offset..end],
             offset: 0,
             limit: None,
-        }
+            base: self.base + self.offset,
+        })
     }
 
-    pub fn skip(&mut self, n: usize) {
-        self.offset += n;
+    pub fn skip(&mut self, n: usize) -> DecoderResult<()> {
+        self.offset = self.ensure(n)?;
+
+        Ok(())
     }
 
     pub fn get_offset(&self) -> usize {
         self.offset
     }
 
-    pub fn bytes(&mut self, n: usize) -> &'a [u8] {
-        let slice = &self.bytes[self.offset..(self.offset + n)];
+    /// Returns the length of the stream in bytes.
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn bytes(&mut self, n: usize) -> DecoderResult<&'a [u8]> {
+        let end = self.ensure(n)?;
+        let slice = &self.bytes[self.offset..end];
 
-        self.offset += n;
+        self.offset = end;
 
-        slice
+        Ok(slice)
     }
 
-    pub fn words(&mut self, n: usize) -> &'a [u32] {
-        let byte_len = 4 * n;
-        let slice = &self.bytes[self.offset..(self.offset + byte_len)];
+    /// Reads `n` words, which must start at a multiple of 4 in the
+    /// outermost stream like all DXBC tokens.
+    pub fn words(&mut self, n: usize) -> DecoderResult<Vec<u32>> {
+        if (self.base + self.offset) % 4 != 0 {
+            return Err(Error::MisalignedWords(self.base + self.offset));
+        }
 
-        self.offset += byte_len;
+        let byte_len = n.checked_mul(4).ok_or(Error::StreamExpected(self.base + self.offset))?;
+        let end = self.ensure(byte_len)?;
 
-        unsafe {
-            slice::from_raw_parts(slice.as_ptr() as _, n)
-        }
-    }
+        let mut words = vec![0; n];
+        LittleEndian::read_u32_into(&self.bytes[self.offset..end], &mut words);
+
+        self.offset = end;
 
-    pub fn read_u64(&mut self) -> u64 {
-        let val = LittleEndian::read_u64(&self.bytes[self.offset..]);
-        self.offset += 8;
-        val
+        Ok(words)
     }
 
-    pub fn read_u32(&mut self) -> u32 {
-        let val = LittleEndian::read_u32(&self.bytes[self.offset..]);
-        self.offset += 4;
-        val
+    pub fn read_u64(&mut self) -> DecoderResult<u64> {
+        let end = self.ensure(8)?;
+        let val = LittleEndian::read_u64(&self.bytes[self.offset..end]);
+        self.offset = end;
+        Ok(val)
     }
 
-    pub fn read_u32_address(&mut self) -> *const u32 {
-        let ptr = self.bytes[self.offset..].as_ptr() as _;
-        self.offset += 4;
-        ptr
+    pub fn read_u32(&mut self) -> DecoderResult<u32> {
+        let end = self.ensure(4)?;
+        let val = LittleEndian::read_u32(&self.bytes[self.offset..end]);
+        self.offset = end;
+        Ok(val)
     }
 
-    pub fn read_u16(&mut self) -> u16 {
-        let val = LittleEndian::read_u16(&self.bytes[self.offset..]);
-        self.offset += 2;
-        val
+    pub fn read_u16(&mut self) -> DecoderResult<u16> {
+        let end = self.ensure(2)?;
+        let val = LittleEndian::read_u16(&self.bytes[self.offset..end]);
+        self.offset = end;
+        Ok(val)
     }
 
-    pub fn read_u8(&mut self) -> u8 {
+    pub fn read_u8(&mut self) -> DecoderResult<u8> {
+        let end = self.ensure(1)?;
         let val = self.bytes[self.offset];
-        self.offset += 1;
-        val
+        self.offset = end;
+        Ok(val)
     }
 
     pub fn str(&mut self) -> DecoderResult<&'a str> {
-        let null = self.bytes[self.offset..].iter().position(|&b| b == 0).unwrap_or(self.bytes.len());
+        let null = self.find_null()?;
 
         let string = str::from_utf8(
             &self.bytes[self.offset..(self.offset + null)]
-        ).map_err(|e| Error::DecodeStrFailed(self.offset, e));
+        ).map_err(|e| Error::DecodeStrFailed(self.base + self.offset, e));
 
         self.offset += null + 1;
 
@@ -116,15 +155,258 @@ impl<'a> Decoder<'a> {
     }
 
     pub fn string(&mut self) -> DecoderResult<String> {
-        let null = self.bytes[self.offset..].iter().position(|&b| b == 0).unwrap_or(self.bytes.len());
+        let null = self.find_null()?;
 
         let string = String::from_utf8(
             self.bytes[self.offset..(self.offset + null)]
                 .to_vec()
-        ).map_err(|e| Error::DecodeStringFailed(self.offset, e));
+        ).map_err(|e| Error::DecodeStringFailed(self.base + self.offset, e));
 
         self.offset += null + 1;
 
         string
     }
+
+    /// Returns the end offset of a read of `n` bytes, or an error if the read
+    /// would go past the limit or the end of the stream.
+    fn ensure(&self, n: usize) -> DecoderResult<usize> {
+        let end = self.offset.checked_add(n).ok_or(Error::StreamExpected(self.base + self.offset))?;
+
+        if let Some(limit) = self.limit {
+            if end > limit {
+                return Err(Error::LimitReached(self.base + limit));
+            }
+        }
+
+        if end > self.bytes.len() {
+            return Err(Error::StreamExpected(self.base + self.offset));
+        }
+
+        Ok(end)
+    }
+
+    /// Returns the length of the null-terminated string at the current offset.
+    fn find_null(&self) -> DecoderResult<usize> {
+        let end = match self.limit {
+            Some(limit) if limit < self.bytes.len() => limit,
+            _ => self.bytes.len(),
+        };
+
+        if self.offset >= end {
+            return Err(Error::StreamExpected(self.base + self.offset));
+        }
+
+        self.bytes[self.offset..end]
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or(Error::StreamExpected(self.base + end))
+    }
+}
+
+/// Decoder over a stream of tokens, such as a shader program.
+///
+/// Offsets are counted in words, while errors report byte offsets into the
+/// token stream.
+pub struct WordDecoder<'a> {
+    words: &'a [u32],
+    offset: usize,
+    limit: Option<usize>,
+}
+
+impl<'a> WordDecoder<'a> {
+    pub fn new(words: &'a [u32]) -> Self {
+        WordDecoder {
+            words,
+            offset: 0,
+            limit: None,
+        }
+    }
+
+    pub fn seek_mut(&mut self, offset: usize) -> DecoderResult<()> {
+        if offset > self.words.len() {
+            return Err(Error::SeekOutOfRange(4 * offset));
+        }
+
+        self.offset = offset;
+
+        Ok(())
+    }
+
+    pub fn eof(&self) -> bool {
+        self.offset >= self.words.len()
+    }
+
+    /// Restricts all following reads to end before the word offset `limit`.
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = Some(limit);
+    }
+
+    pub fn clear_limit(&mut self) {
+        self.limit = None;
+    }
+
+    pub fn skip(&mut self, n: usize) -> DecoderResult<()> {
+        self.offset = self.ensure(n)?;
+
+        Ok(())
+    }
+
+    pub fn get_offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn words(&mut self, n: usize) -> DecoderResult<&'a [u32]> {
+        let end = self.ensure(n)?;
+        let slice = &self.words[self.offset..end];
+
+        self.offset = end;
+
+        Ok(slice)
+    }
+
+    /// Returns the words between `start` and the current offset.
+    pub fn words_from(&self, start: usize) -> &'a [u32] {
+        &self.words[start.min(self.offset)..self.offset]
+    }
+
+    /// Returns the words from the current offset up to the limit or the end
+    /// of the stream, without advancing.
+    pub fn remaining(&self) -> &'a [u32] {
+        let end = match self.limit {
+            Some(limit) if limit < self.words.len() => limit,
+            _ => self.words.len(),
+        };
+
+        &self.words[self.offset.min(end)..end]
+    }
+
+    pub fn read_u32(&mut self) -> DecoderResult<u32> {
+        let end = self.ensure(1)?;
+        let val = self.words[self.offset];
+        self.offset = end;
+        Ok(val)
+    }
+
+    pub fn read_u64(&mut self) -> DecoderResult<u64> {
+        let end = self.ensure(2)?;
+        let val = (self.words[self.offset] as u64) | ((self.words[self.offset + 1] as u64) << 32);
+        self.offset = end;
+        Ok(val)
+    }
+
+    fn ensure(&self, n: usize) -> DecoderResult<usize> {
+        let end = self.offset.checked_add(n).ok_or(Error::StreamExpected(4 * self.offset))?;
+
+        if let Some(limit) = self.limit {
+            if end > limit {
+                return Err(Error::LimitReached(4 * limit));
+            }
+        }
+
+        if end > self.words.len() {
+            return Err(Error::StreamExpected(4 * self.offset));
+        }
+
+        Ok(end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncated_reads() {
+        let bytes = [1, 0, 0, 0, 2, 0];
+        let mut decoder = Decoder::new(&bytes);
+
+        assert_eq!(decoder.read_u32().unwrap(), 1);
+        match decoder.read_u32() {
+            Err(Error::StreamExpected(4)) => {},
+            r => panic!("unexpected result: {:?}", r),
+        }
+        assert_eq!(decoder.read_u16().unwrap(), 2);
+        assert!(decoder.eof());
+    }
+
+    #[test]
+    fn scoped_offsets() {
+        let bytes = [0, 0, 0, 0, 3, 0, 0, 0];
+        let mut decoder = Decoder::new(&bytes);
+        decoder.skip(4).unwrap();
+
+        let mut scoped = decoder.scoped_decoder(4).unwrap();
+        assert_eq!(scoped.read_u32().unwrap(), 3);
+        match scoped.read_u8() {
+            Err(Error::StreamExpected(8)) => {},
+            r => panic!("unexpected result: {:?}", r),
+        }
+
+        assert!(decoder.scoped_decoder(8).is_err());
+        assert!(decoder.seek(9).is_err());
+    }
+
+    #[test]
+    fn limit() {
+        let bytes = [1, 0, 0, 0, 2, 0, 0, 0];
+        let mut decoder = Decoder::new(&bytes);
+        decoder.set_limit(4);
+
+        assert_eq!(decoder.read_u32().unwrap(), 1);
+        match decoder.read_u32() {
+            Err(Error::LimitReached(4)) => {},
+            r => panic!("unexpected result: {:?}", r),
+        }
+
+        decoder.clear_limit();
+        assert_eq!(decoder.read_u32().unwrap(), 2);
+    }
+
+    #[test]
+    fn unaligned_words() {
+        let bytes = [0, 1, 0, 0, 0, 2, 0, 0, 0];
+        let mut decoder = Decoder::new(&bytes[1..]);
+
+        assert_eq!(decoder.words(2).unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn misaligned_words() {
+        let bytes = [1, 0, 0, 0, 2, 0, 0, 0, 3];
+        let mut decoder = Decoder::new(&bytes);
+        decoder.skip(1).unwrap();
+
+        match decoder.words(1) {
+            Err(Error::MisalignedWords(1)) => {},
+            r => panic!("unexpected result: {:?}", r),
+        }
+
+        let mut scoped = decoder.scoped_decoder(4).unwrap();
+        match scoped.words(1) {
+            Err(Error::MisalignedWords(1)) => {},
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn word_limit() {
+        let words = [1, 2, 3];
+        let mut decoder = WordDecoder::new(&words);
+        decoder.set_limit(2);
+
+        assert_eq!(decoder.read_u64().unwrap(), 0x0000000200000001);
+        assert_eq!(decoder.words_from(0), &[1, 2]);
+        match decoder.read_u32() {
+            Err(Error::LimitReached(8)) => {},
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn unterminated_str() {
+        let bytes = *b"abc";
+        let mut decoder = Decoder::new(&bytes);
+
+        assert!(decoder.str().is_err());
+    }
 }
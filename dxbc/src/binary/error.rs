@@ -4,8 +4,18 @@ use std::str::Utf8Error;
 
 #[derive(Debug)]
 pub enum Error {
+    /// Read past the end of the stream at the given offset
     StreamExpected(usize),
+    /// Read past the decoder limit at the given offset
     LimitReached(usize),
+    /// Seek to an offset outside of the stream
+    SeekOutOfRange(usize),
+    /// Words read at an offset that is not a multiple of 4
+    MisalignedWords(usize),
+    /// Operand token at the given offset has an invalid encoding
+    InvalidOperand(usize),
+    /// Opcode token at the given offset has an invalid encoding
+    InvalidOpcode(usize),
     DecodeStringFailed(usize, FromUtf8Error),
     DecodeStrFailed(usize, Utf8Error),
 }
@@ -13,8 +23,14 @@ pub enum Error {
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
+            Error::StreamExpected(index) => write!(f, "expected more bytes in the stream at index {}", index),
+            Error::LimitReached(index) => write!(f, "decoder limit reached at index {}", index),
+            Error::SeekOutOfRange(index) => write!(f, "cannot seek to index {}: out of range", index),
+            Error::MisalignedWords(index) => write!(f, "cannot read words at index {}: not aligned to 4 bytes", index),
+            Error::InvalidOperand(index) => write!(f, "invalid operand token at index {}", index),
+            Error::InvalidOpcode(index) => write!(f, "invalid opcode token at index {}", index),
             Error::DecodeStringFailed(index, ref e) => write!(f, "cannot decode string at index {}: {}", index, e),
-            _ => write!(f, "unimplemented")
+            Error::DecodeStrFailed(index, ref e) => write!(f, "cannot decode string at index {}: {}", index, e),
         }
     }
 }
@@ -22,7 +38,14 @@ impl fmt::Display for Error {
 impl error::Error for Error {
     fn description(&self) -> &str {
         match *self {
-            _ => "unknown operand value for the given kind",
+            Error::StreamExpected(..) => "expected more bytes in the stream",
+            Error::LimitReached(..) => "decoder limit reached",
+            Error::SeekOutOfRange(..) => "seek out of range",
+            Error::MisalignedWords(..) => "misaligned words",
+            Error::InvalidOperand(..) => "invalid operand token",
+            Error::InvalidOpcode(..) => "invalid opcode token",
+            Error::DecodeStringFailed(..) |
+            Error::DecodeStrFailed(..) => "cannot decode string",
         }
     }
 }
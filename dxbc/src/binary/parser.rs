@@ -3,8 +3,6 @@ use super::error;
 use dr;
 use super::decoder;
 
-use std::mem;
-use std::str;
 
 #[derive(Debug)]
 pub enum State {
@@ -29,13 +27,58 @@ pub trait Consumer {
     fn initialize(&mut self) -> Action;
     fn finalize(&mut self) -> Action;
 
-    fn consume_header(&mut self, header: &dr::DxbcHeader) -> Action { Action::Continue }
-    fn consume_rdef(&mut self, rdef: &dr::RdefChunk) -> Action { Action::Continue }
-    fn consume_isgn(&mut self, isgn: &dr::IOsgnChunk) -> Action { Action::Continue }
-    fn consume_osgn(&mut self, osgn: &dr::IOsgnChunk) -> Action { Action::Continue }
-    fn consume_shex(&mut self, osgn: &dr::ShexHeader) -> Action { Action::Continue }
-    fn consume_stat(&mut self, osgn: &dr::IStatChunk) -> Action { Action::Continue }
-    fn consume_instruction(&mut self, offset: u32, instruction: dr::SparseInstruction) -> Action { Action::Continue }
+    fn consume_header(&mut self, _header: &dr::DxbcHeader) -> Action { Action::Continue }
+    fn consume_rdef(&mut self, _rdef: &dr::RdefChunk) -> Action { Action::Continue }
+    fn consume_isgn(&mut self, _isgn: &dr::IOsgnChunk) -> Action { Action::Continue }
+    fn consume_osgn(&mut self, _osgn: &dr::IOsgnChunk) -> Action { Action::Continue }
+    fn consume_pcsg(&mut self, _pcsg: &dr::IOsgnChunk) -> Action { Action::Continue }
+    fn consume_shex(&mut self, _shex: &dr::ShexHeader) -> Action { Action::Continue }
+    fn consume_stat(&mut self, _stat: &dr::IStatChunk) -> Action { Action::Continue }
+    fn consume_instruction(&mut self, _offset: u32, _instruction: dr::SparseInstruction) -> Action { Action::Continue }
+    fn consume_unknown_chunk(&mut self, _fourcc: &[u8; 4], _offset: u32, _bytes: &[u8]) -> Action { Action::Continue }
+}
+
+/// Entry of the chunk table in the container header.
+#[derive(Debug, Copy, Clone)]
+pub struct ChunkDescriptor {
+    pub fourcc: [u8; 4],
+    /// Offset of the chunk header from the start of the container
+    pub offset: u32,
+    /// Size of the chunk data, excluding the chunk header
+    pub size: u32,
+}
+
+/// Lists every chunk of a DXBC container in the order of the header table.
+pub fn chunk_directory(binary: &[u8]) -> Result<Vec<ChunkDescriptor>, State> {
+    let mut decoder = decoder::Decoder::new(binary);
+    let header = dr::DxbcHeader::parse(&mut decoder)?;
+
+    read_chunk_directory(&mut decoder, &header)
+}
+
+pub(crate) fn read_chunk_directory(decoder: &mut decoder::Decoder, header: &dr::DxbcHeader) -> Result<Vec<ChunkDescriptor>, State> {
+    let chunk_offsets = decoder.words(header.chunk_count as usize)?;
+
+    let mut chunks = Vec::new();
+    for &offset in &chunk_offsets {
+        let mut decoder = decoder.seek(offset as usize)?;
+        let fourcc = decoder.bytes(4)?;
+        let size = decoder.read_u32()?;
+
+        chunks.push(ChunkDescriptor {
+            fourcc: [fourcc[0], fourcc[1], fourcc[2], fourcc[3]],
+            offset,
+            size,
+        });
+    }
+
+    Ok(chunks)
+}
+
+impl From<error::Error> for State {
+    fn from(err: error::Error) -> Self {
+        State::DecoderError(err)
+    }
 }
 
 fn try_consume(action: Action) -> Result<(), State> {
@@ -62,40 +105,52 @@ impl<'c, 'd> Parser<'c, 'd> {
     pub fn parse(&mut self) -> Result<(), State> {
         try_consume(self.consumer.initialize())?;
 
-        let header = self.parse_header()?;
-        try_consume(self.consumer.consume_header(header))?;
+        let header = dr::DxbcHeader::parse(&mut self.decoder)?;
+        try_consume(self.consumer.consume_header(&header))?;
 
-        let chunk_offsets = self.decoder.words(header.chunk_count as usize);
+        let chunks = read_chunk_directory(&mut self.decoder, &header)?;
 
-        for &chunk_offset in chunk_offsets {
-            self.decoder.seek_mut(chunk_offset as usize);
-            let fourcc = self.decoder.bytes(4);
-            let chunk_length = self.decoder.read_u32();
+        for chunk in &chunks {
+            self.decoder.seek_mut(chunk.offset as usize + 8)?;
 
-            let mut decoder = self.decoder.scoped_decoder(chunk_length as usize);
+            let mut decoder = self.decoder.scoped_decoder(chunk.size as usize)?;
 
-            match fourcc {
+            match &chunk.fourcc {
                 b"RDEF" => {
                     let rdef = dr::RdefChunk::parse(&mut decoder)?;
                     try_consume(self.consumer.consume_rdef(&rdef))?;
                 },
-                b"ISGN" => {
-                    let isgn = dr::IOsgnChunk::parse(&mut decoder)?;
+                b"ISGN" |
+                b"ISG1" => {
+                    let layout = dr::SignatureLayout::from_fourcc(&chunk.fourcc).unwrap();
+                    let isgn = dr::IOsgnChunk::parse(&mut decoder, layout)?;
                     try_consume(self.consumer.consume_isgn(&isgn))?;
                 },
-                b"OSGN" => {
-                    let osgn = dr::IOsgnChunk::parse(&mut decoder)?;
+                b"OSGN" |
+                b"OSG5" |
+                b"OSG1" => {
+                    let layout = dr::SignatureLayout::from_fourcc(&chunk.fourcc).unwrap();
+                    let osgn = dr::IOsgnChunk::parse(&mut decoder, layout)?;
                     try_consume(self.consumer.consume_osgn(&osgn))?;
                 },
+                b"PCSG" |
+                b"PSG1" => {
+                    let layout = dr::SignatureLayout::from_fourcc(&chunk.fourcc).unwrap();
+                    let pcsg = dr::IOsgnChunk::parse(&mut decoder, layout)?;
+                    try_consume(self.consumer.consume_pcsg(&pcsg))?;
+                },
+                b"SHDR" |
                 b"SHEX" => {
-                    let shex = dr::ShexHeader::parse(&mut decoder)?;
+                    let kind = dr::ShaderChunkKind::from_fourcc(&chunk.fourcc).unwrap();
+                    let shex = dr::ShexHeader::parse(&mut decoder, kind)?;
                     try_consume(self.consumer.consume_shex(&shex))?;
 
-                    let mut decoder = decoder.scoped_decoder(shex.instruction_length as usize * 4);
+                    let code = decoder.words(shex.instruction_length as usize)?;
+                    let mut decoder = decoder::WordDecoder::new(&code);
 
                     while !decoder.eof() {
-                        let offset = decoder.get_offset();
-                        let instruction = dr::SparseInstruction::parse(&mut decoder);
+                        let offset = 4 * decoder.get_offset();
+                        let instruction = dr::SparseInstruction::parse(&mut decoder)?;
 
                         try_consume(self.consumer.consume_instruction(offset as u32, instruction))?;
                     }
@@ -105,13 +160,8 @@ impl<'c, 'd> Parser<'c, 'd> {
                     try_consume(self.consumer.consume_stat(&stat))?;
                 }
                 _ => {
-                    eprintln!(
-                        "{}: Incorrect or unimplemented chunk type '{}'",
-                        chunk_offset,
-                        unsafe {
-                            str::from_utf8_unchecked(fourcc)
-                        },
-                    );
+                    let bytes = decoder.bytes(chunk.size as usize)?;
+                    try_consume(self.consumer.consume_unknown_chunk(&chunk.fourcc, chunk.offset, bytes))?;
                 }
             }
         }
@@ -120,18 +170,67 @@ impl<'c, 'd> Parser<'c, 'd> {
 
         Ok(())
     }
+}
 
-    fn parse_header(&mut self) -> Result<&'d dr::DxbcHeader, State> {
-        let bytes = self.decoder.bytes(mem::size_of::<dr::DxbcHeader>());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use d3d11tokenizedprogramformat::*;
+    use dr::{Container, ShaderChunkKind};
 
-        let header: &'d dr::DxbcHeader = unsafe {
-            mem::transmute(bytes.as_ptr())
-        };
+    use byteorder::{ByteOrder, LittleEndian};
+
+    struct ProgramConsumer {
+        kind: Option<ShaderChunkKind>,
+        profile: Option<String>,
+        instructions: usize,
+    }
 
-        if header.magic == *b"DXBC" {
-            Ok(header)
-        } else {
-            Err(State::HeaderIncorrect)
+    impl Consumer for ProgramConsumer {
+        fn initialize(&mut self) -> Action { Action::Continue }
+        fn finalize(&mut self) -> Action { Action::Continue }
+
+        fn consume_shex(&mut self, shex: &dr::ShexHeader) -> Action {
+            self.kind = Some(shex.kind);
+            self.profile = shex.get_profile();
+            Action::Continue
+        }
+
+        fn consume_instruction(&mut self, _offset: u32, _instruction: dr::SparseInstruction) -> Action {
+            self.instructions += 1;
+            Action::Continue
         }
     }
+
+    #[test]
+    fn shdr_program() {
+        // vs_4_0 consisting of a single ret
+        let words = [
+            ENCODE_D3D10_SB_TOKENIZED_PROGRAM_VERSION_TOKEN(D3D10_SB_VERTEX_SHADER, 4, 0),
+            3,
+            ENCODE_D3D10_SB_OPCODE_TYPE(D3D10_SB_OPCODE_RET) | ENCODE_D3D10_SB_TOKENIZED_INSTRUCTION_LENGTH(1),
+        ];
+        let mut data = vec![0; 4 * words.len()];
+        LittleEndian::write_u32_into(&words, &mut data);
+
+        let mut container = Container::new();
+        container.set_chunk(*b"SHDR", data);
+        let module = container.module();
+
+        let mut consumer = ProgramConsumer {
+            kind: None,
+            profile: None,
+            instructions: 0,
+        };
+        Parser::new(module.as_bytes(), &mut consumer).parse().unwrap();
+        assert_eq!(consumer.kind, Some(ShaderChunkKind::Shdr));
+        assert_eq!(consumer.profile, Some(String::from("vs_4_0")));
+        assert_eq!(consumer.instructions, 1);
+
+        let container = Container::parse(module.as_bytes()).unwrap();
+        let (header, code) = container.shex().unwrap().unwrap();
+        assert_eq!(header.kind, ShaderChunkKind::Shdr);
+        assert_eq!(header.get_profile(), Some(String::from("vs_4_0")));
+        assert_eq!(code.len(), 1);
+    }
 }
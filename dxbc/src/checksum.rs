@@ -16,11 +16,9 @@ pub fn checksum(module: &[u8]) -> [u32; 4] {
         cxt.consume(&last_data[..last_chunk_sz as usize]);
         cxt.consume(&md5::PADDING[..padding_sz as usize]);
 
+        // the length goes into a block of its own, which must not reuse the
+        // data left in `cxt.input` by the block consumed above
         let mut input = [0u32; 16];
-        unsafe {
-            ::std::ptr::copy(cxt.input.as_ptr(), input.as_mut_ptr() as *mut u8, 64);
-        }
-
         input[0] = bits;
         input[15] = (bits >> 2) | 1;
 
@@ -53,3 +51,48 @@ pub fn checksum(module: &[u8]) -> [u32; 4] {
     cxt.buffer
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stored_checksums() {
+        let shaders: &[&[u8]] = &[
+            include_bytes!("../../dxbcd/assembled.dxbc"),
+            include_bytes!("../../dxbcd/reference.dxbc"),
+            include_bytes!("../../dxbcd/shader.dxbc"),
+        ];
+
+        for bytes in shaders {
+            let mut stored = [0u32; 4];
+            for (i, word) in stored.iter_mut().enumerate() {
+                let offset = 4 + 4 * i;
+                *word = u32::from(bytes[offset]) | u32::from(bytes[offset + 1]) << 8 |
+                    u32::from(bytes[offset + 2]) << 16 | u32::from(bytes[offset + 3]) << 24;
+            }
+
+            assert_eq!(checksum(bytes), stored);
+        }
+    }
+
+    #[test]
+    fn trailing_lengths() {
+        // the last partial block takes a different path from 56 bytes on,
+        // where the length no longer fits in front of the data
+        let expected = [
+            (0, [0x1b4bf5c1, 0xd39a4c56, 0x476fe115, 0x18cbffb5]),
+            (16, [0x22ad4892, 0xf41dddf6, 0xbf1b040c, 0x42de75e8]),
+            (55, [0xd5e08802, 0xaaa5c35c, 0xf4e8bb59, 0xd3a74556]),
+            (56, [0x49b92e8f, 0x76daa24c, 0x13cf6db2, 0x409d0001]),
+            (60, [0x844dd03a, 0x7b24aeea, 0xf44f2abd, 0x3beb539d]),
+            (63, [0x4e4c0990, 0x1afe1a25, 0xca9af5f1, 0xea690d5f]),
+        ];
+
+        for &(tail, checksum_words) in &expected {
+            let bytes: Vec<u8> = (0..0x14 + 64 + tail).map(|i| (i * 7 + 3) as u8).collect();
+
+            assert_eq!(checksum(&bytes), checksum_words, "{} trailing bytes", tail);
+        }
+    }
+}
@@ -725,7 +725,9 @@ pub fn D3D10_SB_OPERAND_INDEX_REPRESENTATION_SHIFT(Dim: DWORD) -> DWORD {
 }
 #[inline]
 pub fn D3D10_SB_OPERAND_INDEX_REPRESENTATION_MASK(Dim: DWORD) -> DWORD {
-    0x3 << D3D10_SB_OPERAND_INDEX_REPRESENTATION_SHIFT(Dim)
+    // the original header uses 0x3 here, which cannot hold
+    // D3D10_SB_OPERAND_INDEX_IMMEDIATE64_PLUS_RELATIVE
+    0x7 << D3D10_SB_OPERAND_INDEX_REPRESENTATION_SHIFT(Dim)
 }
 #[inline]
 pub fn DECODE_D3D10_SB_OPERAND_INDEX_REPRESENTATION(Dim: DWORD, OperandToken0: DWORD) -> D3D10_SB_OPERAND_INDEX_REPRESENTATION {
@@ -1,6 +1,7 @@
-use dr::shex::{ResourceDimension, ResourceReturnType};
-use dr::{IStatChunk, IOsgnChunk, RdefChunk};
+use dr::shex::{CustomDataClass, ExtendedOpcode, Index, InterpolationMode, NameToken, Primitive, PrimitiveTopology, ProgramType, RegisterRange, ResourceDimension, ResourceReturnType, SamplerMode, TessellatorDomain, TessellatorOutputPrimitive, TessellatorPartitioning, TestBoolean};
+use dr::{IStatChunk, IOsgnChunk, MinPrecision, RdefChunk, Rd11Header, ShaderChunkKind, ShaderModel, ShaderType, SignatureLayout, SparseInstruction, StatLayout};
 
+use binary::decoder::{DecoderResult, WordDecoder};
 use byteorder::{ByteOrder, LittleEndian};
 use checksum;
 use d3d11tokenizedprogramformat::*;
@@ -10,17 +11,60 @@ use std::{slice, mem};
 const DXBC_MAGIC: u32 = 0x43425844;
 const RDEF_MAGIC: u32 = 0x46454452;
 const RD11_MAGIC: u32 = 0x31314452;
+const RD11_MAGIC_5_1: u32 = 0x25441313;
 const ISGN_MAGIC: u32 = 0x4e475349;
 const OSGN_MAGIC: u32 = 0x4e47534f;
+const PCSG_MAGIC: u32 = 0x47534350;
+const ISG1_MAGIC: u32 = 0x31475349;
+const OSG1_MAGIC: u32 = 0x3147534f;
+const OSG5_MAGIC: u32 = 0x3547534f;
+const PSG1_MAGIC: u32 = 0x31475350;
+const SHDR_MAGIC: u32 = 0x52444853;
 const SHEX_MAGIC: u32 = 0x58454853;
 const STAT_MAGIC: u32 = 0x54415453;
+const IFCE_MAGIC: u32 = 0x45434649;
+const SFI0_MAGIC: u32 = 0x30494653;
+
+/// Identifies a chunk written by the `Builder`, used to configure the order
+/// of the chunk table.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ChunkId {
+    Rdef,
+    Ifce,
+    Isgn,
+    Osgn,
+    Pcsg,
+    Shex,
+    Sfi0,
+    Stat,
+    /// A raw chunk added with `Builder::add_chunk`
+    Raw([u8; 4]),
+}
+
+/// Order chunks are written in unless overridden, matching fxc. Raw chunks
+/// follow in the order they were added.
+const DEFAULT_CHUNK_ORDER: &[ChunkId] = &[
+    ChunkId::Rdef,
+    ChunkId::Ifce,
+    ChunkId::Isgn,
+    ChunkId::Osgn,
+    ChunkId::Pcsg,
+    ChunkId::Shex,
+    ChunkId::Sfi0,
+    ChunkId::Stat,
+];
 
 pub struct Builder<'a> {
     rdef: Option<RdefChunk<'a>>,
+    ifce: Option<Vec<u8>>,
     isgn: Option<IOsgnChunk>,
     osgn: Option<IOsgnChunk>,
+    pcsg: Option<IOsgnChunk>,
     stat: Option<IStatChunk>,
     shex: Option<ShexChunk>,
+    sfi0: Option<u64>,
+    raw: Vec<([u8; 4], Vec<u8>)>,
+    order: Vec<ChunkId>,
     code: Vec<u32>,
 }
 
@@ -55,6 +99,15 @@ impl DxbcModule {
         self.dwords[offset] = val;
     }
 
+    /// Writes `bytes` as little-endian words, zero-padding the last word.
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(4) {
+            let mut word = [0u8; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.write_u32(LittleEndian::read_u32(&word));
+        }
+    }
+
     pub fn write_str(&mut self, text: &str) -> u32 {
         let mut len = 0;
 
@@ -83,6 +136,26 @@ impl DxbcModule {
         len
     }
 
+    /// Writes a chunk with the given fourcc and `data` as its contents.
+    pub fn write_chunk(&mut self, fourcc: &[u8; 4], data: &[u8]) {
+        self.write_u32(LittleEndian::read_u32(fourcc));
+        self.write_u32(data.len() as u32);
+        self.write_bytes(data);
+    }
+
+    pub fn write_ifce(&mut self, data: &[u8]) {
+        self.write_u32(IFCE_MAGIC);
+        self.write_u32(data.len() as u32);
+        self.write_bytes(data);
+    }
+
+    pub fn write_sfi0(&mut self, flags: u64) {
+        self.write_u32(SFI0_MAGIC);
+        self.write_u32(8);
+        self.write_u32(flags as u32);
+        self.write_u32((flags >> 32) as u32);
+    }
+
     pub fn write_stat(&mut self, stat: &IStatChunk) {
         self.write_u32(STAT_MAGIC);
 
@@ -90,35 +163,17 @@ impl DxbcModule {
         self.write_u32(0);
         let chunk_start = self.position();
 
-        self.write_u32(stat.instruction_count);
-        self.write_u32(stat.temp_register_count);
-        self.write_u32(stat.def_count);
-        self.write_u32(stat.dcl_count);
-        self.write_u32(stat.float_instruction_count);
-        self.write_u32(stat.int_instruction_count);
-        self.write_u32(stat.uint_instruction_count);
-        self.write_u32(stat.static_flow_control_count);
-        self.write_u32(stat.dynamic_flow_control_count);
-        self.write_u32(stat.macro_instruction_count);
-        self.write_u32(stat.temp_array_count);
-        self.write_u32(stat.array_instruction_count);
-        self.write_u32(stat.cut_instruction_count);
-        self.write_u32(stat.emit_instruction_count);
-        self.write_u32(stat.texture_normal_instructions);
-        self.write_u32(stat.texture_load_instructions);
-        self.write_u32(stat.texture_comp_instructions);
-        self.write_u32(stat.texture_bias_instructions);
-        self.write_u32(stat.texture_gradient_instructions);
-
-        for _ in 0..18 {
-            self.write_u32(0);
+        for word in stat.to_words() {
+            self.write_u32(word);
         }
 
         let end_pos = self.position();
         self.set_u32(stat_size_pos, 4 * (end_pos - chunk_start) as u32);
     }
 
-    pub fn write_rdef(&mut self, rdef: &RdefChunk) {
+    pub fn write_rdef<'a>(&mut self, rdef: &RdefChunk<'a>) {
+        let layout = rdef.rd11.unwrap_or_else(Rd11Header::sm4);
+
         self.write_u32(RDEF_MAGIC);
         let rdef_size_pos = self.position();
         self.write_u32(0);
@@ -131,52 +186,196 @@ impl DxbcModule {
         self.write_u32(0);
 
         let version_tok = (((rdef.shader_ty as u32) << 16) & 0xffff0000) |
-                          (((rdef.major as u32) << 4) & 0x000000f0) |
-                          (rdef.minor as u32 & 0x0000000f);
+                          (((rdef.major as u32) << 8) & 0x0000ff00) |
+                          (rdef.minor as u32 & 0x000000ff);
         self.write_u32(version_tok);
         self.write_u32(rdef.flags);
-        let author_pos = self.position();
+
+        // strings are written at the end of the chunk, their offsets are
+        // patched in once known
+        let mut strings = Vec::new();
+        strings.push((self.position(), rdef.author));
         self.write_u32(0);
 
         if let Some(rd11) = rdef.rd11 {
-            self.write_u32(RD11_MAGIC);
-            self.write_u32(60);
-            self.write_u32(24);
-            self.write_u32(32);
-            self.write_u32(40);
-            self.write_u32(36);
-            self.write_u32(12);
+            if rdef.major == 5 && rdef.minor >= 1 {
+                self.write_u32(RD11_MAGIC_5_1);
+            } else {
+                self.write_u32(RD11_MAGIC);
+            }
+            self.write_u32(rd11.rdef_header_size);
+            self.write_u32(rd11.constant_buffer_size);
+            self.write_u32(rd11.resource_binding_size);
+            self.write_u32(rd11.variable_size);
+            self.write_u32(rd11.type_size);
+            self.write_u32(rd11.member_size);
+            self.write_u32(rd11.interface_slot_count);
+        }
+
+        let resource_bindings_loc = 4 * (self.position() - chunk_start) as u32;
+        self.set_u32(resource_bindings_pos, resource_bindings_loc);
+        for resource_binding in &rdef.resource_bindings {
+            let start = self.position();
+            strings.push((self.position(), resource_binding.name));
             self.write_u32(0);
-            // self.write_u32(rd11[0]);
-            // self.write_u32(rd11[1]);
-            // self.write_u32(rd11[2]);
-            // self.write_u32(rd11[3]);
-            // self.write_u32(rd11[4]);
-            // self.write_u32(rd11[5]);
-            // self.write_u32(rd11[6]);
+            self.write_u32(resource_binding.input_type as u32);
+            self.write_u32(resource_binding.return_type as u32);
+            self.write_u32(resource_binding.view_dimension as u32);
+            self.write_u32(resource_binding.sample_count);
+            self.write_u32(resource_binding.bind_point);
+            self.write_u32(resource_binding.bind_count);
+            self.write_u32(resource_binding.input_flags.bits());
+
+            if layout.resource_binding_size >= 40 {
+                self.write_u32(resource_binding.space.unwrap_or(0));
+                self.write_u32(resource_binding.id.unwrap_or(0));
+            }
+
+            self.pad_to(start, layout.resource_binding_size);
         }
 
-        let constant_buffers_loc = (self.position() - chunk_start) as u32;
+        let constant_buffers_loc = 4 * (self.position() - chunk_start) as u32;
         self.set_u32(constant_buffers_pos, constant_buffers_loc);
+        let mut variable_positions = Vec::new();
         for constant_buffer in &rdef.constant_buffers {
-            // TODO: 
+            let start = self.position();
+            strings.push((self.position(), constant_buffer.name));
+            self.write_u32(0);
+            self.write_u32(constant_buffer.variables.len() as u32);
+            variable_positions.push(self.position());
+            self.write_u32(0);
+            self.write_u32(constant_buffer.byte_size);
+            self.write_u32(constant_buffer.flags.bits());
+            self.write_u32(constant_buffer.ty as u32);
+            self.pad_to(start, layout.constant_buffer_size);
         }
 
-        let resource_bindings_loc = (self.position() - chunk_start) as u32;
-        self.set_u32(resource_bindings_pos, resource_bindings_loc);
-        for resource_binding in &rdef.resource_bindings {
-            // TODO: 
+        for (constant_buffer, pos) in rdef.constant_buffers.iter().zip(variable_positions) {
+            if constant_buffer.variables.is_empty() {
+                continue;
+            }
+
+            let variables_loc = 4 * (self.position() - chunk_start) as u32;
+            self.set_u32(pos, variables_loc);
+
+            let mut type_positions = Vec::new();
+            for variable in &constant_buffer.variables {
+                let start = self.position();
+                strings.push((self.position(), variable.name));
+                self.write_u32(0);
+                self.write_u32(variable.start_offset);
+                self.write_u32(variable.byte_size);
+                self.write_u32(variable.flags.bits());
+                type_positions.push(self.position());
+                self.write_u32(0);
+                self.write_u32(0);
+
+                if layout.variable_size >= 40 {
+                    match variable.slots {
+                        Some(slots) => {
+                            self.write_u32(slots.start_texture);
+                            self.write_u32(slots.texture_size);
+                            self.write_u32(slots.start_sampler);
+                            self.write_u32(slots.sampler_size);
+                        }
+                        None => {
+                            self.write_u32(0xffffffff);
+                            self.write_u32(0);
+                            self.write_u32(0xffffffff);
+                            self.write_u32(0);
+                        }
+                    }
+                }
+
+                self.pad_to(start, layout.variable_size);
+            }
+
+            for (variable, pos) in constant_buffer.variables.iter().zip(type_positions) {
+                let type_loc = self.write_rdef_type(&variable.ty, chunk_start, &layout, &mut strings);
+                self.set_u32(pos, type_loc);
+
+                if let Some(default_value) = variable.default_value {
+                    let default_value_loc = 4 * (self.position() - chunk_start) as u32;
+                    self.set_u32(pos + 1, default_value_loc);
+                    self.write_bytes(default_value);
+                }
+            }
         }
 
-        let author_loc = 4 * (self.position() - chunk_start) as u32;
-        self.set_u32(author_pos, author_loc);
-        self.write_str(rdef.author);
+        for (pos, string) in strings {
+            let string_loc = 4 * (self.position() - chunk_start) as u32;
+            self.set_u32(pos, string_loc);
+            self.write_str(string);
+        }
 
         let end_pos = self.position();
         self.set_u32(rdef_size_pos, 4 * (end_pos - chunk_start) as u32);
     }
 
+    // writes a type description followed by its members, and returns the
+    // offset of the description in the chunk
+    fn write_rdef_type<'a>(&mut self, ty: &ShaderType<'a>, chunk_start: usize, layout: &Rd11Header, strings: &mut Vec<(usize, &'a str)>) -> u32 {
+        let start = self.position();
+        let type_loc = 4 * (self.position() - chunk_start) as u32;
+
+        self.write_u32((ty.class as u32) | ((ty.ty as u32) << 16));
+        self.write_u32((ty.rows as u32) | ((ty.columns as u32) << 16));
+        self.write_u32((ty.elements as u32) | ((ty.members.len() as u32) << 16));
+        let members_pos = self.position();
+        self.write_u32(0);
+
+        if layout.type_size >= 36 {
+            for _ in 0..4 {
+                self.write_u32(0);
+            }
+
+            if let Some(name) = ty.name {
+                strings.push((self.position(), name));
+            }
+            self.write_u32(0);
+        }
+
+        self.pad_to(start, layout.type_size);
+
+        if !ty.members.is_empty() {
+            let members_loc = 4 * (self.position() - chunk_start) as u32;
+            self.set_u32(members_pos, members_loc);
+
+            let mut type_positions = Vec::new();
+            for member in &ty.members {
+                let start = self.position();
+                strings.push((self.position(), member.name));
+                self.write_u32(0);
+                type_positions.push(self.position());
+                self.write_u32(0);
+                self.write_u32(member.offset);
+                self.pad_to(start, layout.member_size);
+            }
+
+            for (member, pos) in ty.members.iter().zip(type_positions) {
+                let member_type_loc = self.write_rdef_type(&member.ty, chunk_start, layout, strings);
+                self.set_u32(pos, member_type_loc);
+            }
+        }
+
+        type_loc
+    }
+
+    // zero-fills a descriptor which started at `start` up to `size` bytes
+    fn pad_to(&mut self, start: usize, size: u32) {
+        while 4 * (self.position() - start) < size as usize {
+            self.write_u32(0);
+        }
+    }
+
+    /// Writes a signature chunk, using the element layout implied by `magic`.
     pub fn write_iosgn(&mut self, chunk: &IOsgnChunk, magic: u32) {
+        let layout = match magic {
+            OSG5_MAGIC => SignatureLayout::Stream,
+            ISG1_MAGIC | OSG1_MAGIC | PSG1_MAGIC => SignatureLayout::MinPrecision,
+            _ => SignatureLayout::Basic,
+        };
+
         self.write_u32(magic);
         let chunk_sz_pos = self.position();
         self.write_u32(0);
@@ -187,14 +386,22 @@ impl DxbcModule {
 
         let mut string_positions = Vec::new();
         for element in &chunk.elements {
+            if layout != SignatureLayout::Basic {
+                self.write_u32(element.stream.unwrap_or(0));
+            }
+
             string_positions.push(self.position());
             self.write_u32(0);
             self.write_u32(element.semantic_index);
-            self.write_u32(element.semantic_type as u32);
+            self.write_u32(element.semantic_type.to_word());
             self.write_u32(element.component_type as u32);
             self.write_u32(element.register);
             let mask_tok = ((element.rw_mask as u32) << 8) | (element.component_mask as u32);
             self.write_u32(mask_tok);
+
+            if layout == SignatureLayout::MinPrecision {
+                self.write_u32(element.min_precision.map_or(0, |p| p as u32));
+            }
         }
 
         for (element, pos) in chunk.elements.iter().zip(string_positions) {
@@ -208,22 +415,46 @@ impl DxbcModule {
     }
 
     pub fn write_isgn(&mut self, chunk: &IOsgnChunk) {
-        self.write_iosgn(chunk, ISGN_MAGIC);
+        let magic = match chunk.layout {
+            SignatureLayout::MinPrecision => ISG1_MAGIC,
+            _ => ISGN_MAGIC,
+        };
+
+        self.write_iosgn(chunk, magic);
     }
 
     pub fn write_osgn(&mut self, chunk: &IOsgnChunk) {
-        self.write_iosgn(chunk, OSGN_MAGIC);
+        let magic = match chunk.layout {
+            SignatureLayout::Basic => OSGN_MAGIC,
+            SignatureLayout::Stream => OSG5_MAGIC,
+            SignatureLayout::MinPrecision => OSG1_MAGIC,
+        };
+
+        self.write_iosgn(chunk, magic);
     }
 
+    pub fn write_pcsg(&mut self, chunk: &IOsgnChunk) {
+        let magic = match chunk.layout {
+            SignatureLayout::MinPrecision => PSG1_MAGIC,
+            _ => PCSG_MAGIC,
+        };
+
+        self.write_iosgn(chunk, magic);
+    }
+
+    /// Writes the program as `SHDR` or `SHEX`, depending on its shader model.
     pub fn write_shex(&mut self, chunk: &ShexChunk) {
-        self.write_u32(SHEX_MAGIC);
+        self.write_u32(match chunk.get_kind() {
+            ShaderChunkKind::Shdr => SHDR_MAGIC,
+            ShaderChunkKind::Shex => SHEX_MAGIC,
+        });
         let chunk_sz_pos = self.position();
         self.write_u32(0);
         let chunk_start = self.position();
 
         self.write_u32(
             ENCODE_D3D10_SB_TOKENIZED_PROGRAM_VERSION_TOKEN(
-                D3D10_SB_VERTEX_SHADER, 5, 0
+                chunk.program_type as u32, chunk.model.major() as u32, chunk.model.minor() as u32
             )
         );
 
@@ -239,6 +470,17 @@ impl DxbcModule {
         self.set_u32(chunk_sz_pos, 4 * chunk_sz as u32);
     }
 
+    /// Writes a custom data block, whose length is stored in the word
+    /// following the opcode rather than in the opcode itself.
+    pub fn write_custom_data(&mut self, class: CustomDataClass, data: &[u32]) {
+        self.write_u32(ENCODE_D3D10_SB_CUSTOMDATA_CLASS(class as u32));
+        self.write_u32(data.len() as u32 + 2);
+
+        for &word in data {
+            self.write_u32(word);
+        }
+    }
+
     pub fn write_opcode(&mut self, op: u32, instruction_len: u32, test: Option<u32>, saturated: bool, extended: &[OpcodeEx]) {
         let mut opcode = ENCODE_D3D10_SB_OPCODE_TYPE(op) |
             ENCODE_D3D10_SB_TOKENIZED_INSTRUCTION_LENGTH(instruction_len) |
@@ -292,26 +534,27 @@ impl DxbcModule {
     }
 
     pub fn write_operand(&mut self, op: u32, modifier: Modifier, component_mode: NumComponent, immediates: &[Immediate]) {
+        self.write_operand_ex(op, modifier, MinPrecision::Default, component_mode, immediates);
+    }
+
+    /// Writes an operand, adding an extended operand token if it has a
+    /// modifier or a minimum precision.
+    pub fn write_operand_ex(&mut self, op: u32, modifier: Modifier, precision: MinPrecision, component_mode: NumComponent, immediates: &[Immediate]) {
         debug_assert!(immediates.len() < 4);
 
         let mut operand = ENCODE_D3D10_SB_OPERAND_TYPE(op);
 
-        if let Modifier::None = modifier {
+        let is_extended = match (modifier, precision) {
+            (Modifier::None, MinPrecision::Default) => false,
+            _ => true,
+        };
 
-        } else {
+        if is_extended {
             operand |= ENCODE_D3D10_SB_OPERAND_EXTENDED(1);
         }
 
         for (idx, imm) in immediates.iter().enumerate() {
-            let repr = match imm {
-                Immediate::U32(..) => D3D10_SB_OPERAND_INDEX_IMMEDIATE32,
-                Immediate::U64(..) => D3D10_SB_OPERAND_INDEX_IMMEDIATE64,
-                Immediate::Relative(..) => D3D10_SB_OPERAND_INDEX_RELATIVE,
-                Immediate::U32Relative(..) => D3D10_SB_OPERAND_INDEX_IMMEDIATE32_PLUS_RELATIVE,
-                Immediate::U64Relative(..) => D3D10_SB_OPERAND_INDEX_IMMEDIATE64_PLUS_RELATIVE,
-            };
-
-            operand |= ENCODE_D3D10_SB_OPERAND_INDEX_REPRESENTATION(idx as u32, repr);
+            operand |= ENCODE_D3D10_SB_OPERAND_INDEX_REPRESENTATION(idx as u32, imm.get_representation() as u32);
         }
 
         let component_count = match component_mode {
@@ -343,12 +586,34 @@ impl DxbcModule {
                 ComponentMode::Swizzle(x, y, z, w) => {
                     operand |=
                         ENCODE_D3D10_SB_OPERAND_4_COMPONENT_SELECTION_MODE(D3D10_SB_OPERAND_4_COMPONENT_SWIZZLE_MODE) |
-                        ENCODE_D3D10_SB_OPERAND_4_COMPONENT_SWIZZLE(x as u32 >> 5, y as u32 >> 5, z as u32 >> 5, w as u32 >> 5);
+                        ENCODE_D3D10_SB_OPERAND_4_COMPONENT_SWIZZLE(component_index(x), component_index(y), component_index(z), component_index(w));
                 },
                 ComponentMode::Select(comp) => {
                     operand |=
                         ENCODE_D3D10_SB_OPERAND_4_COMPONENT_SELECTION_MODE(D3D10_SB_OPERAND_4_COMPONENT_SELECT_1_MODE) |
-                        ENCODE_D3D10_SB_OPERAND_4_COMPONENT_SELECT_1(comp as u32 >> 5);
+                        ENCODE_D3D10_SB_OPERAND_4_COMPONENT_SELECT_1(component_index(comp));
+                },
+                ComponentMode::DoubleMask(mask) => {
+                    let mut components = 0;
+                    if mask & X != 0 {
+                        components |= X | Y;
+                    }
+                    if mask & Y != 0 {
+                        components |= Z | W;
+                    }
+
+                    operand |=
+                        ENCODE_D3D10_SB_OPERAND_4_COMPONENT_SELECTION_MODE(D3D10_SB_OPERAND_4_COMPONENT_MASK_MODE) |
+                        components as u32;
+                },
+                ComponentMode::DoubleSwizzle(a, b) => {
+                    // each double occupies two consecutive 32-bit components
+                    let a = if a == X { 0 } else { 2 };
+                    let b = if b == X { 0 } else { 2 };
+
+                    operand |=
+                        ENCODE_D3D10_SB_OPERAND_4_COMPONENT_SELECTION_MODE(D3D10_SB_OPERAND_4_COMPONENT_SWIZZLE_MODE) |
+                        ENCODE_D3D10_SB_OPERAND_4_COMPONENT_SWIZZLE(a, a + 1, b, b + 1);
                 },
             },
         }
@@ -356,51 +621,59 @@ impl DxbcModule {
         self.write_u32(operand);
 
         let operand_modifier = match modifier {
-            Modifier::None => None,
-            Modifier::Neg => Some(D3D10_SB_OPERAND_MODIFIER_NEG),
-            Modifier::Abs => Some(D3D10_SB_OPERAND_MODIFIER_ABS),
-            Modifier::AbsNeg => Some(D3D10_SB_OPERAND_MODIFIER_ABSNEG),
+            Modifier::None => D3D10_SB_OPERAND_MODIFIER_NONE,
+            Modifier::Neg => D3D10_SB_OPERAND_MODIFIER_NEG,
+            Modifier::Abs => D3D10_SB_OPERAND_MODIFIER_ABS,
+            Modifier::AbsNeg => D3D10_SB_OPERAND_MODIFIER_ABSNEG,
         };
 
-        if let Some(modifier) = operand_modifier {
+        if is_extended {
             self.write_u32(
-                ENCODE_D3D10_SB_EXTENDED_OPERAND_MODIFIER(modifier)
+                ENCODE_D3D10_SB_EXTENDED_OPERAND_MODIFIER(operand_modifier) |
+                ENCODE_D3D11_SB_OPERAND_MIN_PRECISION(precision as u32)
             );
         }
 
         for imm in immediates {
-            let relative = match imm {
-                &Immediate::U32(val) => {
+            match imm {
+                &Index::Imm32(val) |
+                &Index::RelativePlusImm32(_, val) => {
                     self.write_u32(val);
-                    None
                 }
-                &Immediate::U64(val) => {
-                    // TODO: u64
+                &Index::Imm64(val) |
+                &Index::RelativePlusImm64(_, val) => {
                     self.write_u32(val as u32);
-                    None
-                }
-                &Immediate::Relative(ref rel) => {
-                    Some(rel)
+                    self.write_u32((val >> 32) as u32);
                 }
-                &Immediate::U32Relative(val, ref rel) => {
-                    self.write_u32(val);
-                    Some(rel)
-                }
-                &Immediate::U64Relative(val, ref rel) => {
-                    // TODO: u64
-                    self.write_u32(val as u32);
-                    Some(rel)
-                }
-            };
-
-            if let Some(relative) = relative {
-                let operand_ty = relative.get_type();
+                &Index::Relative(..) => {}
+            }
 
-                self.write_operand(operand_ty, relative.modifiers, relative.component_mode, &[]);
+            if let Some(relative) = imm.get_relative() {
+                relative.encode(self);
             }
         }
     }
 
+    // writes the register operand of a resource declaration, which is a 3D
+    // (range ID, lower, upper) operand in SM5.1
+    fn write_binding_operand(&mut self, op: u32, binding: u32, range: Option<RegisterRange>) {
+        let component_mode = if op == D3D10_SB_OPERAND_TYPE_CONSTANT_BUFFER {
+            NumComponent::D4(ComponentMode::Swizzle(X, Y, Z, W))
+        } else {
+            NumComponent::D0
+        };
+
+        match range {
+            Some(range) => self.write_operand(
+                op,
+                Modifier::None,
+                component_mode,
+                &[Index::Imm32(binding), Index::Imm32(range.lower), Index::Imm32(range.upper)]
+            ),
+            None => self.write_operand(op, Modifier::None, component_mode, &[Index::Imm32(binding)]),
+        }
+    }
+
     pub fn as_bytes(&self) -> &[u8] {
         unsafe {
             slice::from_raw_parts(
@@ -415,10 +688,15 @@ impl<'a> Builder<'a> {
     pub fn new() -> Self {
         Builder {
             rdef: None,
+            ifce: None,
             isgn: None,
             osgn: None,
+            pcsg: None,
             shex: None,
+            sfi0: None,
             stat: None,
+            raw: Vec::new(),
+            order: Vec::new(),
             code: Vec::new(),
         }
     }
@@ -427,6 +705,11 @@ impl<'a> Builder<'a> {
         self.rdef = Some(rdef);
     }
 
+    /// Sets the contents of the `IFCE` (interfaces) chunk.
+    pub fn set_ifce(&mut self, data: Vec<u8>) {
+        self.ifce = Some(data);
+    }
+
     pub fn set_isgn(&mut self, isgn: IOsgnChunk) {
         self.isgn = Some(isgn);
     }
@@ -435,19 +718,77 @@ impl<'a> Builder<'a> {
         self.osgn = Some(osgn);
     }
 
+    pub fn set_pcsg(&mut self, pcsg: IOsgnChunk) {
+        self.pcsg = Some(pcsg);
+    }
+
     pub fn set_shex(&mut self, shex: ShexChunk) {
         self.shex = Some(shex);
     }
 
+    /// Sets the shader feature flags written to the `SFI0` chunk.
+    pub fn set_sfi0(&mut self, flags: u64) {
+        self.sfi0 = Some(flags);
+    }
+
     pub fn set_stat(&mut self, stat: IStatChunk) {
         self.stat = Some(stat);
     }
 
+    /// Adds a chunk that is written as-is, replacing any earlier raw chunk
+    /// with the same fourcc.
+    pub fn add_chunk(&mut self, fourcc: [u8; 4], data: Vec<u8>) {
+        match self.raw.iter_mut().find(|&&mut (ref cc, _)| *cc == fourcc) {
+            Some(chunk) => chunk.1 = data,
+            None => self.raw.push((fourcc, data)),
+        }
+    }
+
+    /// Sets the order of the chunk table. Chunks that are present but not
+    /// listed are written afterwards in the default order, and listed chunks
+    /// that were never set are skipped.
+    pub fn set_chunk_order(&mut self, order: &[ChunkId]) {
+        self.order = order.to_vec();
+    }
+
     pub fn set_profile(&mut self) {
 
     }
 
-    pub fn module(&self) -> Result<DxbcModule, ()> {
+    fn has_chunk(&self, id: ChunkId) -> bool {
+        match id {
+            ChunkId::Rdef => self.rdef.is_some(),
+            ChunkId::Ifce => self.ifce.is_some(),
+            ChunkId::Isgn => self.isgn.is_some(),
+            ChunkId::Osgn => self.osgn.is_some(),
+            ChunkId::Pcsg => self.pcsg.is_some(),
+            ChunkId::Shex => self.shex.is_some(),
+            ChunkId::Sfi0 => self.sfi0.is_some(),
+            ChunkId::Stat => self.stat.is_some() || self.shex.is_some(),
+            ChunkId::Raw(fourcc) => self.raw.iter().any(|&(ref cc, _)| *cc == fourcc),
+        }
+    }
+
+    /// Returns the chunks that will be written, in table order.
+    pub fn chunk_order(&self) -> Vec<ChunkId> {
+        let raw = self.raw.iter().map(|&(fourcc, _)| ChunkId::Raw(fourcc));
+        let candidates = self.order.iter().cloned()
+            .chain(DEFAULT_CHUNK_ORDER.iter().cloned())
+            .chain(raw);
+
+        let mut order = Vec::new();
+        for id in candidates {
+            if self.has_chunk(id) && !order.contains(&id) {
+                order.push(id);
+            }
+        }
+
+        order
+    }
+
+    /// Fails if the program cannot be decoded to compute its statistics,
+    /// which are only needed if none were set.
+    pub fn module(&self) -> DecoderResult<DxbcModule> {
         let mut module = DxbcModule::new();
 
         module.write_u32(DXBC_MAGIC);
@@ -462,39 +803,39 @@ impl<'a> Builder<'a> {
         let size_pos = module.position();
         module.write_u32(0);
 
-        let mut chunk_count = 4;
-        module.write_u32(chunk_count);
-        let chunk_count_pos = module.position();
-        for _ in 0..chunk_count {
+        let chunks = self.chunk_order();
+        module.write_u32(chunks.len() as u32);
+        let chunk_offsets_pos = module.position();
+        for _ in &chunks {
             module.write_u32(0);
         }
 
+        // statistics are computed from the program unless set explicitly
+        let computed_stat = match (&self.stat, &self.shex) {
+            (None, Some(shex)) => Some(shex.compute_stat()?),
+            _ => None,
+        };
 
-        if let Some(ref rdef) = self.rdef {
-            let pos = module.position() * 4;
-            module.set_u32(chunk_count_pos, pos as u32);
-            module.write_rdef(rdef);
-        }
-
-        if let Some(ref isgn) = self.isgn {
-            let pos = module.position() * 4;
-            module.set_u32(chunk_count_pos + 1, pos as u32);
-            module.write_isgn(isgn);
-        }
-
-        if let Some(ref osgn) = self.osgn {
-            let pos = module.position() * 4;
-            module.set_u32(chunk_count_pos + 2, pos as u32);
-            module.write_osgn(osgn);
-        }
-
-        if let Some(ref shex) = self.shex {
+        for (idx, id) in chunks.iter().enumerate() {
             let pos = module.position() * 4;
-            module.set_u32(chunk_count_pos + 3, pos as u32);
-            module.write_shex(shex);
+            module.set_u32(chunk_offsets_pos + idx, pos as u32);
+
+            match *id {
+                ChunkId::Rdef => module.write_rdef(self.rdef.as_ref().unwrap()),
+                ChunkId::Ifce => module.write_ifce(self.ifce.as_ref().unwrap()),
+                ChunkId::Isgn => module.write_isgn(self.isgn.as_ref().unwrap()),
+                ChunkId::Osgn => module.write_osgn(self.osgn.as_ref().unwrap()),
+                ChunkId::Pcsg => module.write_pcsg(self.pcsg.as_ref().unwrap()),
+                ChunkId::Shex => module.write_shex(self.shex.as_ref().unwrap()),
+                ChunkId::Sfi0 => module.write_sfi0(self.sfi0.unwrap()),
+                ChunkId::Stat => module.write_stat(self.stat.as_ref().or(computed_stat.as_ref()).unwrap()),
+                ChunkId::Raw(fourcc) => {
+                    let &(_, ref data) = self.raw.iter().find(|&&(ref cc, _)| *cc == fourcc).unwrap();
+                    module.write_chunk(&fourcc, data);
+                }
+            }
         }
 
-
         // finally, patch in size and checksum
         let len = 4 * module.dwords.len() as u32;
         module.set_u32(size_pos, len);
@@ -522,11 +863,24 @@ bitflags! {
     }
 }
 
+bitflags! {
+    pub struct SyncFlags: u32 {
+        const THREADS_IN_GROUP = 1 << 11;
+        const THREAD_GROUP_SHARED_MEMORY = 1 << 12;
+        const UAV_MEMORY_GROUP = 1 << 13;
+        const UAV_MEMORY_GLOBAL = 1 << 14;
+    }
+}
+
+/// System value name of a declared register.
+pub type Semantic = NameToken;
+
 #[repr(u32)]
 #[derive(Debug, Copy, Clone)]
-pub enum Semantic {
-    Undefined = 0,
-    Position = 1,
+pub enum ResInfoReturnType {
+    Float = 0,
+    RcpFloat = 1,
+    Uint = 2,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -551,11 +905,20 @@ pub const Y: u8 = 0x20;
 pub const Z: u8 = 0x40;
 pub const W: u8 = 0x80;
 
+// swizzle and select modes refer to components by index rather than by mask
+fn component_index(component: u8) -> u32 {
+    (component >> 4).trailing_zeros()
+}
+
 #[derive(Debug, Copy, Clone)]
 pub enum ComponentMode {
     Mask(u8),
     Swizzle(u8, u8, u8, u8),
     Select(u8),
+    /// Mask of 64-bit components, where `X` selects `xy` and `Y` selects `zw`
+    DoubleMask(u8),
+    /// Swizzle of 64-bit components, each being `X` or `Y`
+    DoubleSwizzle(u8, u8),
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -568,7 +931,30 @@ pub enum NumComponent {
 #[derive(Debug)]
 pub enum Address {
     Constant(u32),
-    Relative(IndexOperandType)
+    /// Indexed by a single component of a register, e.g. `[r0.x]`
+    Relative(IndexOperandType, u8),
+    /// Indexed by a register component plus a constant, e.g. `[r0.x + 3]`
+    RelativePlusConstant(IndexOperandType, u8, u32),
+}
+
+impl Address {
+    fn to_index(&self) -> Immediate {
+        let relative = |ty: &IndexOperandType, component: u8| {
+            let ty = match *ty {
+                IndexOperandType::Register(reg) => OperandType::Register(reg),
+                IndexOperandType::Input(reg) => OperandType::Input(reg),
+                IndexOperandType::Output(reg) => OperandType::Output(reg),
+            };
+
+            Box::new(Operand::new(ty, Modifier::None, NumComponent::D4(ComponentMode::Select(component))))
+        };
+
+        match self {
+            &Address::Constant(offset) => Index::Imm32(offset),
+            &Address::Relative(ref ty, component) => Index::Relative(relative(ty, component)),
+            &Address::RelativePlusConstant(ref ty, component, offset) => Index::RelativePlusImm32(relative(ty, component), offset),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -582,19 +968,28 @@ pub enum OperandType {
     Imm32x4(u32, u32, u32, u32),
     Resource(u32),
     Sampler(u32),
+    UnorderedAccessView(u32),
     IndexableRegister(u32, Address),
     ConstantBuffer(u32, Address),
-    CustomData(Vec<u32>),
+    ImmediateConstantBuffer(Address),
+    ThreadGroupSharedMemory(u32),
+    /// Interface pointer `fp#[address]`
+    Interface(u32, Address),
+    /// Register of an input control point, `vicp[point][reg]`
+    InputControlPoint(Address, u32),
+    /// Register of an output control point, `vocp[point][reg]`
+    OutputControlPoint(Address, u32),
+    InputPatchConstant(u32),
+    /// Register without an index such as `vThreadID` or `oDepth`, given as
+    /// its operand type
+    System(u32),
+    /// Custom data block of the given class, encoded in place of an
+    /// instruction.
+    CustomData(CustomDataClass, Vec<u32>),
 }
 
-#[derive(Debug)]
-pub enum Immediate {
-    U32(u32),
-    U64(u64),
-    Relative(Operand),
-    U32Relative(u32, Operand),
-    U64Relative(u64, Operand),
-}
+/// Index of an operand to be encoded.
+pub type Immediate = Index<Operand>;
 
 #[derive(Debug)]
 pub enum IndexOperandType {
@@ -607,82 +1002,1165 @@ pub enum IndexOperandType {
 pub enum Instruction {
     DclGlobalFlags { flags: GlobalFlags },
     DclTemps { count: u32 },
-    DclOutputSiv { register: Operand, semantic: Semantic },
+    /// Declares `count` registers of `x#`, each with `components` components
+    DclIndexableTemp { register: u32, count: u32, components: u32 },
     DclInput { register: Operand },
+    DclInputSgv { register: Operand, semantic: Semantic },
+    DclInputSiv { register: Operand, semantic: Semantic },
+    DclInputPs { register: Operand, interpolation: InterpolationMode },
+    DclInputPsSgv { register: Operand, interpolation: InterpolationMode, semantic: Semantic },
+    DclInputPsSiv { register: Operand, interpolation: InterpolationMode, semantic: Semantic },
+    DclOutput { register: Operand },
+    DclOutputSgv { register: Operand, semantic: Semantic },
+    DclOutputSiv { register: Operand, semantic: Semantic },
+    /// Declares `count` registers starting at `register` as dynamically
+    /// indexable
+    DclIndexRange { register: Operand, count: u32 },
+    /// Declares a constant buffer of `size` vectors. `range` must be set for
+    /// SM5.1 programs, in which case `binding` is the range ID.
+    DclConstantBuffer { binding: u32, size: u32, dynamic: bool, range: Option<RegisterRange> },
+    DclResource { binding: u32, dimension: ResourceDimension, return_type: ResourceReturnType, range: Option<RegisterRange> },
+    DclSampler { binding: u32, mode: SamplerMode, range: Option<RegisterRange> },
+    DclGsInputPrimitive { primitive: Primitive },
+    DclGsOutputPrimitiveTopology { topology: PrimitiveTopology },
+    DclMaxOutputVertexCount { count: u32 },
+    DclGsInstanceCount { count: u32 },
+    DclStream { stream: u32 },
+    DclThreadGroup { x: u32, y: u32, z: u32 },
+    DclTgsmRaw { register: u32, byte_count: u32 },
+    DclTgsmStructured { register: u32, stride: u32, count: u32 },
+    DclUavTyped { binding: u32, dimension: ResourceDimension, return_type: ResourceReturnType, globally_coherent: bool, range: Option<RegisterRange> },
+    DclUavRaw { binding: u32, globally_coherent: bool, range: Option<RegisterRange> },
+    DclUavStructured { binding: u32, stride: u32, globally_coherent: bool, order_preserving_counter: bool, range: Option<RegisterRange> },
+    DclResourceRaw { binding: u32, range: Option<RegisterRange> },
+    DclResourceStructured { binding: u32, stride: u32, range: Option<RegisterRange> },
+    DclInputControlPointCount { count: u32 },
+    DclOutputControlPointCount { count: u32 },
+    DclTessDomain { domain: TessellatorDomain },
+    DclTessPartitioning { partitioning: TessellatorPartitioning },
+    DclTessOutputPrimitive { primitive: TessellatorOutputPrimitive },
+    DclHsMaxTessFactor { factor: f32 },
+    DclHsForkPhaseInstanceCount { count: u32 },
+    DclHsJoinPhaseInstanceCount { count: u32 },
+    DclFunctionBody { body: u32 },
+    DclFunctionTable { table: u32, bodies: Vec<u32> },
+    DclInterface { interface: u32, expected_table_length: u32, array_length: u32, tables: Vec<u32>, dynamically_indexed: bool },
+    HsDecls,
+    HsControlPointPhase,
+    HsForkPhase,
+    HsJoinPhase,
+
     Add { dest: Operand, a: Operand, b: Operand, saturated: bool },
     Mul { dest: Operand, a: Operand, b: Operand, saturated: bool },
+    Div { dest: Operand, a: Operand, b: Operand, saturated: bool },
+    Mad { dest: Operand, a: Operand, b: Operand, c: Operand, saturated: bool },
+    Min { dest: Operand, a: Operand, b: Operand, saturated: bool },
+    Max { dest: Operand, a: Operand, b: Operand, saturated: bool },
+    Dp2 { dest: Operand, a: Operand, b: Operand, saturated: bool },
+    Dp3 { dest: Operand, a: Operand, b: Operand, saturated: bool },
+    Dp4 { dest: Operand, a: Operand, b: Operand, saturated: bool },
+    Mov { dest: Operand, src: Operand, saturated: bool },
+    MovC { dest: Operand, cond: Operand, a: Operand, b: Operand, saturated: bool },
+    SwapC { dest_a: Operand, dest_b: Operand, cond: Operand, a: Operand, b: Operand },
+    Exp { dest: Operand, src: Operand, saturated: bool },
+    Log { dest: Operand, src: Operand, saturated: bool },
+    Frc { dest: Operand, src: Operand, saturated: bool },
+    Rcp { dest: Operand, src: Operand, saturated: bool },
+    Rsq { dest: Operand, src: Operand, saturated: bool },
+    Sqrt { dest: Operand, src: Operand, saturated: bool },
+    SinCos { dest_sin: Operand, dest_cos: Operand, src: Operand, saturated: bool },
+    RoundNe { dest: Operand, src: Operand, saturated: bool },
+    RoundNi { dest: Operand, src: Operand, saturated: bool },
+    RoundPi { dest: Operand, src: Operand, saturated: bool },
+    RoundZ { dest: Operand, src: Operand, saturated: bool },
+    DerivRtx { dest: Operand, src: Operand, saturated: bool },
+    DerivRty { dest: Operand, src: Operand, saturated: bool },
+    DerivRtxCoarse { dest: Operand, src: Operand, saturated: bool },
+    DerivRtxFine { dest: Operand, src: Operand, saturated: bool },
+    DerivRtyCoarse { dest: Operand, src: Operand, saturated: bool },
+    DerivRtyFine { dest: Operand, src: Operand, saturated: bool },
+    Eq { dest: Operand, a: Operand, b: Operand },
+    Ge { dest: Operand, a: Operand, b: Operand },
+    Lt { dest: Operand, a: Operand, b: Operand },
+    Ne { dest: Operand, a: Operand, b: Operand },
+    IAdd { dest: Operand, a: Operand, b: Operand },
+    IMad { dest: Operand, a: Operand, b: Operand, c: Operand },
+    IMul { dest_hi: Operand, dest_lo: Operand, a: Operand, b: Operand },
+    IMax { dest: Operand, a: Operand, b: Operand },
+    IMin { dest: Operand, a: Operand, b: Operand },
+    INeg { dest: Operand, src: Operand },
+    IShl { dest: Operand, a: Operand, b: Operand },
+    IShr { dest: Operand, a: Operand, b: Operand },
+    IEq { dest: Operand, a: Operand, b: Operand },
+    IGe { dest: Operand, a: Operand, b: Operand },
+    ILt { dest: Operand, a: Operand, b: Operand },
+    INe { dest: Operand, a: Operand, b: Operand },
+    UDiv { dest_quot: Operand, dest_rem: Operand, a: Operand, b: Operand },
+    UMad { dest: Operand, a: Operand, b: Operand, c: Operand },
+    UMul { dest_hi: Operand, dest_lo: Operand, a: Operand, b: Operand },
+    UMax { dest: Operand, a: Operand, b: Operand },
+    UMin { dest: Operand, a: Operand, b: Operand },
+    UShr { dest: Operand, a: Operand, b: Operand },
+    UGe { dest: Operand, a: Operand, b: Operand },
+    ULt { dest: Operand, a: Operand, b: Operand },
+    UAddC { dest: Operand, dest_carry: Operand, a: Operand, b: Operand },
+    USubB { dest: Operand, dest_borrow: Operand, a: Operand, b: Operand },
+    Msad { dest: Operand, a: Operand, b: Operand, c: Operand },
+    And { dest: Operand, a: Operand, b: Operand },
+    Or { dest: Operand, a: Operand, b: Operand },
+    Xor { dest: Operand, a: Operand, b: Operand },
+    Not { dest: Operand, src: Operand },
+    CountBits { dest: Operand, src: Operand },
+    FirstBitHi { dest: Operand, src: Operand },
+    FirstBitLo { dest: Operand, src: Operand },
+    FirstBitShi { dest: Operand, src: Operand },
+    UBfe { dest: Operand, width: Operand, offset: Operand, src: Operand },
+    IBfe { dest: Operand, width: Operand, offset: Operand, src: Operand },
+    Bfi { dest: Operand, width: Operand, offset: Operand, insert: Operand, src: Operand },
+    BfRev { dest: Operand, src: Operand },
+    Ftoi { dest: Operand, src: Operand },
+    Ftou { dest: Operand, src: Operand },
+    Itof { dest: Operand, src: Operand },
+    Utof { dest: Operand, src: Operand },
+    F32tof16 { dest: Operand, src: Operand },
+    F16tof32 { dest: Operand, src: Operand },
+    DAdd { dest: Operand, a: Operand, b: Operand, saturated: bool },
+    DMul { dest: Operand, a: Operand, b: Operand, saturated: bool },
+    DMax { dest: Operand, a: Operand, b: Operand, saturated: bool },
+    DMin { dest: Operand, a: Operand, b: Operand, saturated: bool },
+    DDiv { dest: Operand, a: Operand, b: Operand, saturated: bool },
+    DFma { dest: Operand, a: Operand, b: Operand, c: Operand, saturated: bool },
+    DRcp { dest: Operand, src: Operand, saturated: bool },
+    DMov { dest: Operand, src: Operand, saturated: bool },
+    DMovC { dest: Operand, cond: Operand, a: Operand, b: Operand, saturated: bool },
+    DEq { dest: Operand, a: Operand, b: Operand },
+    DGe { dest: Operand, a: Operand, b: Operand },
+    DLt { dest: Operand, a: Operand, b: Operand },
+    DNe { dest: Operand, a: Operand, b: Operand },
+    Dtof { dest: Operand, src: Operand },
+    Ftod { dest: Operand, src: Operand },
+    Dtoi { dest: Operand, src: Operand },
+    Dtou { dest: Operand, src: Operand },
+    Itod { dest: Operand, src: Operand },
+    Utod { dest: Operand, src: Operand },
+
+    /// `test` selects whether the branch is taken when `cond` is zero or
+    /// non-zero, as for the other conditional instructions.
+    If { cond: Operand, test: TestBoolean },
+    Else,
+    EndIf,
+    Loop,
+    EndLoop,
+    Break,
+    BreakC { cond: Operand, test: TestBoolean },
+    Continue,
+    ContinueC { cond: Operand, test: TestBoolean },
+    Switch { selector: Operand },
+    Case { value: u32 },
+    Default,
+    EndSwitch,
+    Label { label: u32 },
+    Call { label: u32 },
+    CallC { cond: Operand, label: u32, test: TestBoolean },
+    /// Calls `function` of the interface pointer `interface`
+    InterfaceCall { function: u32, interface: Operand },
+    Discard { cond: Operand, test: TestBoolean },
+    RetC { cond: Operand, test: TestBoolean },
+    Nop,
+    Abort,
+    DebugBreak,
+
+    /// `extended` holds extended opcode tokens, such as texel offsets or the
+    /// resource dimension and return type.
+    Sample { dest: Operand, address: Operand, resource: Operand, sampler: Operand, extended: Vec<OpcodeEx> },
+    SampleB { dest: Operand, address: Operand, resource: Operand, sampler: Operand, bias: Operand, extended: Vec<OpcodeEx> },
+    SampleL { dest: Operand, address: Operand, resource: Operand, sampler: Operand, lod: Operand, extended: Vec<OpcodeEx> },
+    SampleD { dest: Operand, address: Operand, resource: Operand, sampler: Operand, ddx: Operand, ddy: Operand, extended: Vec<OpcodeEx> },
+    SampleC { dest: Operand, address: Operand, resource: Operand, sampler: Operand, reference: Operand, extended: Vec<OpcodeEx> },
+    SampleCLz { dest: Operand, address: Operand, resource: Operand, sampler: Operand, reference: Operand, extended: Vec<OpcodeEx> },
+    Gather4 { dest: Operand, address: Operand, resource: Operand, sampler: Operand, extended: Vec<OpcodeEx> },
+    Gather4C { dest: Operand, address: Operand, resource: Operand, sampler: Operand, reference: Operand, extended: Vec<OpcodeEx> },
+    Gather4Po { dest: Operand, address: Operand, offset: Operand, resource: Operand, sampler: Operand, extended: Vec<OpcodeEx> },
+    Gather4PoC { dest: Operand, address: Operand, offset: Operand, resource: Operand, sampler: Operand, reference: Operand, extended: Vec<OpcodeEx> },
+    Lod { dest: Operand, address: Operand, resource: Operand, sampler: Operand, extended: Vec<OpcodeEx> },
+    Ld { dest: Operand, address: Operand, resource: Operand, extended: Vec<OpcodeEx> },
+    LdMs { dest: Operand, address: Operand, resource: Operand, sample_index: Operand, extended: Vec<OpcodeEx> },
+    ResInfo { dest: Operand, mip_level: Operand, resource: Operand, return_type: ResInfoReturnType },
+    /// Returns the sample count as integers when `uint` is set
+    SampleInfo { dest: Operand, resource: Operand, uint: bool },
+    SamplePos { dest: Operand, resource: Operand, sample_index: Operand },
+    BufInfo { dest: Operand, resource: Operand },
+    EvalSnapped { dest: Operand, src: Operand, offset: Operand },
+    EvalSampleIndex { dest: Operand, src: Operand, sample_index: Operand },
+    EvalCentroid { dest: Operand, src: Operand },
+    LdUavTyped { dest: Operand, address: Operand, uav: Operand, extended: Vec<OpcodeEx> },
+    StoreUavTyped { uav: Operand, address: Operand, src: Operand },
+    LdRaw { dest: Operand, offset: Operand, resource: Operand, extended: Vec<OpcodeEx> },
+    StoreRaw { dest: Operand, offset: Operand, src: Operand },
+    LdStructured { dest: Operand, index: Operand, offset: Operand, resource: Operand, extended: Vec<OpcodeEx> },
+    StoreStructured { dest: Operand, index: Operand, offset: Operand, src: Operand },
+    AtomicAnd { dest: Operand, address: Operand, src: Operand },
+    AtomicOr { dest: Operand, address: Operand, src: Operand },
+    AtomicXor { dest: Operand, address: Operand, src: Operand },
+    AtomicCmpStore { dest: Operand, address: Operand, compare: Operand, src: Operand },
+    AtomicIAdd { dest: Operand, address: Operand, src: Operand },
+    AtomicIMax { dest: Operand, address: Operand, src: Operand },
+    AtomicIMin { dest: Operand, address: Operand, src: Operand },
+    AtomicUMax { dest: Operand, address: Operand, src: Operand },
+    AtomicUMin { dest: Operand, address: Operand, src: Operand },
+    ImmAtomicAlloc { dest: Operand, uav: Operand },
+    ImmAtomicConsume { dest: Operand, uav: Operand },
+    ImmAtomicIAdd { dest: Operand, uav: Operand, address: Operand, src: Operand },
+    ImmAtomicAnd { dest: Operand, uav: Operand, address: Operand, src: Operand },
+    ImmAtomicOr { dest: Operand, uav: Operand, address: Operand, src: Operand },
+    ImmAtomicXor { dest: Operand, uav: Operand, address: Operand, src: Operand },
+    ImmAtomicExch { dest: Operand, uav: Operand, address: Operand, src: Operand },
+    ImmAtomicCmpExch { dest: Operand, uav: Operand, address: Operand, compare: Operand, src: Operand },
+    ImmAtomicIMax { dest: Operand, uav: Operand, address: Operand, src: Operand },
+    ImmAtomicIMin { dest: Operand, uav: Operand, address: Operand, src: Operand },
+    ImmAtomicUMax { dest: Operand, uav: Operand, address: Operand, src: Operand },
+    ImmAtomicUMin { dest: Operand, uav: Operand, address: Operand, src: Operand },
+
+    Emit,
+    Cut,
+    EmitThenCut,
+    EmitStream { stream: u32 },
+    CutStream { stream: u32 },
+    EmitThenCutStream { stream: u32 },
+    Sync { flags: SyncFlags },
+    /// Custom data block such as an immediate constant buffer. `data` must be
+    /// an `OperandType::CustomData` operand.
+    CustomData { data: Operand },
     Ret
 }
 
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone)]
 pub enum OpcodeEx {
     UvOffset(u32, u32, u32),
     Dimension(ResourceDimension, u32),
     ResourceReturnType(ResourceReturnType, ResourceReturnType, ResourceReturnType, ResourceReturnType),
 }
 
+impl OpcodeEx {
+    /// Converts a decoded extended opcode token back into its encodable
+    /// form. Empty tokens have no equivalent and yield `None`.
+    pub fn from_extended_opcode(opcode: ExtendedOpcode) -> Option<Self> {
+        match opcode {
+            ExtendedOpcode::Empty => None,
+            ExtendedOpcode::SampleControls(u, v, w) => Some(OpcodeEx::UvOffset(u as u32, v as u32, w as u32)),
+            ExtendedOpcode::ResourceDim(dim, stride) => Some(OpcodeEx::Dimension(dim, stride)),
+            ExtendedOpcode::ResourceReturnType(x, y, z, w) => Some(OpcodeEx::ResourceReturnType(x, y, z, w)),
+        }
+    }
+}
+
 
 
 
 impl Instruction {
     fn get_opcode(&self) -> u32 {
         match self {
-            Instruction::Add { .. } => D3D10_SB_OPCODE_ADD,
-            Instruction::Mul { .. } => D3D10_SB_OPCODE_MUL,
             Instruction::DclGlobalFlags { .. } => D3D10_SB_OPCODE_DCL_GLOBAL_FLAGS,
-            Instruction::DclOutputSiv { .. } => D3D10_SB_OPCODE_DCL_OUTPUT_SIV,
-            Instruction::DclInput { .. } => D3D10_SB_OPCODE_DCL_INPUT,
             Instruction::DclTemps { .. } => D3D10_SB_OPCODE_DCL_TEMPS,
+            Instruction::DclIndexableTemp { .. } => D3D10_SB_OPCODE_DCL_INDEXABLE_TEMP,
+            Instruction::DclInput { .. } => D3D10_SB_OPCODE_DCL_INPUT,
+            Instruction::DclInputSgv { .. } => D3D10_SB_OPCODE_DCL_INPUT_SGV,
+            Instruction::DclInputSiv { .. } => D3D10_SB_OPCODE_DCL_INPUT_SIV,
+            Instruction::DclInputPs { .. } => D3D10_SB_OPCODE_DCL_INPUT_PS,
+            Instruction::DclInputPsSgv { .. } => D3D10_SB_OPCODE_DCL_INPUT_PS_SGV,
+            Instruction::DclInputPsSiv { .. } => D3D10_SB_OPCODE_DCL_INPUT_PS_SIV,
+            Instruction::DclOutput { .. } => D3D10_SB_OPCODE_DCL_OUTPUT,
+            Instruction::DclOutputSgv { .. } => D3D10_SB_OPCODE_DCL_OUTPUT_SGV,
+            Instruction::DclOutputSiv { .. } => D3D10_SB_OPCODE_DCL_OUTPUT_SIV,
+            Instruction::DclIndexRange { .. } => D3D10_SB_OPCODE_DCL_INDEX_RANGE,
+            Instruction::DclConstantBuffer { .. } => D3D10_SB_OPCODE_DCL_CONSTANT_BUFFER,
+            Instruction::DclResource { .. } => D3D10_SB_OPCODE_DCL_RESOURCE,
+            Instruction::DclSampler { .. } => D3D10_SB_OPCODE_DCL_SAMPLER,
+            Instruction::DclGsInputPrimitive { .. } => D3D10_SB_OPCODE_DCL_GS_INPUT_PRIMITIVE,
+            Instruction::DclGsOutputPrimitiveTopology { .. } => D3D10_SB_OPCODE_DCL_GS_OUTPUT_PRIMITIVE_TOPOLOGY,
+            Instruction::DclMaxOutputVertexCount { .. } => D3D10_SB_OPCODE_DCL_MAX_OUTPUT_VERTEX_COUNT,
+            Instruction::DclGsInstanceCount { .. } => D3D11_SB_OPCODE_DCL_GS_INSTANCE_COUNT,
+            Instruction::DclStream { .. } => D3D11_SB_OPCODE_DCL_STREAM,
+            Instruction::DclThreadGroup { .. } => D3D11_SB_OPCODE_DCL_THREAD_GROUP,
+            Instruction::DclTgsmRaw { .. } => D3D11_SB_OPCODE_DCL_THREAD_GROUP_SHARED_MEMORY_RAW,
+            Instruction::DclTgsmStructured { .. } => D3D11_SB_OPCODE_DCL_THREAD_GROUP_SHARED_MEMORY_STRUCTURED,
+            Instruction::DclUavTyped { .. } => D3D11_SB_OPCODE_DCL_UNORDERED_ACCESS_VIEW_TYPED,
+            Instruction::DclUavRaw { .. } => D3D11_SB_OPCODE_DCL_UNORDERED_ACCESS_VIEW_RAW,
+            Instruction::DclUavStructured { .. } => D3D11_SB_OPCODE_DCL_UNORDERED_ACCESS_VIEW_STRUCTURED,
+            Instruction::DclResourceRaw { .. } => D3D11_SB_OPCODE_DCL_RESOURCE_RAW,
+            Instruction::DclResourceStructured { .. } => D3D11_SB_OPCODE_DCL_RESOURCE_STRUCTURED,
+            Instruction::DclInputControlPointCount { .. } => D3D11_SB_OPCODE_DCL_INPUT_CONTROL_POINT_COUNT,
+            Instruction::DclOutputControlPointCount { .. } => D3D11_SB_OPCODE_DCL_OUTPUT_CONTROL_POINT_COUNT,
+            Instruction::DclTessDomain { .. } => D3D11_SB_OPCODE_DCL_TESS_DOMAIN,
+            Instruction::DclTessPartitioning { .. } => D3D11_SB_OPCODE_DCL_TESS_PARTITIONING,
+            Instruction::DclTessOutputPrimitive { .. } => D3D11_SB_OPCODE_DCL_TESS_OUTPUT_PRIMITIVE,
+            Instruction::DclHsMaxTessFactor { .. } => D3D11_SB_OPCODE_DCL_HS_MAX_TESSFACTOR,
+            Instruction::DclHsForkPhaseInstanceCount { .. } => D3D11_SB_OPCODE_DCL_HS_FORK_PHASE_INSTANCE_COUNT,
+            Instruction::DclHsJoinPhaseInstanceCount { .. } => D3D11_SB_OPCODE_DCL_HS_JOIN_PHASE_INSTANCE_COUNT,
+            Instruction::DclFunctionBody { .. } => D3D11_SB_OPCODE_DCL_FUNCTION_BODY,
+            Instruction::DclFunctionTable { .. } => D3D11_SB_OPCODE_DCL_FUNCTION_TABLE,
+            Instruction::DclInterface { .. } => D3D11_SB_OPCODE_DCL_INTERFACE,
+            Instruction::HsDecls => D3D11_SB_OPCODE_HS_DECLS,
+            Instruction::HsControlPointPhase => D3D11_SB_OPCODE_HS_CONTROL_POINT_PHASE,
+            Instruction::HsForkPhase => D3D11_SB_OPCODE_HS_FORK_PHASE,
+            Instruction::HsJoinPhase => D3D11_SB_OPCODE_HS_JOIN_PHASE,
+            Instruction::Add { .. } => D3D10_SB_OPCODE_ADD,
+            Instruction::Mul { .. } => D3D10_SB_OPCODE_MUL,
+            Instruction::Div { .. } => D3D10_SB_OPCODE_DIV,
+            Instruction::Mad { .. } => D3D10_SB_OPCODE_MAD,
+            Instruction::Min { .. } => D3D10_SB_OPCODE_MIN,
+            Instruction::Max { .. } => D3D10_SB_OPCODE_MAX,
+            Instruction::Dp2 { .. } => D3D10_SB_OPCODE_DP2,
+            Instruction::Dp3 { .. } => D3D10_SB_OPCODE_DP3,
+            Instruction::Dp4 { .. } => D3D10_SB_OPCODE_DP4,
+            Instruction::Mov { .. } => D3D10_SB_OPCODE_MOV,
+            Instruction::MovC { .. } => D3D10_SB_OPCODE_MOVC,
+            Instruction::SwapC { .. } => D3D11_SB_OPCODE_SWAPC,
+            Instruction::Exp { .. } => D3D10_SB_OPCODE_EXP,
+            Instruction::Log { .. } => D3D10_SB_OPCODE_LOG,
+            Instruction::Frc { .. } => D3D10_SB_OPCODE_FRC,
+            Instruction::Rcp { .. } => D3D11_SB_OPCODE_RCP,
+            Instruction::Rsq { .. } => D3D10_SB_OPCODE_RSQ,
+            Instruction::Sqrt { .. } => D3D10_SB_OPCODE_SQRT,
+            Instruction::SinCos { .. } => D3D10_SB_OPCODE_SINCOS,
+            Instruction::RoundNe { .. } => D3D10_SB_OPCODE_ROUND_NE,
+            Instruction::RoundNi { .. } => D3D10_SB_OPCODE_ROUND_NI,
+            Instruction::RoundPi { .. } => D3D10_SB_OPCODE_ROUND_PI,
+            Instruction::RoundZ { .. } => D3D10_SB_OPCODE_ROUND_Z,
+            Instruction::DerivRtx { .. } => D3D10_SB_OPCODE_DERIV_RTX,
+            Instruction::DerivRty { .. } => D3D10_SB_OPCODE_DERIV_RTY,
+            Instruction::DerivRtxCoarse { .. } => D3D11_SB_OPCODE_DERIV_RTX_COARSE,
+            Instruction::DerivRtxFine { .. } => D3D11_SB_OPCODE_DERIV_RTX_FINE,
+            Instruction::DerivRtyCoarse { .. } => D3D11_SB_OPCODE_DERIV_RTY_COARSE,
+            Instruction::DerivRtyFine { .. } => D3D11_SB_OPCODE_DERIV_RTY_FINE,
+            Instruction::Eq { .. } => D3D10_SB_OPCODE_EQ,
+            Instruction::Ge { .. } => D3D10_SB_OPCODE_GE,
+            Instruction::Lt { .. } => D3D10_SB_OPCODE_LT,
+            Instruction::Ne { .. } => D3D10_SB_OPCODE_NE,
+            Instruction::IAdd { .. } => D3D10_SB_OPCODE_IADD,
+            Instruction::IMad { .. } => D3D10_SB_OPCODE_IMAD,
+            Instruction::IMul { .. } => D3D10_SB_OPCODE_IMUL,
+            Instruction::IMax { .. } => D3D10_SB_OPCODE_IMAX,
+            Instruction::IMin { .. } => D3D10_SB_OPCODE_IMIN,
+            Instruction::INeg { .. } => D3D10_SB_OPCODE_INEG,
+            Instruction::IShl { .. } => D3D10_SB_OPCODE_ISHL,
+            Instruction::IShr { .. } => D3D10_SB_OPCODE_ISHR,
+            Instruction::IEq { .. } => D3D10_SB_OPCODE_IEQ,
+            Instruction::IGe { .. } => D3D10_SB_OPCODE_IGE,
+            Instruction::ILt { .. } => D3D10_SB_OPCODE_ILT,
+            Instruction::INe { .. } => D3D10_SB_OPCODE_INE,
+            Instruction::UDiv { .. } => D3D10_SB_OPCODE_UDIV,
+            Instruction::UMad { .. } => D3D10_SB_OPCODE_UMAD,
+            Instruction::UMul { .. } => D3D10_SB_OPCODE_UMUL,
+            Instruction::UMax { .. } => D3D10_SB_OPCODE_UMAX,
+            Instruction::UMin { .. } => D3D10_SB_OPCODE_UMIN,
+            Instruction::UShr { .. } => D3D10_SB_OPCODE_USHR,
+            Instruction::UGe { .. } => D3D10_SB_OPCODE_UGE,
+            Instruction::ULt { .. } => D3D10_SB_OPCODE_ULT,
+            Instruction::UAddC { .. } => D3D11_SB_OPCODE_UADDC,
+            Instruction::USubB { .. } => D3D11_SB_OPCODE_USUBB,
+            Instruction::Msad { .. } => D3D11_1_SB_OPCODE_MSAD,
+            Instruction::And { .. } => D3D10_SB_OPCODE_AND,
+            Instruction::Or { .. } => D3D10_SB_OPCODE_OR,
+            Instruction::Xor { .. } => D3D10_SB_OPCODE_XOR,
+            Instruction::Not { .. } => D3D10_SB_OPCODE_NOT,
+            Instruction::CountBits { .. } => D3D11_SB_OPCODE_COUNTBITS,
+            Instruction::FirstBitHi { .. } => D3D11_SB_OPCODE_FIRSTBIT_HI,
+            Instruction::FirstBitLo { .. } => D3D11_SB_OPCODE_FIRSTBIT_LO,
+            Instruction::FirstBitShi { .. } => D3D11_SB_OPCODE_FIRSTBIT_SHI,
+            Instruction::UBfe { .. } => D3D11_SB_OPCODE_UBFE,
+            Instruction::IBfe { .. } => D3D11_SB_OPCODE_IBFE,
+            Instruction::Bfi { .. } => D3D11_SB_OPCODE_BFI,
+            Instruction::BfRev { .. } => D3D11_SB_OPCODE_BFREV,
+            Instruction::Ftoi { .. } => D3D10_SB_OPCODE_FTOI,
+            Instruction::Ftou { .. } => D3D10_SB_OPCODE_FTOU,
+            Instruction::Itof { .. } => D3D10_SB_OPCODE_ITOF,
+            Instruction::Utof { .. } => D3D10_SB_OPCODE_UTOF,
+            Instruction::F32tof16 { .. } => D3D11_SB_OPCODE_F32TOF16,
+            Instruction::F16tof32 { .. } => D3D11_SB_OPCODE_F16TOF32,
+            Instruction::DAdd { .. } => D3D11_SB_OPCODE_DADD,
+            Instruction::DMul { .. } => D3D11_SB_OPCODE_DMUL,
+            Instruction::DMax { .. } => D3D11_SB_OPCODE_DMAX,
+            Instruction::DMin { .. } => D3D11_SB_OPCODE_DMIN,
+            Instruction::DDiv { .. } => D3D11_1_SB_OPCODE_DDIV,
+            Instruction::DFma { .. } => D3D11_1_SB_OPCODE_DFMA,
+            Instruction::DRcp { .. } => D3D11_1_SB_OPCODE_DRCP,
+            Instruction::DMov { .. } => D3D11_SB_OPCODE_DMOV,
+            Instruction::DMovC { .. } => D3D11_SB_OPCODE_DMOVC,
+            Instruction::DEq { .. } => D3D11_SB_OPCODE_DEQ,
+            Instruction::DGe { .. } => D3D11_SB_OPCODE_DGE,
+            Instruction::DLt { .. } => D3D11_SB_OPCODE_DLT,
+            Instruction::DNe { .. } => D3D11_SB_OPCODE_DNE,
+            Instruction::Dtof { .. } => D3D11_SB_OPCODE_DTOF,
+            Instruction::Ftod { .. } => D3D11_SB_OPCODE_FTOD,
+            Instruction::Dtoi { .. } => D3D11_1_SB_OPCODE_DTOI,
+            Instruction::Dtou { .. } => D3D11_1_SB_OPCODE_DTOU,
+            Instruction::Itod { .. } => D3D11_1_SB_OPCODE_ITOD,
+            Instruction::Utod { .. } => D3D11_1_SB_OPCODE_UTOD,
+            Instruction::If { .. } => D3D10_SB_OPCODE_IF,
+            Instruction::Else => D3D10_SB_OPCODE_ELSE,
+            Instruction::EndIf => D3D10_SB_OPCODE_ENDIF,
+            Instruction::Loop => D3D10_SB_OPCODE_LOOP,
+            Instruction::EndLoop => D3D10_SB_OPCODE_ENDLOOP,
+            Instruction::Break => D3D10_SB_OPCODE_BREAK,
+            Instruction::BreakC { .. } => D3D10_SB_OPCODE_BREAKC,
+            Instruction::Continue => D3D10_SB_OPCODE_CONTINUE,
+            Instruction::ContinueC { .. } => D3D10_SB_OPCODE_CONTINUEC,
+            Instruction::Switch { .. } => D3D10_SB_OPCODE_SWITCH,
+            Instruction::Case { .. } => D3D10_SB_OPCODE_CASE,
+            Instruction::Default => D3D10_SB_OPCODE_DEFAULT,
+            Instruction::EndSwitch => D3D10_SB_OPCODE_ENDSWITCH,
+            Instruction::Label { .. } => D3D10_SB_OPCODE_LABEL,
+            Instruction::Call { .. } => D3D10_SB_OPCODE_CALL,
+            Instruction::CallC { .. } => D3D10_SB_OPCODE_CALLC,
+            Instruction::InterfaceCall { .. } => D3D11_SB_OPCODE_INTERFACE_CALL,
+            Instruction::Discard { .. } => D3D10_SB_OPCODE_DISCARD,
+            Instruction::RetC { .. } => D3D10_SB_OPCODE_RETC,
+            Instruction::Nop => D3D10_SB_OPCODE_NOP,
+            Instruction::Abort => D3D11_SB_OPCODE_ABORT,
+            Instruction::DebugBreak => D3D11_SB_OPCODE_DEBUG_BREAK,
+            Instruction::Sample { .. } => D3D10_SB_OPCODE_SAMPLE,
+            Instruction::SampleB { .. } => D3D10_SB_OPCODE_SAMPLE_B,
+            Instruction::SampleL { .. } => D3D10_SB_OPCODE_SAMPLE_L,
+            Instruction::SampleD { .. } => D3D10_SB_OPCODE_SAMPLE_D,
+            Instruction::SampleC { .. } => D3D10_SB_OPCODE_SAMPLE_C,
+            Instruction::SampleCLz { .. } => D3D10_SB_OPCODE_SAMPLE_C_LZ,
+            Instruction::Gather4 { .. } => D3D10_1_SB_OPCODE_GATHER4,
+            Instruction::Gather4C { .. } => D3D11_SB_OPCODE_GATHER4_C,
+            Instruction::Gather4Po { .. } => D3D11_SB_OPCODE_GATHER4_PO,
+            Instruction::Gather4PoC { .. } => D3D11_SB_OPCODE_GATHER4_PO_C,
+            Instruction::Lod { .. } => D3D10_1_SB_OPCODE_LOD,
+            Instruction::Ld { .. } => D3D10_SB_OPCODE_LD,
+            Instruction::LdMs { .. } => D3D10_SB_OPCODE_LD_MS,
+            Instruction::ResInfo { .. } => D3D10_SB_OPCODE_RESINFO,
+            Instruction::SampleInfo { .. } => D3D10_1_SB_OPCODE_SAMPLE_INFO,
+            Instruction::SamplePos { .. } => D3D10_1_SB_OPCODE_SAMPLE_POS,
+            Instruction::BufInfo { .. } => D3D11_SB_OPCODE_BUFINFO,
+            Instruction::EvalSnapped { .. } => D3D11_SB_OPCODE_EVAL_SNAPPED,
+            Instruction::EvalSampleIndex { .. } => D3D11_SB_OPCODE_EVAL_SAMPLE_INDEX,
+            Instruction::EvalCentroid { .. } => D3D11_SB_OPCODE_EVAL_CENTROID,
+            Instruction::LdUavTyped { .. } => D3D11_SB_OPCODE_LD_UAV_TYPED,
+            Instruction::StoreUavTyped { .. } => D3D11_SB_OPCODE_STORE_UAV_TYPED,
+            Instruction::LdRaw { .. } => D3D11_SB_OPCODE_LD_RAW,
+            Instruction::StoreRaw { .. } => D3D11_SB_OPCODE_STORE_RAW,
+            Instruction::LdStructured { .. } => D3D11_SB_OPCODE_LD_STRUCTURED,
+            Instruction::StoreStructured { .. } => D3D11_SB_OPCODE_STORE_STRUCTURED,
+            Instruction::AtomicAnd { .. } => D3D11_SB_OPCODE_ATOMIC_AND,
+            Instruction::AtomicOr { .. } => D3D11_SB_OPCODE_ATOMIC_OR,
+            Instruction::AtomicXor { .. } => D3D11_SB_OPCODE_ATOMIC_XOR,
+            Instruction::AtomicCmpStore { .. } => D3D11_SB_OPCODE_ATOMIC_CMP_STORE,
+            Instruction::AtomicIAdd { .. } => D3D11_SB_OPCODE_ATOMIC_IADD,
+            Instruction::AtomicIMax { .. } => D3D11_SB_OPCODE_ATOMIC_IMAX,
+            Instruction::AtomicIMin { .. } => D3D11_SB_OPCODE_ATOMIC_IMIN,
+            Instruction::AtomicUMax { .. } => D3D11_SB_OPCODE_ATOMIC_UMAX,
+            Instruction::AtomicUMin { .. } => D3D11_SB_OPCODE_ATOMIC_UMIN,
+            Instruction::ImmAtomicAlloc { .. } => D3D11_SB_OPCODE_IMM_ATOMIC_ALLOC,
+            Instruction::ImmAtomicConsume { .. } => D3D11_SB_OPCODE_IMM_ATOMIC_CONSUME,
+            Instruction::ImmAtomicIAdd { .. } => D3D11_SB_OPCODE_IMM_ATOMIC_IADD,
+            Instruction::ImmAtomicAnd { .. } => D3D11_SB_OPCODE_IMM_ATOMIC_AND,
+            Instruction::ImmAtomicOr { .. } => D3D11_SB_OPCODE_IMM_ATOMIC_OR,
+            Instruction::ImmAtomicXor { .. } => D3D11_SB_OPCODE_IMM_ATOMIC_XOR,
+            Instruction::ImmAtomicExch { .. } => D3D11_SB_OPCODE_IMM_ATOMIC_EXCH,
+            Instruction::ImmAtomicCmpExch { .. } => D3D11_SB_OPCODE_IMM_ATOMIC_CMP_EXCH,
+            Instruction::ImmAtomicIMax { .. } => D3D11_SB_OPCODE_IMM_ATOMIC_IMAX,
+            Instruction::ImmAtomicIMin { .. } => D3D11_SB_OPCODE_IMM_ATOMIC_IMIN,
+            Instruction::ImmAtomicUMax { .. } => D3D11_SB_OPCODE_IMM_ATOMIC_UMAX,
+            Instruction::ImmAtomicUMin { .. } => D3D11_SB_OPCODE_IMM_ATOMIC_UMIN,
+            Instruction::Emit => D3D10_SB_OPCODE_EMIT,
+            Instruction::Cut => D3D10_SB_OPCODE_CUT,
+            Instruction::EmitThenCut => D3D10_SB_OPCODE_EMITTHENCUT,
+            Instruction::EmitStream { .. } => D3D11_SB_OPCODE_EMIT_STREAM,
+            Instruction::CutStream { .. } => D3D11_SB_OPCODE_CUT_STREAM,
+            Instruction::EmitThenCutStream { .. } => D3D11_SB_OPCODE_EMITTHENCUT_STREAM,
+            Instruction::Sync { .. } => D3D11_SB_OPCODE_SYNC,
+            Instruction::CustomData { .. } => D3D10_SB_OPCODE_CUSTOMDATA,
             Instruction::Ret => D3D10_SB_OPCODE_RET,
         }
     }
 
-    fn encode_opcode(&self, module: &mut DxbcModule) {
-        let opcode = self.get_opcode();
+    fn is_saturated(&self) -> bool {
+        match self {
+            Instruction::Add { saturated, .. } |
+            Instruction::Mul { saturated, .. } |
+            Instruction::Div { saturated, .. } |
+            Instruction::Mad { saturated, .. } |
+            Instruction::Min { saturated, .. } |
+            Instruction::Max { saturated, .. } |
+            Instruction::Dp2 { saturated, .. } |
+            Instruction::Dp3 { saturated, .. } |
+            Instruction::Dp4 { saturated, .. } |
+            Instruction::Mov { saturated, .. } |
+            Instruction::MovC { saturated, .. } |
+            Instruction::Exp { saturated, .. } |
+            Instruction::Log { saturated, .. } |
+            Instruction::Frc { saturated, .. } |
+            Instruction::Rcp { saturated, .. } |
+            Instruction::Rsq { saturated, .. } |
+            Instruction::Sqrt { saturated, .. } |
+            Instruction::SinCos { saturated, .. } |
+            Instruction::RoundNe { saturated, .. } |
+            Instruction::RoundNi { saturated, .. } |
+            Instruction::RoundPi { saturated, .. } |
+            Instruction::RoundZ { saturated, .. } |
+            Instruction::DerivRtx { saturated, .. } |
+            Instruction::DerivRty { saturated, .. } |
+            Instruction::DerivRtxCoarse { saturated, .. } |
+            Instruction::DerivRtxFine { saturated, .. } |
+            Instruction::DerivRtyCoarse { saturated, .. } |
+            Instruction::DerivRtyFine { saturated, .. } |
+            Instruction::DAdd { saturated, .. } |
+            Instruction::DMul { saturated, .. } |
+            Instruction::DMax { saturated, .. } |
+            Instruction::DMin { saturated, .. } |
+            Instruction::DDiv { saturated, .. } |
+            Instruction::DFma { saturated, .. } |
+            Instruction::DRcp { saturated, .. } |
+            Instruction::DMov { saturated, .. } |
+            Instruction::DMovC { saturated, .. } => *saturated,
+            _ => false,
+        }
+    }
+
+    fn get_test(&self) -> Option<u32> {
+        match self {
+            Instruction::If { test, .. } |
+            Instruction::BreakC { test, .. } |
+            Instruction::ContinueC { test, .. } |
+            Instruction::CallC { test, .. } |
+            Instruction::Discard { test, .. } |
+            Instruction::RetC { test, .. } => Some(*test as u32),
+            _ => None,
+        }
+    }
+
+    fn get_extended(&self) -> &[OpcodeEx] {
+        match self {
+            Instruction::Sample { extended, .. } |
+            Instruction::SampleB { extended, .. } |
+            Instruction::SampleL { extended, .. } |
+            Instruction::SampleD { extended, .. } |
+            Instruction::SampleC { extended, .. } |
+            Instruction::SampleCLz { extended, .. } |
+            Instruction::Gather4 { extended, .. } |
+            Instruction::Gather4C { extended, .. } |
+            Instruction::Gather4Po { extended, .. } |
+            Instruction::Gather4PoC { extended, .. } |
+            Instruction::Lod { extended, .. } |
+            Instruction::Ld { extended, .. } |
+            Instruction::LdMs { extended, .. } |
+            Instruction::LdUavTyped { extended, .. } |
+            Instruction::LdRaw { extended, .. } |
+            Instruction::LdStructured { extended, .. } => extended,
+            _ => &[],
+        }
+    }
 
+    // instruction specific bits of the opcode token
+    fn get_controls(&self) -> u32 {
         match self {
-            Instruction::Add { dest, a, b, saturated } |
-            Instruction::Mul { dest, a, b, saturated } => { module.write_opcode(opcode, 0, None, *saturated, &[]); }
+            Instruction::DclGlobalFlags { flags } => flags.bits() & 0x00fff800,
+            &Instruction::DclConstantBuffer { dynamic, .. } => {
+                let pattern = if dynamic {
+                    D3D10_SB_CONSTANT_BUFFER_DYNAMIC_INDEXED
+                } else {
+                    D3D10_SB_CONSTANT_BUFFER_IMMEDIATE_INDEXED
+                };
+
+                ENCODE_D3D10_SB_D3D10_SB_CONSTANT_BUFFER_ACCESS_PATTERN(pattern)
+            }
+            &Instruction::DclResource { dimension, .. } => ENCODE_D3D10_SB_RESOURCE_DIMENSION(dimension as u32),
+            &Instruction::DclSampler { mode, .. } => ENCODE_D3D10_SB_SAMPLER_MODE(mode as u32),
+            &Instruction::DclGsInputPrimitive { primitive } => ENCODE_D3D10_SB_GS_INPUT_PRIMITIVE(primitive.to_word()),
+            &Instruction::DclGsOutputPrimitiveTopology { topology } => ENCODE_D3D10_SB_GS_OUTPUT_PRIMITIVE_TOPOLOGY(topology as u32),
+            &Instruction::DclUavTyped { dimension, globally_coherent, .. } => {
+                let mut flags = ENCODE_D3D10_SB_RESOURCE_DIMENSION(dimension as u32);
+                if globally_coherent {
+                    flags |= D3D11_SB_GLOBALLY_COHERENT_ACCESS;
+                }
 
-            Instruction::DclGlobalFlags { flags } => {
-                let opcode_pos = module.position();
-                module.write_opcode(opcode, 0, None, false, &[]);
+                flags
+            }
+            &Instruction::DclUavRaw { globally_coherent, .. } |
+            &Instruction::DclUavStructured { globally_coherent, order_preserving_counter: false, .. } => {
+                if globally_coherent {
+                    D3D11_SB_GLOBALLY_COHERENT_ACCESS
+                } else {
+                    0
+                }
+            }
+            &Instruction::DclUavStructured { globally_coherent, order_preserving_counter: true, .. } => {
+                let mut flags = D3D11_SB_UAV_HAS_ORDER_PRESERVING_COUNTER;
+                if globally_coherent {
+                    flags |= D3D11_SB_GLOBALLY_COHERENT_ACCESS;
+                }
 
-                let opcode = module.get_u32(opcode_pos);
-                module.set_u32(opcode_pos, opcode | (flags.bits() & 0x00fff800));
+                flags
+            }
+            &Instruction::DclInputPs { interpolation, .. } |
+            &Instruction::DclInputPsSgv { interpolation, .. } |
+            &Instruction::DclInputPsSiv { interpolation, .. } => ENCODE_D3D10_SB_INPUT_INTERPOLATION_MODE(interpolation as u32),
+            &Instruction::DclInputControlPointCount { count } => ENCODE_D3D11_SB_INPUT_CONTROL_POINT_COUNT(count),
+            &Instruction::DclOutputControlPointCount { count } => ENCODE_D3D11_SB_OUTPUT_CONTROL_POINT_COUNT(count),
+            &Instruction::DclTessDomain { domain } => ENCODE_D3D11_SB_TESS_DOMAIN(domain as u32),
+            &Instruction::DclTessPartitioning { partitioning } => ENCODE_D3D11_SB_TESS_PARTITIONING(partitioning as u32),
+            &Instruction::DclTessOutputPrimitive { primitive } => ENCODE_D3D11_SB_TESS_OUTPUT_PRIMITIVE(primitive as u32),
+            &Instruction::DclInterface { dynamically_indexed, .. } => ENCODE_D3D11_SB_INTERFACE_INDEXED_BIT(dynamically_indexed as u32),
+            &Instruction::ResInfo { return_type, .. } => ENCODE_D3D10_SB_RESINFO_INSTRUCTION_RETURN_TYPE(return_type as u32),
+            &Instruction::SampleInfo { uint, .. } => {
+                let return_type = if uint {
+                    D3D10_SB_INSTRUCTION_RETURN_UINT
+                } else {
+                    D3D10_SB_INSTRUCTION_RETURN_FLOAT
+                };
+
+                ENCODE_D3D10_SB_INSTRUCTION_RETURN_TYPE(return_type)
             }
-            Instruction::DclOutputSiv { .. } |
-            Instruction::DclInput { .. } |
-            Instruction::DclTemps { .. } |
-            Instruction::Ret => { module.write_opcode(opcode, 0, None, false, &[]); }
-            _ => {}
+            &Instruction::Sync { flags } => ENCODE_D3D11_SB_SYNC_FLAGS(flags.bits()),
+            _ => 0,
         }
+    }
 
+    fn encode_opcode(&self, module: &mut DxbcModule) {
+        let opcode_pos = module.position();
+        module.write_opcode(self.get_opcode(), 0, self.get_test(), self.is_saturated(), self.get_extended());
+
+        let controls = self.get_controls();
+        if controls != 0 {
+            let opcode = module.get_u32(opcode_pos);
+            module.set_u32(opcode_pos, opcode | controls);
+        }
     }
 
     fn encode(&self, module: &mut DxbcModule) {
+        // custom data stores its length in a separate word
+        if let Instruction::CustomData { data } = self {
+            data.encode(module);
+            return;
+        }
+
         let start = module.position();
 
         self.encode_opcode(module);
 
         match self {
             Instruction::Add { dest, a, b, .. } |
-            Instruction::Mul { dest, a, b, .. } => {
+            Instruction::Mul { dest, a, b, .. } |
+            Instruction::Div { dest, a, b, .. } |
+            Instruction::Min { dest, a, b, .. } |
+            Instruction::Max { dest, a, b, .. } |
+            Instruction::Dp2 { dest, a, b, .. } |
+            Instruction::Dp3 { dest, a, b, .. } |
+            Instruction::Dp4 { dest, a, b, .. } |
+            Instruction::Eq { dest, a, b } |
+            Instruction::Ge { dest, a, b } |
+            Instruction::Lt { dest, a, b } |
+            Instruction::Ne { dest, a, b } |
+            Instruction::IAdd { dest, a, b } |
+            Instruction::IMax { dest, a, b } |
+            Instruction::IMin { dest, a, b } |
+            Instruction::IShl { dest, a, b } |
+            Instruction::IShr { dest, a, b } |
+            Instruction::IEq { dest, a, b } |
+            Instruction::IGe { dest, a, b } |
+            Instruction::ILt { dest, a, b } |
+            Instruction::INe { dest, a, b } |
+            Instruction::UMax { dest, a, b } |
+            Instruction::UMin { dest, a, b } |
+            Instruction::UShr { dest, a, b } |
+            Instruction::UGe { dest, a, b } |
+            Instruction::ULt { dest, a, b } |
+            Instruction::And { dest, a, b } |
+            Instruction::Or { dest, a, b } |
+            Instruction::Xor { dest, a, b } |
+            Instruction::DAdd { dest, a, b, .. } |
+            Instruction::DMul { dest, a, b, .. } |
+            Instruction::DMax { dest, a, b, .. } |
+            Instruction::DMin { dest, a, b, .. } |
+            Instruction::DDiv { dest, a, b, .. } |
+            Instruction::DEq { dest, a, b } |
+            Instruction::DGe { dest, a, b } |
+            Instruction::DLt { dest, a, b } |
+            Instruction::DNe { dest, a, b } => {
+                dest.encode(module);
+                a.encode(module);
+                b.encode(module);
+            }
+            Instruction::Mad { dest, a, b, c, .. } |
+            Instruction::IMad { dest, a, b, c } |
+            Instruction::UMad { dest, a, b, c } |
+            Instruction::Msad { dest, a, b, c } |
+            Instruction::DFma { dest, a, b, c, .. } => {
                 dest.encode(module);
                 a.encode(module);
                 b.encode(module);
+                c.encode(module);
+            }
+            Instruction::Mov { dest, src, .. } |
+            Instruction::Exp { dest, src, .. } |
+            Instruction::Log { dest, src, .. } |
+            Instruction::Frc { dest, src, .. } |
+            Instruction::Rcp { dest, src, .. } |
+            Instruction::Rsq { dest, src, .. } |
+            Instruction::Sqrt { dest, src, .. } |
+            Instruction::RoundNe { dest, src, .. } |
+            Instruction::RoundNi { dest, src, .. } |
+            Instruction::RoundPi { dest, src, .. } |
+            Instruction::RoundZ { dest, src, .. } |
+            Instruction::DerivRtx { dest, src, .. } |
+            Instruction::DerivRty { dest, src, .. } |
+            Instruction::DerivRtxCoarse { dest, src, .. } |
+            Instruction::DerivRtxFine { dest, src, .. } |
+            Instruction::DerivRtyCoarse { dest, src, .. } |
+            Instruction::DerivRtyFine { dest, src, .. } |
+            Instruction::INeg { dest, src } |
+            Instruction::Not { dest, src } |
+            Instruction::CountBits { dest, src } |
+            Instruction::FirstBitHi { dest, src } |
+            Instruction::FirstBitLo { dest, src } |
+            Instruction::FirstBitShi { dest, src } |
+            Instruction::BfRev { dest, src } |
+            Instruction::Ftoi { dest, src } |
+            Instruction::Ftou { dest, src } |
+            Instruction::Itof { dest, src } |
+            Instruction::Utof { dest, src } |
+            Instruction::F32tof16 { dest, src } |
+            Instruction::F16tof32 { dest, src } |
+            Instruction::DRcp { dest, src, .. } |
+            Instruction::DMov { dest, src, .. } |
+            Instruction::Dtof { dest, src } |
+            Instruction::Ftod { dest, src } |
+            Instruction::Dtoi { dest, src } |
+            Instruction::Dtou { dest, src } |
+            Instruction::Itod { dest, src } |
+            Instruction::Utod { dest, src } |
+            Instruction::EvalCentroid { dest, src } => {
+                dest.encode(module);
+                src.encode(module);
+            }
+            Instruction::MovC { dest, cond, a, b, .. } |
+            Instruction::DMovC { dest, cond, a, b, .. } => {
+                dest.encode(module);
+                cond.encode(module);
+                a.encode(module);
+                b.encode(module);
+            }
+            Instruction::SwapC { dest_a, dest_b, cond, a, b } => {
+                dest_a.encode(module);
+                dest_b.encode(module);
+                cond.encode(module);
+                a.encode(module);
+                b.encode(module);
+            }
+            Instruction::SinCos { dest_sin, dest_cos, src, .. } => {
+                dest_sin.encode(module);
+                dest_cos.encode(module);
+                src.encode(module);
+            }
+            Instruction::IMul { dest_hi, dest_lo, a, b } |
+            Instruction::UMul { dest_hi, dest_lo, a, b } => {
+                dest_hi.encode(module);
+                dest_lo.encode(module);
+                a.encode(module);
+                b.encode(module);
+            }
+            Instruction::UDiv { dest_quot, dest_rem, a, b } => {
+                dest_quot.encode(module);
+                dest_rem.encode(module);
+                a.encode(module);
+                b.encode(module);
+            }
+            Instruction::UAddC { dest, dest_carry, a, b } => {
+                dest.encode(module);
+                dest_carry.encode(module);
+                a.encode(module);
+                b.encode(module);
+            }
+            Instruction::USubB { dest, dest_borrow, a, b } => {
+                dest.encode(module);
+                dest_borrow.encode(module);
+                a.encode(module);
+                b.encode(module);
+            }
+            Instruction::UBfe { dest, width, offset, src } |
+            Instruction::IBfe { dest, width, offset, src } => {
+                dest.encode(module);
+                width.encode(module);
+                offset.encode(module);
+                src.encode(module);
+            }
+            Instruction::Bfi { dest, width, offset, insert, src } => {
+                dest.encode(module);
+                width.encode(module);
+                offset.encode(module);
+                insert.encode(module);
+                src.encode(module);
+            }
+            Instruction::If { cond, .. } |
+            Instruction::BreakC { cond, .. } |
+            Instruction::ContinueC { cond, .. } |
+            Instruction::Discard { cond, .. } |
+            Instruction::RetC { cond, .. } => {
+                cond.encode(module);
+            }
+            Instruction::Switch { selector } => {
+                selector.encode(module);
+            }
+            Instruction::Sample { dest, address, resource, sampler, .. } |
+            Instruction::Gather4 { dest, address, resource, sampler, .. } |
+            Instruction::Lod { dest, address, resource, sampler, .. } => {
+                dest.encode(module);
+                address.encode(module);
+                resource.encode(module);
+                sampler.encode(module);
+            }
+            Instruction::SampleB { dest, address, resource, sampler, bias, .. } => {
+                dest.encode(module);
+                address.encode(module);
+                resource.encode(module);
+                sampler.encode(module);
+                bias.encode(module);
+            }
+            Instruction::SampleL { dest, address, resource, sampler, lod, .. } => {
+                dest.encode(module);
+                address.encode(module);
+                resource.encode(module);
+                sampler.encode(module);
+                lod.encode(module);
+            }
+            Instruction::SampleD { dest, address, resource, sampler, ddx, ddy, .. } => {
+                dest.encode(module);
+                address.encode(module);
+                resource.encode(module);
+                sampler.encode(module);
+                ddx.encode(module);
+                ddy.encode(module);
+            }
+            Instruction::SampleC { dest, address, resource, sampler, reference, .. } |
+            Instruction::SampleCLz { dest, address, resource, sampler, reference, .. } |
+            Instruction::Gather4C { dest, address, resource, sampler, reference, .. } => {
+                dest.encode(module);
+                address.encode(module);
+                resource.encode(module);
+                sampler.encode(module);
+                reference.encode(module);
+            }
+            Instruction::Gather4Po { dest, address, offset, resource, sampler, .. } => {
+                dest.encode(module);
+                address.encode(module);
+                offset.encode(module);
+                resource.encode(module);
+                sampler.encode(module);
+            }
+            Instruction::Gather4PoC { dest, address, offset, resource, sampler, reference, .. } => {
+                dest.encode(module);
+                address.encode(module);
+                offset.encode(module);
+                resource.encode(module);
+                sampler.encode(module);
+                reference.encode(module);
+            }
+            Instruction::Ld { dest, address, resource, .. } => {
+                dest.encode(module);
+                address.encode(module);
+                resource.encode(module);
+            }
+            Instruction::LdMs { dest, address, resource, sample_index, .. } => {
+                dest.encode(module);
+                address.encode(module);
+                resource.encode(module);
+                sample_index.encode(module);
+            }
+            Instruction::ResInfo { dest, mip_level, resource, .. } => {
+                dest.encode(module);
+                mip_level.encode(module);
+                resource.encode(module);
+            }
+            Instruction::SampleInfo { dest, resource, .. } |
+            Instruction::BufInfo { dest, resource } => {
+                dest.encode(module);
+                resource.encode(module);
+            }
+            Instruction::SamplePos { dest, resource, sample_index } => {
+                dest.encode(module);
+                resource.encode(module);
+                sample_index.encode(module);
+            }
+            Instruction::EvalSnapped { dest, src, offset } => {
+                dest.encode(module);
+                src.encode(module);
+                offset.encode(module);
+            }
+            Instruction::EvalSampleIndex { dest, src, sample_index } => {
+                dest.encode(module);
+                src.encode(module);
+                sample_index.encode(module);
+            }
+            Instruction::LdUavTyped { dest, address, uav, .. } => {
+                dest.encode(module);
+                address.encode(module);
+                uav.encode(module);
+            }
+            Instruction::StoreUavTyped { uav, address, src } => {
+                uav.encode(module);
+                address.encode(module);
+                src.encode(module);
+            }
+            Instruction::LdRaw { dest, offset, resource, .. } => {
+                dest.encode(module);
+                offset.encode(module);
+                resource.encode(module);
+            }
+            Instruction::StoreRaw { dest, offset, src } => {
+                dest.encode(module);
+                offset.encode(module);
+                src.encode(module);
+            }
+            Instruction::LdStructured { dest, index, offset, resource, .. } => {
+                dest.encode(module);
+                index.encode(module);
+                offset.encode(module);
+                resource.encode(module);
+            }
+            Instruction::StoreStructured { dest, index, offset, src } => {
+                dest.encode(module);
+                index.encode(module);
+                offset.encode(module);
+                src.encode(module);
             }
-            &Instruction::DclTemps { count: val } => {
+            Instruction::AtomicAnd { dest, address, src } |
+            Instruction::AtomicOr { dest, address, src } |
+            Instruction::AtomicXor { dest, address, src } |
+            Instruction::AtomicIAdd { dest, address, src } |
+            Instruction::AtomicIMax { dest, address, src } |
+            Instruction::AtomicIMin { dest, address, src } |
+            Instruction::AtomicUMax { dest, address, src } |
+            Instruction::AtomicUMin { dest, address, src } => {
+                dest.encode(module);
+                address.encode(module);
+                src.encode(module);
+            }
+            Instruction::AtomicCmpStore { dest, address, compare, src } => {
+                dest.encode(module);
+                address.encode(module);
+                compare.encode(module);
+                src.encode(module);
+            }
+            Instruction::ImmAtomicAlloc { dest, uav } |
+            Instruction::ImmAtomicConsume { dest, uav } => {
+                dest.encode(module);
+                uav.encode(module);
+            }
+            Instruction::ImmAtomicIAdd { dest, uav, address, src } |
+            Instruction::ImmAtomicAnd { dest, uav, address, src } |
+            Instruction::ImmAtomicOr { dest, uav, address, src } |
+            Instruction::ImmAtomicXor { dest, uav, address, src } |
+            Instruction::ImmAtomicExch { dest, uav, address, src } |
+            Instruction::ImmAtomicIMax { dest, uav, address, src } |
+            Instruction::ImmAtomicIMin { dest, uav, address, src } |
+            Instruction::ImmAtomicUMax { dest, uav, address, src } |
+            Instruction::ImmAtomicUMin { dest, uav, address, src } => {
+                dest.encode(module);
+                uav.encode(module);
+                address.encode(module);
+                src.encode(module);
+            }
+            Instruction::ImmAtomicCmpExch { dest, uav, address, compare, src } => {
+                dest.encode(module);
+                uav.encode(module);
+                address.encode(module);
+                compare.encode(module);
+                src.encode(module);
+            }
+            &Instruction::Case { value } => {
+                Operand::imm32(value).encode(module);
+            }
+            &Instruction::Label { label } |
+            &Instruction::Call { label } => {
+                module.write_operand(D3D10_SB_OPERAND_TYPE_LABEL, Modifier::None, NumComponent::D0, &[Index::Imm32(label)]);
+            }
+            &Instruction::CallC { ref cond, label, .. } => {
+                cond.encode(module);
+                module.write_operand(D3D10_SB_OPERAND_TYPE_LABEL, Modifier::None, NumComponent::D0, &[Index::Imm32(label)]);
+            }
+            &Instruction::InterfaceCall { function, ref interface } => {
+                module.write_u32(function);
+                interface.encode(module);
+            }
+            &Instruction::DclTemps { count: val } |
+            &Instruction::DclMaxOutputVertexCount { count: val } |
+            &Instruction::DclGsInstanceCount { count: val } |
+            &Instruction::DclHsForkPhaseInstanceCount { count: val } |
+            &Instruction::DclHsJoinPhaseInstanceCount { count: val } |
+            &Instruction::DclFunctionBody { body: val } => {
                 module.write_u32(val)
             }
+            &Instruction::DclThreadGroup { x, y, z } => {
+                module.write_u32(x);
+                module.write_u32(y);
+                module.write_u32(z);
+            }
+            &Instruction::DclTgsmRaw { register, byte_count } => {
+                module.write_operand(D3D11_SB_OPERAND_TYPE_THREAD_GROUP_SHARED_MEMORY, Modifier::None, NumComponent::D0, &[Index::Imm32(register)]);
+                module.write_u32(byte_count);
+            }
+            &Instruction::DclTgsmStructured { register, stride, count } => {
+                module.write_operand(D3D11_SB_OPERAND_TYPE_THREAD_GROUP_SHARED_MEMORY, Modifier::None, NumComponent::D0, &[Index::Imm32(register)]);
+                module.write_u32(stride);
+                module.write_u32(count);
+            }
+            &Instruction::DclUavTyped { binding, return_type, range, .. } => {
+                module.write_binding_operand(D3D11_SB_OPERAND_TYPE_UNORDERED_ACCESS_VIEW, binding, range);
+                module.write_u32(
+                    ENCODE_D3D10_SB_RESOURCE_RETURN_TYPE(return_type as u32, D3D10_SB_4_COMPONENT_X) |
+                    ENCODE_D3D10_SB_RESOURCE_RETURN_TYPE(return_type as u32, D3D10_SB_4_COMPONENT_Y) |
+                    ENCODE_D3D10_SB_RESOURCE_RETURN_TYPE(return_type as u32, D3D10_SB_4_COMPONENT_Z) |
+                    ENCODE_D3D10_SB_RESOURCE_RETURN_TYPE(return_type as u32, D3D10_SB_4_COMPONENT_W)
+                );
+                if let Some(range) = range {
+                    module.write_u32(range.space);
+                }
+            }
+            &Instruction::DclUavRaw { binding, range, .. } => {
+                module.write_binding_operand(D3D11_SB_OPERAND_TYPE_UNORDERED_ACCESS_VIEW, binding, range);
+                if let Some(range) = range {
+                    module.write_u32(range.space);
+                }
+            }
+            &Instruction::DclUavStructured { binding, stride, range, .. } => {
+                module.write_binding_operand(D3D11_SB_OPERAND_TYPE_UNORDERED_ACCESS_VIEW, binding, range);
+                module.write_u32(stride);
+                if let Some(range) = range {
+                    module.write_u32(range.space);
+                }
+            }
+            &Instruction::DclResourceRaw { binding, range } => {
+                module.write_binding_operand(D3D10_SB_OPERAND_TYPE_RESOURCE, binding, range);
+                if let Some(range) = range {
+                    module.write_u32(range.space);
+                }
+            }
+            &Instruction::DclResourceStructured { binding, stride, range } => {
+                module.write_binding_operand(D3D10_SB_OPERAND_TYPE_RESOURCE, binding, range);
+                module.write_u32(stride);
+                if let Some(range) = range {
+                    module.write_u32(range.space);
+                }
+            }
+            &Instruction::DclStream { stream } |
+            &Instruction::EmitStream { stream } |
+            &Instruction::CutStream { stream } |
+            &Instruction::EmitThenCutStream { stream } => {
+                module.write_operand(D3D11_SB_OPERAND_TYPE_STREAM, Modifier::None, NumComponent::D0, &[Index::Imm32(stream)]);
+            }
+            &Instruction::DclInputSgv { ref register, semantic } |
+            &Instruction::DclInputSiv { ref register, semantic } |
+            &Instruction::DclInputPsSgv { ref register, semantic, .. } |
+            &Instruction::DclInputPsSiv { ref register, semantic, .. } |
+            &Instruction::DclOutputSgv { ref register, semantic } |
             &Instruction::DclOutputSiv { ref register, semantic } => {
                 register.encode(module);
-                module.write_u32(semantic as u32)
+                module.write_u32(ENCODE_D3D10_SB_NAME(semantic as u32))
             }
-            &Instruction::DclInput { ref register } => {
+            &Instruction::DclInput { ref register } |
+            &Instruction::DclInputPs { ref register, .. } |
+            &Instruction::DclOutput { ref register } => {
                 register.encode(module);
             }
-            _ => {}
+            &Instruction::DclIndexableTemp { register, count, components } => {
+                module.write_u32(register);
+                module.write_u32(count);
+                module.write_u32(components);
+            }
+            &Instruction::DclIndexRange { ref register, count } => {
+                register.encode(module);
+                module.write_u32(count);
+            }
+            &Instruction::DclHsMaxTessFactor { factor } => {
+                module.write_u32(factor.to_bits());
+            }
+            &Instruction::DclFunctionTable { table, ref bodies } => {
+                module.write_u32(table);
+                module.write_u32(bodies.len() as u32);
+                for &body in bodies {
+                    module.write_u32(body);
+                }
+            }
+            &Instruction::DclInterface { interface, expected_table_length, array_length, ref tables, .. } => {
+                module.write_u32(interface);
+                module.write_u32(expected_table_length);
+                module.write_u32(
+                    ENCODE_D3D11_SB_INTERFACE_TABLE_LENGTH(tables.len() as u32) |
+                    ENCODE_D3D11_SB_INTERFACE_ARRAY_LENGTH(array_length)
+                );
+                for &table in tables {
+                    module.write_u32(table);
+                }
+            }
+            &Instruction::DclConstantBuffer { binding, size, range, .. } => {
+                match range {
+                    Some(range) => {
+                        module.write_binding_operand(D3D10_SB_OPERAND_TYPE_CONSTANT_BUFFER, binding, Some(range));
+                        module.write_u32(size);
+                        module.write_u32(range.space);
+                    }
+                    None => {
+                        module.write_operand(
+                            D3D10_SB_OPERAND_TYPE_CONSTANT_BUFFER,
+                            Modifier::None,
+                            NumComponent::D4(ComponentMode::Swizzle(X, Y, Z, W)),
+                            &[Index::Imm32(binding), Index::Imm32(size)]
+                        );
+                    }
+                }
+            }
+            &Instruction::DclResource { binding, return_type, range, .. } => {
+                module.write_binding_operand(D3D10_SB_OPERAND_TYPE_RESOURCE, binding, range);
+                module.write_u32(
+                    ENCODE_D3D10_SB_RESOURCE_RETURN_TYPE(return_type as u32, D3D10_SB_4_COMPONENT_X) |
+                    ENCODE_D3D10_SB_RESOURCE_RETURN_TYPE(return_type as u32, D3D10_SB_4_COMPONENT_Y) |
+                    ENCODE_D3D10_SB_RESOURCE_RETURN_TYPE(return_type as u32, D3D10_SB_4_COMPONENT_Z) |
+                    ENCODE_D3D10_SB_RESOURCE_RETURN_TYPE(return_type as u32, D3D10_SB_4_COMPONENT_W)
+                );
+                if let Some(range) = range {
+                    module.write_u32(range.space);
+                }
+            }
+            &Instruction::DclSampler { binding, range, .. } => {
+                module.write_binding_operand(D3D10_SB_OPERAND_TYPE_SAMPLER, binding, range);
+                if let Some(range) = range {
+                    module.write_u32(range.space);
+                }
+            }
+            // everything else is encoded in the opcode token
+            Instruction::DclGlobalFlags { .. } |
+            Instruction::DclGsInputPrimitive { .. } |
+            Instruction::DclGsOutputPrimitiveTopology { .. } |
+            Instruction::DclInputControlPointCount { .. } |
+            Instruction::DclOutputControlPointCount { .. } |
+            Instruction::DclTessDomain { .. } |
+            Instruction::DclTessPartitioning { .. } |
+            Instruction::DclTessOutputPrimitive { .. } |
+            Instruction::HsDecls |
+            Instruction::HsControlPointPhase |
+            Instruction::HsForkPhase |
+            Instruction::HsJoinPhase |
+            Instruction::Else |
+            Instruction::EndIf |
+            Instruction::Loop |
+            Instruction::EndLoop |
+            Instruction::Break |
+            Instruction::Continue |
+            Instruction::Default |
+            Instruction::EndSwitch |
+            Instruction::Nop |
+            Instruction::Abort |
+            Instruction::DebugBreak |
+            Instruction::Emit |
+            Instruction::Cut |
+            Instruction::EmitThenCut |
+            Instruction::Sync { .. } |
+            Instruction::Ret => {}
+            Instruction::CustomData { .. } => unreachable!(),
         }
 
         // patch in instruction length
@@ -698,6 +2176,7 @@ impl Instruction {
 pub struct Operand {
     ty: OperandType,
     modifiers: Modifier,
+    min_precision: MinPrecision,
     component_mode: NumComponent
 }
 
@@ -706,10 +2185,17 @@ impl Operand {
         Operand {
             ty,
             modifiers,
+            min_precision: MinPrecision::Default,
             component_mode
         }
     }
 
+    /// Allows the operand to be evaluated at a lower precision. Requires the
+    /// `ENABLE_MINIMUM_PRECISION` global flag.
+    pub fn set_min_precision(&mut self, precision: MinPrecision) {
+        self.min_precision = precision;
+    }
+
     pub fn register(reg: u32, modifiers: Modifier, component_mode: NumComponent) -> Self {
         Self::new(OperandType::Register(reg), modifiers, component_mode)
     }
@@ -722,51 +2208,781 @@ impl Operand {
         Self::new(OperandType::Output(reg), modifiers, component_mode)
     }
 
+    /// Single component immediate, `l(imm)`
+    pub fn imm32(imm: u32) -> Self {
+        Self::new(OperandType::Imm32(imm), Modifier::None, NumComponent::D1)
+    }
+
+    /// Four component immediate, `l(x, y, z, w)`
+    pub fn imm32x4(x: u32, y: u32, z: u32, w: u32) -> Self {
+        Self::new(OperandType::Imm32x4(x, y, z, w), Modifier::None, NumComponent::D4(ComponentMode::Mask(0)))
+    }
+
+    pub fn resource(reg: u32, component_mode: NumComponent) -> Self {
+        Self::new(OperandType::Resource(reg), Modifier::None, component_mode)
+    }
+
+    pub fn sampler(reg: u32) -> Self {
+        Self::new(OperandType::Sampler(reg), Modifier::None, NumComponent::D0)
+    }
+
+    pub fn uav(reg: u32, component_mode: NumComponent) -> Self {
+        Self::new(OperandType::UnorderedAccessView(reg), Modifier::None, component_mode)
+    }
+
+    pub fn indexable_register(reg: u32, address: Address, modifiers: Modifier, component_mode: NumComponent) -> Self {
+        Self::new(OperandType::IndexableRegister(reg, address), modifiers, component_mode)
+    }
+
+    pub fn constant_buffer(reg: u32, address: Address, modifiers: Modifier, component_mode: NumComponent) -> Self {
+        Self::new(OperandType::ConstantBuffer(reg, address), modifiers, component_mode)
+    }
+
+    pub fn immediate_constant_buffer(address: Address, modifiers: Modifier, component_mode: NumComponent) -> Self {
+        Self::new(OperandType::ImmediateConstantBuffer(address), modifiers, component_mode)
+    }
+
+    pub fn tgsm(reg: u32, component_mode: NumComponent) -> Self {
+        Self::new(OperandType::ThreadGroupSharedMemory(reg), Modifier::None, component_mode)
+    }
+
+    pub fn interface(reg: u32, address: Address) -> Self {
+        Self::new(OperandType::Interface(reg, address), Modifier::None, NumComponent::D0)
+    }
+
+    pub fn input_control_point(point: Address, reg: u32, modifiers: Modifier, component_mode: NumComponent) -> Self {
+        Self::new(OperandType::InputControlPoint(point, reg), modifiers, component_mode)
+    }
+
+    pub fn output_control_point(point: Address, reg: u32, modifiers: Modifier, component_mode: NumComponent) -> Self {
+        Self::new(OperandType::OutputControlPoint(point, reg), modifiers, component_mode)
+    }
+
+    pub fn input_patch_constant(reg: u32, modifiers: Modifier, component_mode: NumComponent) -> Self {
+        Self::new(OperandType::InputPatchConstant(reg), modifiers, component_mode)
+    }
+
+    pub fn system(ty: u32, modifiers: Modifier, component_mode: NumComponent) -> Self {
+        Self::new(OperandType::System(ty), modifiers, component_mode)
+    }
+
+    pub fn custom_data(class: CustomDataClass, data: Vec<u32>) -> Self {
+        Self::new(OperandType::CustomData(class, data), Modifier::None, NumComponent::D0)
+    }
+
     fn encode(&self, module: &mut DxbcModule) {
+        let ty = match self.get_type() {
+            Some(ty) => ty,
+            None => {
+                if let &OperandType::CustomData(class, ref data) = &self.ty {
+                    module.write_custom_data(class, data);
+                }
+                return;
+            }
+        };
+
         match &self.ty {
-            &OperandType::Register(reg) => {
-                module.write_operand(D3D10_SB_OPERAND_TYPE_TEMP, self.modifiers, self.component_mode, &[Immediate::U32(reg)])
+            &OperandType::Register(reg) |
+            &OperandType::Input(reg) |
+            &OperandType::Output(reg) |
+            &OperandType::Resource(reg) |
+            &OperandType::Sampler(reg) |
+            &OperandType::UnorderedAccessView(reg) |
+            &OperandType::ThreadGroupSharedMemory(reg) |
+            &OperandType::InputPatchConstant(reg) => {
+                module.write_operand_ex(ty, self.modifiers, self.min_precision, self.component_mode, &[Index::Imm32(reg)])
             },
-            &OperandType::Input(reg) => {
-                module.write_operand(D3D10_SB_OPERAND_TYPE_INPUT, self.modifiers, self.component_mode, &[Immediate::U32(reg)])
+            &OperandType::IndexableRegister(reg, ref address) |
+            &OperandType::ConstantBuffer(reg, ref address) |
+            &OperandType::Interface(reg, ref address) => {
+                module.write_operand_ex(ty, self.modifiers, self.min_precision, self.component_mode, &[Index::Imm32(reg), address.to_index()])
             },
-            &OperandType::Output(reg) => {
-                module.write_operand(D3D10_SB_OPERAND_TYPE_OUTPUT, self.modifiers, self.component_mode, &[Immediate::U32(reg)])
+            &OperandType::InputControlPoint(ref point, reg) |
+            &OperandType::OutputControlPoint(ref point, reg) => {
+                module.write_operand_ex(ty, self.modifiers, self.min_precision, self.component_mode, &[point.to_index(), Index::Imm32(reg)])
             },
-            OperandType::Imm32(imm) => {},
-            OperandType::Imm32x2(imm0, imm1) => {},
-            OperandType::Imm32x3(imm0, imm1, imm2) => {},
-            OperandType::Imm32x4(imm0, imm1, imm2, imm3) => {},
-            OperandType::Resource(reg) => {},
-            OperandType::Sampler(reg) => {},
-            OperandType::IndexableRegister(reg, index) => {},
-            OperandType::ConstantBuffer(reg, index) => {},
-            OperandType::CustomData(data) => {},
+            &OperandType::System(..) => {
+                module.write_operand_ex(ty, self.modifiers, self.min_precision, self.component_mode, &[])
+            },
+            &OperandType::ImmediateConstantBuffer(ref address) => {
+                module.write_operand_ex(ty, self.modifiers, self.min_precision, self.component_mode, &[address.to_index()])
+            },
+            &OperandType::Imm32(imm) => {
+                module.write_operand_ex(ty, self.modifiers, self.min_precision, NumComponent::D1, &[]);
+                module.write_u32(imm);
+            },
+            // immediates hold either one or four components, so shorter
+            // vectors are padded with zeroes
+            &OperandType::Imm32x2(imm0, imm1) => self.encode_imm32x4(module, [imm0, imm1, 0, 0]),
+            &OperandType::Imm32x3(imm0, imm1, imm2) => self.encode_imm32x4(module, [imm0, imm1, imm2, 0]),
+            &OperandType::Imm32x4(imm0, imm1, imm2, imm3) => self.encode_imm32x4(module, [imm0, imm1, imm2, imm3]),
+            &OperandType::CustomData(..) => unreachable!(),
         }
     }
 
-    fn get_type(&self) -> u32 {
-        match &self.ty {
+    fn encode_imm32x4(&self, module: &mut DxbcModule, imm: [u32; 4]) {
+        module.write_operand_ex(D3D10_SB_OPERAND_TYPE_IMMEDIATE32, self.modifiers, self.min_precision, NumComponent::D4(ComponentMode::Mask(0)), &[]);
+        for &word in &imm {
+            module.write_u32(word);
+        }
+    }
+
+    /// Returns the encoded operand type, or `None` for custom data which is
+    /// not encoded as an operand.
+    fn get_type(&self) -> Option<u32> {
+        let ty = match &self.ty {
             &OperandType::Register(..) => D3D10_SB_OPERAND_TYPE_TEMP,
             &OperandType::Input(..) => D3D10_SB_OPERAND_TYPE_INPUT,
             &OperandType::Output(..) => D3D10_SB_OPERAND_TYPE_OUTPUT,
-            _ => 100000,
-        }
+            &OperandType::Imm32(..) |
+            &OperandType::Imm32x2(..) |
+            &OperandType::Imm32x3(..) |
+            &OperandType::Imm32x4(..) => D3D10_SB_OPERAND_TYPE_IMMEDIATE32,
+            &OperandType::Resource(..) => D3D10_SB_OPERAND_TYPE_RESOURCE,
+            &OperandType::Sampler(..) => D3D10_SB_OPERAND_TYPE_SAMPLER,
+            &OperandType::UnorderedAccessView(..) => D3D11_SB_OPERAND_TYPE_UNORDERED_ACCESS_VIEW,
+            &OperandType::IndexableRegister(..) => D3D10_SB_OPERAND_TYPE_INDEXABLE_TEMP,
+            &OperandType::ConstantBuffer(..) => D3D10_SB_OPERAND_TYPE_CONSTANT_BUFFER,
+            &OperandType::ImmediateConstantBuffer(..) => D3D10_SB_OPERAND_TYPE_IMMEDIATE_CONSTANT_BUFFER,
+            &OperandType::ThreadGroupSharedMemory(..) => D3D11_SB_OPERAND_TYPE_THREAD_GROUP_SHARED_MEMORY,
+            &OperandType::Interface(..) => D3D11_SB_OPERAND_TYPE_INTERFACE,
+            &OperandType::InputControlPoint(..) => D3D11_SB_OPERAND_TYPE_INPUT_CONTROL_POINT,
+            &OperandType::OutputControlPoint(..) => D3D11_SB_OPERAND_TYPE_OUTPUT_CONTROL_POINT,
+            &OperandType::InputPatchConstant(..) => D3D11_SB_OPERAND_TYPE_INPUT_PATCH_CONSTANT,
+            &OperandType::System(ty) => ty,
+            &OperandType::CustomData(..) => return None,
+        };
+
+        Some(ty)
     }
 }
 
 pub struct ShexChunk {
+    program_type: ProgramType,
+    model: ShaderModel,
     instructions: Vec<Instruction>,
 }
 
 impl ShexChunk {
     pub fn new() -> Self {
         ShexChunk {
+            program_type: ProgramType::Vertex,
+            model: ShaderModel::V5_0,
             instructions: Vec::new(),
         }
     }
 
+    pub fn set_program_type(&mut self, program_type: ProgramType) {
+        self.program_type = program_type;
+    }
+
+    /// Sets the shader model, which also selects the chunk: `SHDR` for 4.x
+    /// and `SHEX` for 5.x.
+    pub fn set_model(&mut self, model: ShaderModel) {
+        self.model = model;
+    }
+
+    pub fn get_kind(&self) -> ShaderChunkKind {
+        match self.model.major() {
+            4 => ShaderChunkKind::Shdr,
+            _ => ShaderChunkKind::Shex,
+        }
+    }
+
     pub fn add_instruction(&mut self, instruction: Instruction) {
         self.instructions.push(instruction);
     }
+
+    /// Computes the statistics of the encoded program, as written to `STAT`.
+    pub fn compute_stat(&self) -> DecoderResult<IStatChunk> {
+        let mut module = DxbcModule::new();
+        module.write_shex(self);
+
+        // skip the chunk header, version and length tokens
+        let mut decoder = WordDecoder::new(&module.dwords[4..]);
+        let mut instructions = Vec::new();
+        while !decoder.eof() {
+            instructions.push(SparseInstruction::parse(&mut decoder)?);
+        }
+
+        let layout = StatLayout::from_major_version(self.model.major());
+        Ok(IStatChunk::from_instructions(layout, &instructions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dr::shex::{ComponentMask, CustomData, HullPhase, HullShaderPhase, OperandModifier, OperandType as ShexOperandType, Operands};
+    use dr::{Container, ShexHeader};
+    use binary::Error;
+
+    // writes `shex` into a container and reads back its program header
+    fn program_header(shex: ShexChunk) -> ShexHeader {
+        let mut builder = Builder::new();
+        builder.set_shex(shex);
+        let module = builder.module().unwrap();
+
+        Container::parse(module.as_bytes()).unwrap().shex().unwrap().unwrap().0
+    }
+
+    #[test]
+    fn register_ranges() {
+        let range = RegisterRange { lower: 2, upper: 0xffffffff, space: 3 };
+
+        let mut shex = ShexChunk::new();
+        shex.set_model(ShaderModel::V5_1);
+        shex.add_instruction(Instruction::DclConstantBuffer { binding: 0, size: 4, dynamic: false, range: Some(range) });
+        shex.add_instruction(Instruction::DclResource {
+            binding: 1,
+            dimension: ResourceDimension::Texture2D,
+            return_type: ResourceReturnType::Float,
+            range: Some(range),
+        });
+        shex.add_instruction(Instruction::DclSampler { binding: 2, mode: SamplerMode::Default, range: Some(range) });
+
+        let mut module = DxbcModule::new();
+        module.write_shex(&shex);
+        let mut decoder = WordDecoder::new(&module.dwords[4..]);
+
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::DclConstantBuffer(cb) => {
+                assert_eq!(cb.get_binding(), Some(0));
+                assert_eq!(cb.get_size(), Some(4));
+                assert_eq!(cb.get_range(), Some(range));
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::DclResource(resource) => {
+                assert_eq!(resource.get_register(), Some(1));
+                assert_eq!(resource.get_range(), Some(range));
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::DclSampler(sampler) => {
+                assert_eq!(sampler.get_register(), Some(2));
+                assert_eq!(sampler.get_range(), Some(range));
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        assert!(decoder.eof());
+    }
+
+    #[test]
+    fn custom_data() {
+        let table = vec![0x3f800000, 0, 0, 0, 0, 0x3f800000, 0, 0];
+
+        let mut shex = ShexChunk::new();
+        shex.add_instruction(Instruction::CustomData {
+            data: Operand::custom_data(CustomDataClass::ImmediateConstantBuffer, table.clone()),
+        });
+        shex.add_instruction(Instruction::CustomData {
+            data: Operand::custom_data(CustomDataClass::Comment, vec![]),
+        });
+        shex.add_instruction(Instruction::Ret);
+
+        let mut module = DxbcModule::new();
+        module.write_shex(&shex);
+        let mut decoder = WordDecoder::new(&module.dwords[4..]);
+
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::CustomData(CustomData::ImmediateConstantBuffer(icb)) => {
+                assert_eq!(icb.data, &table[..]);
+                assert_eq!(icb.get_vector_count(), 2);
+                assert_eq!(icb.get_vector(1), Some([0, 0x3f800000, 0, 0]));
+                assert_eq!(icb.get_vector(2), None);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::CustomData(CustomData::Comment(data)) => assert!(data.is_empty()),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::Ret => {}
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        assert!(decoder.eof());
+    }
+
+    #[test]
+    fn computed_stat() {
+        let mut shex = ShexChunk::new();
+        shex.add_instruction(Instruction::DclInput { register: Operand::input(0, Modifier::None, NumComponent::D4(ComponentMode::Mask(X))) });
+        shex.add_instruction(Instruction::DclTemps { count: 2 });
+        shex.add_instruction(Instruction::Add {
+            dest: Operand::register(0, Modifier::None, NumComponent::D4(ComponentMode::Mask(X))),
+            a: Operand::input(0, Modifier::None, NumComponent::D4(ComponentMode::Select(X))),
+            b: Operand::input(0, Modifier::None, NumComponent::D4(ComponentMode::Select(X))),
+            saturated: false,
+        });
+        shex.add_instruction(Instruction::Ret);
+
+        let mut builder = Builder::new();
+        builder.set_shex(shex);
+        let module = builder.module().unwrap();
+
+        let container = Container::parse(module.as_bytes()).unwrap();
+        let stat = container.stat().unwrap().unwrap();
+        assert_eq!(stat.layout, StatLayout::Sm5);
+        assert_eq!(stat.instruction_count, 2);
+        assert_eq!(stat.temp_register_count, 2);
+        assert_eq!(stat.dcl_count, 1);
+        assert_eq!(stat.float_instruction_count, 1);
+        assert_eq!(stat.static_flow_control_count, 1);
+    }
+
+    #[test]
+    fn undecodable_program() {
+        // a shader message without any of its fields
+        let mut shex = ShexChunk::new();
+        shex.add_instruction(Instruction::CustomData {
+            data: Operand::custom_data(CustomDataClass::ShaderMessage, vec![]),
+        });
+        shex.add_instruction(Instruction::Ret);
+
+        let mut builder = Builder::new();
+        builder.set_shex(shex);
+        match builder.module() {
+            Err(Error::LimitReached(..)) => {},
+            result => panic!("unexpected result: {:?}", result.map(|module| module.dwords.len())),
+        }
+    }
+
+    #[test]
+    fn chunk_table() {
+        let mut shex = ShexChunk::new();
+        shex.add_instruction(Instruction::Ret);
+
+        let mut builder = Builder::new();
+        builder.set_shex(shex);
+        builder.set_sfi0(0x20);
+        builder.set_ifce(vec![0; 8]);
+        builder.add_chunk(*b"PRIV", vec![1, 2, 3]);
+        builder.set_chunk_order(&[ChunkId::Raw(*b"PRIV"), ChunkId::Stat, ChunkId::Rdef]);
+        let module = builder.module().unwrap();
+
+        let container = Container::parse(module.as_bytes()).unwrap();
+        let fourccs: Vec<_> = container.chunks.iter().map(|chunk| &chunk.fourcc).collect();
+        assert_eq!(fourccs, vec![b"PRIV", b"STAT", b"IFCE", b"SHEX", b"SFI0"]);
+        assert_eq!(container.chunks[0].data, vec![1, 2, 3]);
+        assert_eq!(container.chunks[4].data, vec![0x20, 0, 0, 0, 0, 0, 0, 0]);
+
+        assert_eq!(module.dwords[6], 4 * module.dwords.len() as u32);
+        assert_eq!(module.dwords[7], 5);
+        assert_eq!(module.dwords[8], 4 * 13);
+        assert_eq!(Container::parse(module.as_bytes()).unwrap().module().dwords, module.dwords);
+    }
+
+    #[test]
+    fn geometry_shader() {
+        let mut shex = ShexChunk::new();
+        shex.set_program_type(ProgramType::Geometry);
+        shex.add_instruction(Instruction::DclGsInputPrimitive { primitive: Primitive::TriangleAdj });
+        shex.add_instruction(Instruction::DclGsOutputPrimitiveTopology { topology: PrimitiveTopology::TriangleStrip });
+        shex.add_instruction(Instruction::DclMaxOutputVertexCount { count: 6 });
+        shex.add_instruction(Instruction::DclStream { stream: 1 });
+        shex.add_instruction(Instruction::EmitStream { stream: 1 });
+        shex.add_instruction(Instruction::Cut);
+
+        let mut module = DxbcModule::new();
+        module.write_shex(&shex);
+        let mut decoder = WordDecoder::new(&module.dwords[4..]);
+
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::DclGsInputPrimitive(dcl) => assert_eq!(dcl.get_primitive(), Some(Primitive::TriangleAdj)),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::DclGsOutputPrimitiveTopology(dcl) => assert_eq!(dcl.get_topology(), Some(PrimitiveTopology::TriangleStrip)),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::DclMaxOutputVertexCount(dcl) => assert_eq!(dcl.count, 6),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::DclStream(dcl) => assert_eq!(dcl.get_stream(), Some(1)),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::EmitStream(emit) => assert_eq!(emit.get_stream(), Some(1)),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::Cut => {}
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        assert!(decoder.eof());
+
+        let header = program_header(shex);
+        assert_eq!(header.kind, ShaderChunkKind::Shex);
+        assert_eq!(header.get_program_type(), Some(ProgramType::Geometry));
+        assert_eq!(header.get_profile(), Some(String::from("gs_5_0")));
+    }
+
+    #[test]
+    fn hull_shader() {
+        let mut shex = ShexChunk::new();
+        shex.set_program_type(ProgramType::Hull);
+        shex.add_instruction(Instruction::HsDecls);
+        shex.add_instruction(Instruction::DclOutputControlPointCount { count: 3 });
+        shex.add_instruction(Instruction::DclTessDomain { domain: TessellatorDomain::Tri });
+        shex.add_instruction(Instruction::HsForkPhase);
+        shex.add_instruction(Instruction::DclHsForkPhaseInstanceCount { count: 3 });
+        shex.add_instruction(Instruction::Ret);
+
+        let mut builder = Builder::new();
+        builder.set_shex(shex);
+        let module = builder.module().unwrap();
+        let container = Container::parse(module.as_bytes()).unwrap();
+        let (header, code) = container.shex().unwrap().unwrap();
+        assert_eq!(header.get_program_type(), Some(ProgramType::Hull));
+        assert_eq!(header.get_profile(), Some(String::from("hs_5_0")));
+
+        let mut decoder = WordDecoder::new(&code);
+        let phases = HullPhase::parse_all(&mut decoder).unwrap();
+        assert_eq!(phases.len(), 2);
+        assert_eq!(phases[0].phase, HullShaderPhase::Declarations);
+        assert_eq!(phases[0].declarations.len(), 2);
+        assert_eq!(phases[1].phase, HullShaderPhase::Fork);
+        assert_eq!(phases[1].get_instance_count(), Some(3));
+        assert_eq!(phases[1].instructions.len(), 1);
+    }
+
+    #[test]
+    fn compute_shader() {
+        let mut shex = ShexChunk::new();
+        shex.set_program_type(ProgramType::Compute);
+        shex.add_instruction(Instruction::DclUavStructured {
+            binding: 1,
+            stride: 16,
+            globally_coherent: true,
+            order_preserving_counter: false,
+            range: None,
+        });
+        shex.add_instruction(Instruction::DclTgsmStructured { register: 0, stride: 4, count: 64 });
+        shex.add_instruction(Instruction::DclThreadGroup { x: 64, y: 1, z: 1 });
+        shex.add_instruction(Instruction::Sync { flags: SyncFlags::THREADS_IN_GROUP | SyncFlags::THREAD_GROUP_SHARED_MEMORY });
+
+        let mut module = DxbcModule::new();
+        module.write_shex(&shex);
+        let mut decoder = WordDecoder::new(&module.dwords[4..]);
+
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::DclUavStructured(uav) => {
+                assert_eq!(uav.get_register(), Some(1));
+                assert_eq!(uav.stride, 16);
+                assert!(uav.is_globally_coherent());
+                assert!(!uav.has_order_preserving_counter());
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::DclTgsmStructured(tgsm) => assert_eq!((tgsm.get_register(), tgsm.stride, tgsm.count), (Some(0), 4, 64)),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::DclThreadGroup(group) => assert_eq!((group.x, group.y, group.z), (64, 1, 1)),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::Sync(sync) => {
+                assert!(sync.is_thread_group_sync());
+                assert!(sync.is_tgsm_sync());
+                assert!(!sync.is_uav_global_sync());
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        assert!(decoder.eof());
+
+        let header = program_header(shex);
+        assert_eq!(header.kind, ShaderChunkKind::Shex);
+        assert_eq!(header.get_program_type(), Some(ProgramType::Compute));
+        assert_eq!(header.get_profile(), Some(String::from("cs_5_0")));
+
+        // shader model 4 programs are stored in SHDR
+        let mut shex = ShexChunk::new();
+        shex.set_program_type(ProgramType::Compute);
+        shex.set_model(ShaderModel::V4_0);
+        shex.add_instruction(Instruction::DclThreadGroup { x: 64, y: 1, z: 1 });
+
+        let header = program_header(shex);
+        assert_eq!(header.kind, ShaderChunkKind::Shdr);
+        assert_eq!(header.get_profile(), Some(String::from("cs_4_0")));
+    }
+
+    #[test]
+    fn double_precision() {
+        let mut src = Operand::register(1, Modifier::Neg, NumComponent::D4(ComponentMode::DoubleSwizzle(Y, X)));
+        src.set_min_precision(MinPrecision::Float16);
+
+        let mut shex = ShexChunk::new();
+        shex.add_instruction(Instruction::DAdd {
+            dest: Operand::register(0, Modifier::None, NumComponent::D4(ComponentMode::DoubleMask(Y))),
+            a: src,
+            b: Operand::register(2, Modifier::None, NumComponent::D4(ComponentMode::DoubleSwizzle(X, X))),
+            saturated: false,
+        });
+        shex.add_instruction(Instruction::Dtof {
+            dest: Operand::register(3, Modifier::None, NumComponent::D4(ComponentMode::Mask(X))),
+            src: Operand::register(0, Modifier::None, NumComponent::D4(ComponentMode::DoubleSwizzle(Y, Y))),
+        });
+
+        let mut module = DxbcModule::new();
+        module.write_shex(&shex);
+        let mut decoder = WordDecoder::new(&module.dwords[4..]);
+
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::DAdd(add) => {
+                assert_eq!(add.dst.get_component_mask(), ComponentMask::COMPONENT_MASK_B | ComponentMask::COMPONENT_MASK_A);
+                assert_eq!(add.dst.get_min_precision(), MinPrecision::Default);
+
+                let swizzle = add.a.get_component_swizzle();
+                assert_eq!([swizzle.0 as u8, swizzle.1 as u8, swizzle.2 as u8, swizzle.3 as u8], [2, 3, 0, 1]);
+                assert_eq!(add.a.get_min_precision(), MinPrecision::Float16);
+                assert_eq!(add.a.get_extended_operand().unwrap().get_operand_modifier(), OperandModifier::Neg);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::Dtof(dtof) => {
+                let swizzle = dtof.src.get_component_swizzle();
+                assert_eq!([swizzle.0 as u8, swizzle.1 as u8, swizzle.2 as u8, swizzle.3 as u8], [2, 3, 2, 3]);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        assert!(decoder.eof());
+    }
+
+    #[test]
+    fn operand_types() {
+        let select_w = NumComponent::D4(ComponentMode::Select(W));
+
+        let mut shex = ShexChunk::new();
+        shex.add_instruction(Instruction::Add {
+            dest: Operand::indexable_register(1, Address::Relative(IndexOperandType::Register(2), Y), Modifier::None, NumComponent::D4(ComponentMode::Mask(X | W))),
+            a: Operand::constant_buffer(0, Address::RelativePlusConstant(IndexOperandType::Input(3), Z, 4), Modifier::AbsNeg, select_w),
+            b: Operand::imm32x4(1, 2, 3, 4),
+            saturated: false,
+        });
+        shex.add_instruction(Instruction::Mul {
+            dest: Operand::uav(5, NumComponent::D4(ComponentMode::Mask(X))),
+            a: Operand::immediate_constant_buffer(Address::Constant(7), Modifier::None, NumComponent::D4(ComponentMode::Swizzle(W, Z, Y, X))),
+            b: Operand::imm32(0x3f800000),
+            saturated: false,
+        });
+
+        let mut module = DxbcModule::new();
+        module.write_shex(&shex);
+        let mut decoder = WordDecoder::new(&module.dwords[4..]);
+
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::Add(add) => {
+                assert_eq!(add.dst.get_operand_type(), ShexOperandType::IndexableTemp);
+                assert_eq!(add.dst.get_component_mask(), ComponentMask::COMPONENT_MASK_R | ComponentMask::COMPONENT_MASK_A);
+                match add.dst.get_immediate(1) {
+                    Some(Index::Relative(relative)) => {
+                        assert_eq!(relative.get_operand_type(), ShexOperandType::Temp);
+                        assert_eq!(relative.get_component_swizzle().0 as u8, 1);
+                    }
+                    imm => panic!("unexpected index: {:?}", imm),
+                }
+
+                assert_eq!(add.a.get_operand_type(), ShexOperandType::ConstantBuffer);
+                assert_eq!(add.a.get_extended_operand().unwrap().get_operand_modifier(), OperandModifier::AbsNeg);
+                assert_eq!(add.a.get_component_swizzle().0 as u8, 3);
+                assert_eq!(add.a.get_immediate(1).unwrap().get_offset(), 4);
+                assert_eq!(add.a.get_immediate(1).unwrap().get_relative().unwrap().get_operand_type(), ShexOperandType::Input);
+
+                assert_eq!(add.b.get_operand_type(), ShexOperandType::Immediate32);
+                let values: Vec<_> = add.b.get_immediates().iter().map(|imm| imm.get_offset()).collect();
+                assert_eq!(values, vec![1, 2, 3, 4]);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::Mul(mul) => {
+                assert_eq!(mul.dst.get_operand_type(), ShexOperandType::UnorderedAccessView);
+                assert_eq!(mul.a.get_operand_type(), ShexOperandType::ImmediateConstantBuffer);
+                assert_eq!(mul.a.get_immediate(0).unwrap().get_offset(), 7);
+                let swizzle = mul.a.get_component_swizzle();
+                assert_eq!([swizzle.0 as u8, swizzle.1 as u8, swizzle.2 as u8, swizzle.3 as u8], [3, 2, 1, 0]);
+                assert_eq!(mul.b.get_immediates().len(), 1);
+                assert_eq!(mul.b.get_immediate(0).unwrap().get_offset(), 0x3f800000);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        assert!(decoder.eof());
+    }
+
+    #[test]
+    fn sm5_instruction_set() {
+        let mask = NumComponent::D4(ComponentMode::Mask(X | Y | Z | W));
+        let swizzle = NumComponent::D4(ComponentMode::Swizzle(X, Y, Z, W));
+        let select_x = NumComponent::D4(ComponentMode::Select(X));
+
+        let mut shex = ShexChunk::new();
+        shex.add_instruction(Instruction::DclInputPsSiv {
+            register: Operand::input(1, Modifier::None, NumComponent::D4(ComponentMode::Mask(X | Y))),
+            interpolation: InterpolationMode::LinearNoPerspective,
+            semantic: Semantic::Position,
+        });
+        shex.add_instruction(Instruction::DclTessDomain { domain: TessellatorDomain::Quad });
+        shex.add_instruction(Instruction::DclInterface {
+            interface: 0,
+            expected_table_length: 2,
+            array_length: 1,
+            tables: vec![3, 4],
+            dynamically_indexed: true,
+        });
+        shex.add_instruction(Instruction::If { cond: Operand::register(0, Modifier::None, select_x), test: TestBoolean::NonZero });
+        shex.add_instruction(Instruction::Sample {
+            dest: Operand::register(0, Modifier::None, mask),
+            address: Operand::input(1, Modifier::None, swizzle),
+            resource: Operand::resource(2, swizzle),
+            sampler: Operand::sampler(3),
+            extended: vec![OpcodeEx::UvOffset(1, -1i32 as u32, 0)],
+        });
+        shex.add_instruction(Instruction::EndIf);
+        shex.add_instruction(Instruction::Mov { dest: Operand::output(0, Modifier::None, mask), src: Operand::register(0, Modifier::None, swizzle), saturated: true });
+        shex.add_instruction(Instruction::ResInfo {
+            dest: Operand::register(1, Modifier::None, mask),
+            mip_level: Operand::imm32(0),
+            resource: Operand::resource(2, swizzle),
+            return_type: ResInfoReturnType::Uint,
+        });
+        shex.add_instruction(Instruction::ImmAtomicCmpExch {
+            dest: Operand::register(2, Modifier::None, NumComponent::D4(ComponentMode::Mask(X))),
+            uav: Operand::tgsm(0, NumComponent::D4(ComponentMode::Mask(X))),
+            address: Operand::imm32(16),
+            compare: Operand::register(0, Modifier::None, select_x),
+            src: Operand::register(1, Modifier::None, select_x),
+        });
+        shex.add_instruction(Instruction::Ret);
+
+        let mut module = DxbcModule::new();
+        module.write_shex(&shex);
+        let mut decoder = WordDecoder::new(&module.dwords[4..]);
+
+        let instruction = SparseInstruction::parse(&mut decoder).unwrap();
+        assert_eq!(instruction.opcode.get_interpolation_mode(), Some(InterpolationMode::LinearNoPerspective));
+        match instruction.operands {
+            Operands::DclInputPsSiv(input) => {
+                assert_eq!(input.get_input_register(), Some(1));
+                assert_eq!(input.get_system_name(), Some(NameToken::Position));
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::DclTessDomain(dcl) => assert_eq!(dcl.get_domain(), Some(TessellatorDomain::Quad)),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::DclInterface(dcl) => {
+                assert_eq!((dcl.expected_table_length, dcl.array_length), (2, 1));
+                assert_eq!(dcl.tables, &[3, 4]);
+                assert!(dcl.dynamically_indexed);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+
+        let instruction = SparseInstruction::parse(&mut decoder).unwrap();
+        assert_eq!(instruction.opcode.get_test_type(), TestBoolean::NonZero);
+        match instruction.operands {
+            Operands::If(_) => {}
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+
+        let instruction = SparseInstruction::parse(&mut decoder).unwrap();
+        let extended: Vec<_> = instruction.extended.iter()
+            .filter_map(|&ex| OpcodeEx::from_extended_opcode(ex))
+            .collect();
+        match extended[..] {
+            [OpcodeEx::UvOffset(1, v, 0)] => assert_eq!(v & 0xf, 0xf),
+            _ => panic!("unexpected extended opcodes: {:?}", extended),
+        }
+        match instruction.operands {
+            Operands::Sample(sample) => {
+                assert_eq!(sample.src_resource.get_operand_type(), ShexOperandType::Resource);
+                assert_eq!(sample.src_sampler.get_operand_type(), ShexOperandType::Sampler);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::EndIf => {}
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+
+        let instruction = SparseInstruction::parse(&mut decoder).unwrap();
+        assert!(instruction.opcode.is_saturated());
+        match instruction.operands {
+            Operands::Mov(_) => {}
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+
+        let instruction = SparseInstruction::parse(&mut decoder).unwrap();
+        let return_type = DECODE_D3D10_SB_RESINFO_INSTRUCTION_RETURN_TYPE(instruction.opcode.get_word());
+        assert_eq!(return_type, D3D10_SB_RESINFO_INSTRUCTION_RETURN_UINT);
+        match instruction.operands {
+            Operands::ResInfo(resinfo) => assert_eq!(resinfo.src_mip_level.get_immediate(0).unwrap().get_offset(), 0),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::ImmAtomicCmpExch(atomic) => {
+                assert_eq!(atomic.dst_resource.get_operand_type(), ShexOperandType::ThreadGroupSharedMemory);
+                assert_eq!(atomic.dst_address.get_immediate(0).unwrap().get_offset(), 16);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::Ret => {}
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        assert!(decoder.eof());
+    }
+
+    #[test]
+    fn extended_opcodes() {
+        let extended = [
+            OpcodeEx::UvOffset(-1i32 as u32, 7, -8i32 as u32),
+            OpcodeEx::Dimension(ResourceDimension::StructuredBuffer, 24),
+            OpcodeEx::ResourceReturnType(ResourceReturnType::Float, ResourceReturnType::Sint, ResourceReturnType::Uint, ResourceReturnType::Unorm),
+        ];
+
+        let mut module = DxbcModule::new();
+        module.write_opcode(D3D10_SB_OPCODE_NOP, 4, None, false, &extended);
+        let mut decoder = WordDecoder::new(&module.dwords);
+        let instruction = SparseInstruction::parse(&mut decoder).unwrap();
+        assert!(decoder.eof());
+
+        assert_eq!(instruction.extended, vec![
+            ExtendedOpcode::SampleControls(-1, 7, -8),
+            ExtendedOpcode::ResourceDim(ResourceDimension::StructuredBuffer, 24),
+            ExtendedOpcode::ResourceReturnType(ResourceReturnType::Float, ResourceReturnType::Sint, ResourceReturnType::Uint, ResourceReturnType::Unorm),
+        ]);
+
+        let reencoded = instruction.extended
+            .iter()
+            .filter_map(|&ex| OpcodeEx::from_extended_opcode(ex))
+            .collect::<Vec<_>>();
+        let mut roundtrip = DxbcModule::new();
+        roundtrip.write_opcode(D3D10_SB_OPCODE_NOP, 4, None, false, &reencoded);
+        assert_eq!(roundtrip.dwords, module.dwords);
+
+        // extended opcode type 63 is not defined
+        let bogus = [
+            ENCODE_D3D10_SB_OPCODE_TYPE(D3D10_SB_OPCODE_NOP) |
+            ENCODE_D3D10_SB_OPCODE_EXTENDED(1) |
+            ENCODE_D3D10_SB_TOKENIZED_INSTRUCTION_LENGTH(2),
+            ENCODE_D3D10_SB_EXTENDED_OPCODE_TYPE(0x3f),
+        ];
+        let mut decoder = WordDecoder::new(&bogus);
+        match SparseInstruction::parse(&mut decoder) {
+            Err(Error::InvalidOpcode(offset)) => assert_eq!(offset, 0),
+            result => panic!("unexpected result: {:?}", result.map(|instruction| instruction.extended)),
+        }
+    }
 }
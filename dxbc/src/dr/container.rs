@@ -0,0 +1,462 @@
+use binary::*;
+use dr::{DxbcHeader, DxbcModule, IOsgnChunk, IStatChunk, RdefChunk, ShaderChunkKind, ShexChunk, ShexHeader, SignatureLayout, SparseInstruction, StatLayout};
+
+use byteorder::{ByteOrder, LittleEndian};
+
+const DXBC_MAGIC: u32 = 0x43425844;
+
+const INPUT_SIGNATURES: &[&[u8; 4]] = &[b"ISGN", b"ISG1"];
+const OUTPUT_SIGNATURES: &[&[u8; 4]] = &[b"OSGN", b"OSG5", b"OSG1"];
+const PATCH_CONSTANT_SIGNATURES: &[&[u8; 4]] = &[b"PCSG", b"PSG1"];
+
+/// A single chunk of a DXBC container, without its fourcc and size header.
+#[derive(Debug, Clone)]
+pub struct Chunk {
+    pub fourcc: [u8; 4],
+    pub data: Vec<u8>,
+}
+
+/// Owned in-memory DXBC container.
+///
+/// Chunks are kept as raw bytes in their original order, so a container that
+/// is loaded and written back without modification is byte-identical to the
+/// input. Known chunks are validated on load and decoded on demand through the
+/// typed accessors.
+#[derive(Debug, Clone)]
+pub struct Container {
+    unknown: u32,
+    pub chunks: Vec<Chunk>,
+    // checksum computed over the parsed input and the one stored in it, which
+    // differ for files edited without updating the checksum. The stored one is
+    // written back as long as the content is unchanged.
+    checksum: Option<([u32; 4], [u32; 4])>,
+}
+
+impl Container {
+    pub fn new() -> Self {
+        Container {
+            unknown: 1,
+            chunks: Vec::new(),
+            checksum: None,
+        }
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Container, State> {
+        let mut decoder = decoder::Decoder::new(bytes);
+        let header = DxbcHeader::parse(&mut decoder)?;
+        let directory = read_chunk_directory(&mut decoder, &header)?;
+
+        let mut chunks = Vec::new();
+        for entry in &directory {
+            decoder.seek_mut(entry.offset as usize + 8)?;
+
+            let chunk = Chunk {
+                fourcc: entry.fourcc,
+                data: decoder.bytes(entry.size as usize)?.to_vec(),
+            };
+
+            chunk.validate()?;
+            chunks.push(chunk);
+        }
+
+        Ok(Container {
+            unknown: header._unknown,
+            chunks,
+            checksum: Some((::checksum(bytes), header.checksum)),
+        })
+    }
+
+    /// Returns false if the checksum stored in the parsed input does not match
+    /// its content, e.g. because the file was patched after compilation.
+    pub fn is_checksum_valid(&self) -> bool {
+        match self.checksum {
+            Some((computed, stored)) => computed == stored,
+            None => true,
+        }
+    }
+
+    pub fn chunk(&self, fourcc: &[u8; 4]) -> Option<&Chunk> {
+        self.chunks.iter().find(|chunk| &chunk.fourcc == fourcc)
+    }
+
+    /// Replaces the data of the chunk with the given fourcc, or appends a new
+    /// chunk if there is none.
+    pub fn set_chunk(&mut self, fourcc: [u8; 4], data: Vec<u8>) {
+        match self.chunks.iter_mut().find(|chunk| chunk.fourcc == fourcc) {
+            Some(chunk) => chunk.data = data,
+            None => self.chunks.push(Chunk { fourcc, data }),
+        }
+    }
+
+    pub fn remove_chunk(&mut self, fourcc: &[u8; 4]) -> Option<Chunk> {
+        let idx = self.chunks.iter().position(|chunk| &chunk.fourcc == fourcc)?;
+
+        Some(self.chunks.remove(idx))
+    }
+
+    pub fn rdef<'a>(&'a self) -> Result<Option<RdefChunk<'a>>, State> {
+        match self.chunk(b"RDEF") {
+            Some(chunk) => Ok(Some(RdefChunk::parse(&mut chunk.decoder())?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the input signature, read from either `ISGN` or `ISG1`.
+    pub fn isgn(&self) -> Result<Option<IOsgnChunk>, State> {
+        self.signature(INPUT_SIGNATURES)
+    }
+
+    /// Returns the output signature, read from either `OSGN`, `OSG5` or `OSG1`.
+    pub fn osgn(&self) -> Result<Option<IOsgnChunk>, State> {
+        self.signature(OUTPUT_SIGNATURES)
+    }
+
+    /// Returns the patch constant signature, read from either `PCSG` or `PSG1`.
+    pub fn pcsg(&self) -> Result<Option<IOsgnChunk>, State> {
+        self.signature(PATCH_CONSTANT_SIGNATURES)
+    }
+
+    pub fn stat(&self) -> Result<Option<IStatChunk>, State> {
+        match self.chunk(b"STAT") {
+            Some(chunk) => Ok(Some(IStatChunk::parse(&mut chunk.decoder())?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Computes statistics from the shader program, for example to compare
+    /// them against the ones stored in `STAT`.
+    pub fn compute_stat(&self) -> Result<Option<IStatChunk>, State> {
+        let (header, code) = match self.shex()? {
+            Some(shex) => shex,
+            None => return Ok(None),
+        };
+
+        let mut decoder = decoder::WordDecoder::new(&code);
+        let mut instructions = Vec::new();
+        while !decoder.eof() {
+            instructions.push(SparseInstruction::parse(&mut decoder)?);
+        }
+
+        let layout = StatLayout::from_major_version(header.major);
+        Ok(Some(IStatChunk::from_instructions(layout, &instructions)))
+    }
+
+    /// Returns the shader program header along with its instruction tokens,
+    /// which can be decoded with `SparseInstruction::parse`. Both `SHEX` and
+    /// `SHDR` chunks are considered.
+    pub fn shex(&self) -> Result<Option<(ShexHeader, Vec<u32>)>, State> {
+        let chunk = self.chunks
+            .iter()
+            .find(|chunk| ShaderChunkKind::from_fourcc(&chunk.fourcc).is_some());
+
+        match chunk {
+            Some(chunk) => Ok(Some(chunk.program()?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn set_rdef(&mut self, rdef: &RdefChunk) {
+        let mut module = DxbcModule::new();
+        module.write_rdef(rdef);
+        self.replace_chunk(&[b"RDEF"], chunk_from_module(&module));
+    }
+
+    /// Replaces the input signature. The chunk is written as `ISGN` or
+    /// `ISG1` depending on the layout of `isgn`.
+    pub fn set_isgn(&mut self, isgn: &IOsgnChunk) {
+        let mut module = DxbcModule::new();
+        module.write_isgn(isgn);
+        self.replace_chunk(INPUT_SIGNATURES, chunk_from_module(&module));
+    }
+
+    pub fn set_osgn(&mut self, osgn: &IOsgnChunk) {
+        let mut module = DxbcModule::new();
+        module.write_osgn(osgn);
+        self.replace_chunk(OUTPUT_SIGNATURES, chunk_from_module(&module));
+    }
+
+    pub fn set_pcsg(&mut self, pcsg: &IOsgnChunk) {
+        let mut module = DxbcModule::new();
+        module.write_pcsg(pcsg);
+        self.replace_chunk(PATCH_CONSTANT_SIGNATURES, chunk_from_module(&module));
+    }
+
+    pub fn set_stat(&mut self, stat: &IStatChunk) {
+        let mut module = DxbcModule::new();
+        module.write_stat(stat);
+        self.replace_chunk(&[b"STAT"], chunk_from_module(&module));
+    }
+
+    pub fn set_shex(&mut self, shex: &ShexChunk) {
+        let mut module = DxbcModule::new();
+        module.write_shex(shex);
+        self.replace_chunk(&[b"SHDR", b"SHEX"], chunk_from_module(&module));
+    }
+
+    fn signature(&self, fourccs: &[&[u8; 4]]) -> Result<Option<IOsgnChunk>, State> {
+        match self.chunks.iter().find(|chunk| fourccs.contains(&&chunk.fourcc)) {
+            Some(chunk) => {
+                let layout = SignatureLayout::from_fourcc(&chunk.fourcc).unwrap();
+
+                Ok(Some(IOsgnChunk::parse(&mut chunk.decoder(), layout)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    // replaces the first chunk matching any of `fourccs` in place, so that
+    // e.g. an `ISGN` chunk can be swapped for an `ISG1` chunk
+    fn replace_chunk(&mut self, fourccs: &[&[u8; 4]], chunk: Chunk) {
+        match self.chunks.iter_mut().find(|c| fourccs.contains(&&c.fourcc)) {
+            Some(c) => *c = chunk,
+            None => self.chunks.push(chunk),
+        }
+    }
+
+    pub fn module(&self) -> DxbcModule {
+        let mut module = DxbcModule::new();
+
+        module.write_u32(DXBC_MAGIC);
+        let checksum_pos = module.position();
+        module.write_u32(0);
+        module.write_u32(0);
+        module.write_u32(0);
+        module.write_u32(0);
+
+        module.write_u32(self.unknown);
+
+        let size_pos = module.position();
+        module.write_u32(0);
+
+        module.write_u32(self.chunks.len() as u32);
+        let chunk_offsets_pos = module.position();
+        for _ in &self.chunks {
+            module.write_u32(0);
+        }
+
+        for (idx, chunk) in self.chunks.iter().enumerate() {
+            let pos = module.position() * 4;
+            module.set_u32(chunk_offsets_pos + idx, pos as u32);
+
+            module.write_u32(LittleEndian::read_u32(&chunk.fourcc));
+            module.write_u32(chunk.data.len() as u32);
+            module.write_bytes(&chunk.data);
+        }
+
+        // finally, patch in size and checksum
+        let len = 4 * module.dwords.len() as u32;
+        module.set_u32(size_pos, len);
+        let computed = ::checksum(module.as_bytes());
+        let checksum = match self.checksum {
+            Some((parsed, stored)) if parsed == computed => stored,
+            _ => computed,
+        };
+        module.set_u32(checksum_pos,     checksum[0]);
+        module.set_u32(checksum_pos + 1, checksum[1]);
+        module.set_u32(checksum_pos + 2, checksum[2]);
+        module.set_u32(checksum_pos + 3, checksum[3]);
+
+        module
+    }
+}
+
+impl Chunk {
+    pub fn decoder<'a>(&'a self) -> decoder::Decoder<'a> {
+        decoder::Decoder::new(&self.data)
+    }
+
+    fn program(&self) -> Result<(ShexHeader, Vec<u32>), State> {
+        let kind = ShaderChunkKind::from_fourcc(&self.fourcc).ok_or(State::ChunkIncorrect)?;
+        let mut decoder = self.decoder();
+        let header = ShexHeader::parse(&mut decoder, kind)?;
+        let code = decoder.words(header.instruction_length as usize)?;
+
+        Ok((header, code))
+    }
+
+    /// Decodes known chunks, discarding the result.
+    fn validate(&self) -> Result<(), State> {
+        match &self.fourcc {
+            b"RDEF" => { RdefChunk::parse(&mut self.decoder())?; },
+            b"ISGN" | b"ISG1" |
+            b"OSGN" | b"OSG5" | b"OSG1" |
+            b"PCSG" | b"PSG1" => {
+                let layout = SignatureLayout::from_fourcc(&self.fourcc).unwrap();
+                IOsgnChunk::parse(&mut self.decoder(), layout)?;
+            },
+            b"STAT" => { IStatChunk::parse(&mut self.decoder())?; },
+            b"SHDR" |
+            b"SHEX" => {
+                let (_, code) = self.program()?;
+                let mut decoder = decoder::WordDecoder::new(&code);
+
+                while !decoder.eof() {
+                    SparseInstruction::parse(&mut decoder)?;
+                }
+            },
+            _ => {},
+        }
+
+        Ok(())
+    }
+}
+
+// splits a module holding a single chunk into its fourcc and data
+fn chunk_from_module(module: &DxbcModule) -> Chunk {
+    let bytes = module.as_bytes();
+
+    Chunk {
+        fourcc: [bytes[0], bytes[1], bytes[2], bytes[3]],
+        data: bytes[8..].to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dr::{MinPrecision, SemanticName};
+
+    #[test]
+    fn round_trip() {
+        let shaders: &[&[u8]] = &[
+            include_bytes!("../../../dxbcd/assembled.dxbc"),
+            include_bytes!("../../../dxbcd/complex_shader.dxbc"),
+            include_bytes!("../../../dxbcd/reference.dxbc"),
+            include_bytes!("../../../dxbcd/shader.dxbc"),
+            include_bytes!("../../../dxbcd/shader_abort.dxbc"),
+        ];
+
+        for bytes in shaders {
+            let container = Container::parse(bytes).unwrap();
+
+            assert_eq!(container.module().as_bytes(), *bytes);
+        }
+    }
+
+    #[test]
+    fn modified_checksum() {
+        // a new container with the chunks of an fxc output has to end up with
+        // the checksum fxc stored
+        let bytes = include_bytes!("../../../dxbcd/shader.dxbc");
+        let parsed = Container::parse(bytes).unwrap();
+        assert!(parsed.is_checksum_valid());
+
+        let mut container = Container::new();
+        for chunk in &parsed.chunks {
+            container.set_chunk(chunk.fourcc, chunk.data.clone());
+        }
+        assert_eq!(container.module().as_bytes(), &bytes[..]);
+
+        container.remove_chunk(b"STAT").unwrap();
+        let module = container.module();
+        let written = module.as_bytes();
+        let header = DxbcHeader::parse(&mut decoder::Decoder::new(written)).unwrap();
+        assert_eq!(header.checksum, ::checksum(written));
+    }
+
+    #[test]
+    fn stale_checksum() {
+        // both were patched after compilation without updating the checksum
+        let shaders: &[&[u8]] = &[
+            include_bytes!("../../../dxbcd/complex_shader.dxbc"),
+            include_bytes!("../../../dxbcd/shader_abort.dxbc"),
+        ];
+
+        for bytes in shaders {
+            let mut container = Container::parse(bytes).unwrap();
+            assert!(!container.is_checksum_valid());
+            assert_eq!(container.module().as_bytes(), *bytes);
+
+            container.remove_chunk(b"STAT").unwrap();
+            let module = container.module();
+            let written = module.as_bytes();
+            let header = DxbcHeader::parse(&mut decoder::Decoder::new(written)).unwrap();
+            assert_eq!(header.checksum, ::checksum(written));
+        }
+    }
+
+    #[test]
+    fn corrupt_signature() {
+        let bytes = include_bytes!("../../../dxbcd/shader.dxbc");
+        let mut container = Container::parse(bytes).unwrap();
+        let mut isgn = container.chunk(b"ISGN").unwrap().data.clone();
+
+        // component type of the first element
+        LittleEndian::write_u32(&mut isgn[20..], 0x1000);
+        container.set_chunk(*b"ISGN", isgn);
+        assert!(Container::parse(container.module().as_bytes()).is_err());
+    }
+
+    #[test]
+    fn unknown_system_values() {
+        let mut bytes = include_bytes!("../../../dxbcd/shader.dxbc").to_vec();
+        let isgn = chunk_directory(&bytes).unwrap().into_iter()
+            .find(|entry| &entry.fourcc == b"ISGN")
+            .unwrap();
+
+        // semantic type of the first element
+        let pos = isgn.offset as usize + 8 + 16;
+        for &(word, name) in &[(69, SemanticName::StencilRef), (70, SemanticName::InnerCoverage), (0x1000, SemanticName::Unknown(0x1000))] {
+            LittleEndian::write_u32(&mut bytes[pos..], word);
+
+            let container = Container::parse(&bytes).unwrap();
+            let isgn = container.isgn().unwrap().unwrap();
+            assert_eq!(isgn.elements[0].semantic_type, name);
+            assert_eq!(name.to_word(), word);
+        }
+    }
+
+    #[test]
+    fn directory() {
+        let bytes = include_bytes!("../../../dxbcd/shader.dxbc");
+        let container = Container::parse(bytes).unwrap();
+        let directory = chunk_directory(bytes).unwrap();
+
+        assert_eq!(directory.len(), container.chunks.len());
+        for (entry, chunk) in directory.iter().zip(&container.chunks) {
+            assert_eq!(entry.fourcc, chunk.fourcc);
+            assert_eq!(entry.size as usize, chunk.data.len());
+        }
+    }
+
+    #[test]
+    fn replace_signature() {
+        let bytes = include_bytes!("../../../dxbcd/shader.dxbc");
+        let mut container = Container::parse(bytes).unwrap();
+        let mut isgn = container.isgn().unwrap().unwrap();
+
+        isgn.layout = SignatureLayout::MinPrecision;
+        isgn.elements[0].min_precision = Some(MinPrecision::Float16);
+        container.set_isgn(&isgn);
+
+        assert!(container.chunk(b"ISGN").is_none());
+        let isg1 = container.isgn().unwrap().unwrap();
+        assert_eq!(isg1.layout, SignatureLayout::MinPrecision);
+        assert_eq!(isg1.elements[0].name, isgn.elements[0].name);
+        assert_eq!(isg1.elements[0].min_precision, Some(MinPrecision::Float16));
+    }
+
+    #[test]
+    fn stat_round_trip() {
+        let bytes = include_bytes!("../../../dxbcd/complex_shader.dxbc");
+        let mut container = Container::parse(bytes).unwrap();
+        let original = container.chunk(b"STAT").unwrap().data.clone();
+        let stat = container.stat().unwrap().unwrap();
+
+        assert_eq!(stat.layout, StatLayout::Sm5);
+        assert_eq!(4 * stat.to_words().len(), original.len());
+
+        container.set_stat(&stat);
+        assert_eq!(container.chunk(b"STAT").unwrap().data, original);
+    }
+
+    #[test]
+    fn computed_stat() {
+        let bytes = include_bytes!("../../../dxbcd/complex_shader.dxbc");
+        let container = Container::parse(bytes).unwrap();
+        let stat = container.stat().unwrap().unwrap();
+        let computed = container.compute_stat().unwrap().unwrap();
+
+        assert_eq!(computed.to_words(), stat.to_words());
+    }
+}
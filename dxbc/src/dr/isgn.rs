@@ -1,7 +1,5 @@
 use binary::*;
 
-use std::mem;
-
 #[repr(u32)]
 #[derive(Debug, Copy, Clone)]
 pub enum RegisterComponentType {
@@ -12,47 +10,158 @@ pub enum RegisterComponentType {
 }
 
 impl RegisterComponentType {
-    pub fn from_word(word: u32) -> Self {
+    pub fn from_word(word: u32) -> Option<Self> {
         match word {
-            0...3 => unsafe { mem::transmute(word) },
-            _ => unreachable!()
+            0 => Some(RegisterComponentType::Unknown),
+            1 => Some(RegisterComponentType::Uint32),
+            2 => Some(RegisterComponentType::Int32),
+            3 => Some(RegisterComponentType::Float32),
+            _ => None,
         }
     }
 }
 
-#[repr(u32)]
-#[derive(Debug, Copy, Clone)]
+/// System value of a signature element, `D3D_NAME` in `d3dcommon.h`.
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum SemanticName {
-    Undefined = 0,
-    Position = 1,
-    ClipDistance = 2,
-    CullDistance = 3,
-    RenderTargetArrayIndex = 4,
-    ViewportArrayIndex = 5,
-    VertexId = 6,
-    PrimitiveId = 7,
-    InstanceId = 8,
-    IsFrontFace = 9,
-    SampleIndex = 10,
-    FinalQuadEdgeTessfactor = 11,
-    FinalQuadInsideTessfactor = 12,
-    FinalTriEdgeTessfactor = 13,
-    FinalTriInsideTessfactor = 14,
-    FinalLineDetailTessfactor = 15,
-    FinalLineDensityTessfactor = 16,
-    Target = 64,
-    Depth = 65,
-    Coverage = 66,
-    DepthGreaterEqual = 67,
-    DepthLessEqual = 68,
+    Undefined,
+    Position,
+    ClipDistance,
+    CullDistance,
+    RenderTargetArrayIndex,
+    ViewportArrayIndex,
+    VertexId,
+    PrimitiveId,
+    InstanceId,
+    IsFrontFace,
+    SampleIndex,
+    FinalQuadEdgeTessfactor,
+    FinalQuadInsideTessfactor,
+    FinalTriEdgeTessfactor,
+    FinalTriInsideTessfactor,
+    FinalLineDetailTessfactor,
+    FinalLineDensityTessfactor,
+    Target,
+    Depth,
+    Coverage,
+    DepthGreaterEqual,
+    DepthLessEqual,
+    StencilRef,
+    InnerCoverage,
+    /// System value added after this list, kept as its raw value
+    Unknown(u32),
 }
 
 impl SemanticName {
     pub fn from_word(word: u32) -> Self {
         match word {
-            0...16 |
-            64...68 => unsafe { mem::transmute(word) },
-            _ => unreachable!()
+            0 => SemanticName::Undefined,
+            1 => SemanticName::Position,
+            2 => SemanticName::ClipDistance,
+            3 => SemanticName::CullDistance,
+            4 => SemanticName::RenderTargetArrayIndex,
+            5 => SemanticName::ViewportArrayIndex,
+            6 => SemanticName::VertexId,
+            7 => SemanticName::PrimitiveId,
+            8 => SemanticName::InstanceId,
+            9 => SemanticName::IsFrontFace,
+            10 => SemanticName::SampleIndex,
+            11 => SemanticName::FinalQuadEdgeTessfactor,
+            12 => SemanticName::FinalQuadInsideTessfactor,
+            13 => SemanticName::FinalTriEdgeTessfactor,
+            14 => SemanticName::FinalTriInsideTessfactor,
+            15 => SemanticName::FinalLineDetailTessfactor,
+            16 => SemanticName::FinalLineDensityTessfactor,
+            64 => SemanticName::Target,
+            65 => SemanticName::Depth,
+            66 => SemanticName::Coverage,
+            67 => SemanticName::DepthGreaterEqual,
+            68 => SemanticName::DepthLessEqual,
+            69 => SemanticName::StencilRef,
+            70 => SemanticName::InnerCoverage,
+            _ => SemanticName::Unknown(word),
+        }
+    }
+
+    pub fn to_word(&self) -> u32 {
+        match *self {
+            SemanticName::Undefined => 0,
+            SemanticName::Position => 1,
+            SemanticName::ClipDistance => 2,
+            SemanticName::CullDistance => 3,
+            SemanticName::RenderTargetArrayIndex => 4,
+            SemanticName::ViewportArrayIndex => 5,
+            SemanticName::VertexId => 6,
+            SemanticName::PrimitiveId => 7,
+            SemanticName::InstanceId => 8,
+            SemanticName::IsFrontFace => 9,
+            SemanticName::SampleIndex => 10,
+            SemanticName::FinalQuadEdgeTessfactor => 11,
+            SemanticName::FinalQuadInsideTessfactor => 12,
+            SemanticName::FinalTriEdgeTessfactor => 13,
+            SemanticName::FinalTriInsideTessfactor => 14,
+            SemanticName::FinalLineDetailTessfactor => 15,
+            SemanticName::FinalLineDensityTessfactor => 16,
+            SemanticName::Target => 64,
+            SemanticName::Depth => 65,
+            SemanticName::Coverage => 66,
+            SemanticName::DepthGreaterEqual => 67,
+            SemanticName::DepthLessEqual => 68,
+            SemanticName::StencilRef => 69,
+            SemanticName::InnerCoverage => 70,
+            SemanticName::Unknown(word) => word,
+        }
+    }
+}
+
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MinPrecision {
+    Default = 0,
+    Float16 = 1,
+    Float2_8 = 2,
+    Reserved = 3,
+    SInt16 = 4,
+    UInt16 = 5,
+    Any16 = 0xf0,
+    Any10 = 0xf1,
+}
+
+impl MinPrecision {
+    pub fn from_word(word: u32) -> Option<Self> {
+        match word {
+            0 => Some(MinPrecision::Default),
+            1 => Some(MinPrecision::Float16),
+            2 => Some(MinPrecision::Float2_8),
+            3 => Some(MinPrecision::Reserved),
+            4 => Some(MinPrecision::SInt16),
+            5 => Some(MinPrecision::UInt16),
+            0xf0 => Some(MinPrecision::Any16),
+            0xf1 => Some(MinPrecision::Any10),
+            _ => None,
+        }
+    }
+}
+
+/// Element layout of a signature chunk.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SignatureLayout {
+    /// 24 byte elements, used by `ISGN`, `OSGN` and `PCSG`
+    Basic,
+    /// 28 byte elements prefixed by a stream index, used by `OSG5`
+    Stream,
+    /// 32 byte elements with stream index and minimum precision, used by
+    /// `ISG1`, `OSG1` and `PSG1`
+    MinPrecision,
+}
+
+impl SignatureLayout {
+    pub fn from_fourcc(fourcc: &[u8; 4]) -> Option<Self> {
+        match fourcc {
+            b"ISGN" | b"OSGN" | b"PCSG" => Some(SignatureLayout::Basic),
+            b"OSG5" => Some(SignatureLayout::Stream),
+            b"ISG1" | b"OSG1" | b"PSG1" => Some(SignatureLayout::MinPrecision),
+            _ => None,
         }
     }
 }
@@ -67,20 +176,37 @@ pub struct InputOutputElement {
     pub register: u32,
     pub component_mask: u8,
     pub rw_mask: u8,
+    /// Geometry shader output stream, present in `OSG5` and `*SG1` chunks
+    pub stream: Option<u32>,
+    /// Present in `*SG1` chunks
+    pub min_precision: Option<MinPrecision>,
 }
 
 impl InputOutputElement {
-    pub fn parse<'a>(decoder: &mut decoder::Decoder<'a>) -> Result<Self, State> {
-        let name_offset = decoder.read_u32();
-        let semantic_index = decoder.read_u32();
-        let semantic_type = SemanticName::from_word(decoder.read_u32());
-        let component_type = RegisterComponentType::from_word(decoder.read_u32());
-        let register = decoder.read_u32();
-        let component_mask = decoder.read_u8();
-        let rw_mask = decoder.read_u8();
-        decoder.skip(2);
-
-        let name = decoder.seek(name_offset as usize).string().map_err(|e| State::DecoderError(e))?;
+    pub fn parse<'a>(decoder: &mut decoder::Decoder<'a>, layout: SignatureLayout) -> Result<Self, State> {
+        let stream = match layout {
+            SignatureLayout::Basic => None,
+            SignatureLayout::Stream |
+            SignatureLayout::MinPrecision => Some(decoder.read_u32()?),
+        };
+
+        let name_offset = decoder.read_u32()?;
+        let semantic_index = decoder.read_u32()?;
+        let semantic_type = SemanticName::from_word(decoder.read_u32()?);
+        let component_type = RegisterComponentType::from_word(decoder.read_u32()?).ok_or(State::ChunkIncorrect)?;
+        let register = decoder.read_u32()?;
+        let component_mask = decoder.read_u8()?;
+        let rw_mask = decoder.read_u8()?;
+        decoder.skip(2)?;
+
+        let min_precision = match layout {
+            SignatureLayout::MinPrecision => {
+                Some(MinPrecision::from_word(decoder.read_u32()?).ok_or(State::ChunkIncorrect)?)
+            }
+            _ => None,
+        };
+
+        let name = decoder.seek(name_offset as usize)?.string()?;
 
         Ok(Self {
             name,
@@ -90,6 +216,8 @@ impl InputOutputElement {
             register,
             component_mask,
             rw_mask,
+            stream,
+            min_precision,
         })
     }
 }
@@ -97,20 +225,22 @@ impl InputOutputElement {
 #[repr(C)]
 #[derive(Debug)]
 pub struct IOsgnChunk {
+    pub layout: SignatureLayout,
     pub elements: Vec<InputOutputElement>,
 }
 
 impl IOsgnChunk {
-    pub fn parse<'b>(decoder: &'b mut decoder::Decoder) -> Result<IOsgnChunk, State> {
-        let element_count = decoder.read_u32();
-        let _unknown = decoder.read_u32();
+    pub fn parse<'b>(decoder: &'b mut decoder::Decoder, layout: SignatureLayout) -> Result<IOsgnChunk, State> {
+        let element_count = decoder.read_u32()?;
+        let _unknown = decoder.read_u32()?;
 
         let mut elements = Vec::new();
         for _ in 0..element_count {
-            elements.push(InputOutputElement::parse(decoder)?);
+            elements.push(InputOutputElement::parse(decoder, layout)?);
         }
 
         Ok(IOsgnChunk {
+            layout,
             elements,
         })
     }
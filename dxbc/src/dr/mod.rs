@@ -1,5 +1,5 @@
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ResourceReturnType {
     NotApplicable = 0,
     UNorm = 1,
@@ -12,20 +12,39 @@ pub enum ResourceReturnType {
     Continued = 8
 }
 
+impl ResourceReturnType {
+    pub fn from_word(word: u32) -> Option<Self> {
+        match word {
+            0 => Some(ResourceReturnType::NotApplicable),
+            1 => Some(ResourceReturnType::UNorm),
+            2 => Some(ResourceReturnType::SNorm),
+            3 => Some(ResourceReturnType::SInt),
+            4 => Some(ResourceReturnType::UInt),
+            5 => Some(ResourceReturnType::Float),
+            6 => Some(ResourceReturnType::Mixed),
+            7 => Some(ResourceReturnType::Double),
+            8 => Some(ResourceReturnType::Continued),
+            _ => None,
+        }
+    }
+}
+
 pub mod rdef;
 pub mod isgn;
 pub mod shex;
 pub mod stat;
 pub mod builder;
+pub mod container;
 
 pub use self::rdef::*;
 pub use self::isgn::*;
 pub use self::shex::*;
 pub use self::stat::*;
 pub use self::builder::*;
+pub use self::container::*;
 
+use binary::*;
 
-#[repr(C)]
 #[derive(Debug)]
 pub struct DxbcHeader {
     pub magic: [u8; 4],
@@ -34,3 +53,26 @@ pub struct DxbcHeader {
     pub size: u32,
     pub chunk_count: u32,
 }
+
+impl DxbcHeader {
+    pub fn parse(decoder: &mut decoder::Decoder) -> Result<DxbcHeader, State> {
+        let magic = decoder.bytes(4)?;
+
+        if magic != b"DXBC" {
+            return Err(State::HeaderIncorrect);
+        }
+
+        Ok(DxbcHeader {
+            magic: [magic[0], magic[1], magic[2], magic[3]],
+            checksum: [
+                decoder.read_u32()?,
+                decoder.read_u32()?,
+                decoder.read_u32()?,
+                decoder.read_u32()?,
+            ],
+            _unknown: decoder.read_u32()?,
+            size: decoder.read_u32()?,
+            chunk_count: decoder.read_u32()?,
+        })
+    }
+}
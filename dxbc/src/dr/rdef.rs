@@ -31,33 +31,69 @@ bitflags! {
 }
 
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ConstantBufferType {
-    ConstantBuffer,
-    TextureBuffer,
-    InterfacePointers,
-    ResourceBindInformation,
+    ConstantBuffer = 0,
+    TextureBuffer = 1,
+    InterfacePointers = 2,
+    ResourceBindInformation = 3,
+}
+
+impl ConstantBufferType {
+    pub fn from_word(word: u32) -> Option<Self> {
+        match word {
+            0 => Some(ConstantBufferType::ConstantBuffer),
+            1 => Some(ConstantBufferType::TextureBuffer),
+            2 => Some(ConstantBufferType::InterfacePointers),
+            3 => Some(ConstantBufferType::ResourceBindInformation),
+            _ => None,
+        }
+    }
 }
 
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ShaderInputType {
-    CBuffer,
-    TBuffer,
-    Texture,
-    Sampler,
-    UavRwTyped,
-    Structured,
-    UavRwStructured,
-    ByteAddress,
-    UavRwByteAddress,
-    UavAppendStructured,
-    UavConsumeStructured,
-    UavRwStructuredWithCounter,
+    CBuffer = 0,
+    TBuffer = 1,
+    Texture = 2,
+    Sampler = 3,
+    UavRwTyped = 4,
+    Structured = 5,
+    UavRwStructured = 6,
+    ByteAddress = 7,
+    UavRwByteAddress = 8,
+    UavAppendStructured = 9,
+    UavConsumeStructured = 10,
+    UavRwStructuredWithCounter = 11,
+    RtAccelerationStructure = 12,
+    UavFeedbackTexture = 13,
+}
+
+impl ShaderInputType {
+    pub fn from_word(word: u32) -> Option<Self> {
+        match word {
+            0 => Some(ShaderInputType::CBuffer),
+            1 => Some(ShaderInputType::TBuffer),
+            2 => Some(ShaderInputType::Texture),
+            3 => Some(ShaderInputType::Sampler),
+            4 => Some(ShaderInputType::UavRwTyped),
+            5 => Some(ShaderInputType::Structured),
+            6 => Some(ShaderInputType::UavRwStructured),
+            7 => Some(ShaderInputType::ByteAddress),
+            8 => Some(ShaderInputType::UavRwByteAddress),
+            9 => Some(ShaderInputType::UavAppendStructured),
+            10 => Some(ShaderInputType::UavConsumeStructured),
+            11 => Some(ShaderInputType::UavRwStructuredWithCounter),
+            12 => Some(ShaderInputType::RtAccelerationStructure),
+            13 => Some(ShaderInputType::UavFeedbackTexture),
+            _ => None,
+        }
+    }
 }
 
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ShaderVariableClass {
     Scalar,
     Vector,
@@ -69,8 +105,24 @@ pub enum ShaderVariableClass {
     InterfacePointer,
 }
 
+impl ShaderVariableClass {
+    pub fn from_word(word: u32) -> Option<Self> {
+        match word {
+            0 => Some(ShaderVariableClass::Scalar),
+            1 => Some(ShaderVariableClass::Vector),
+            2 => Some(ShaderVariableClass::MatrixRows),
+            3 => Some(ShaderVariableClass::MatrixColumns),
+            4 => Some(ShaderVariableClass::Object),
+            5 => Some(ShaderVariableClass::Struct),
+            6 => Some(ShaderVariableClass::InterfaceClass),
+            7 => Some(ShaderVariableClass::InterfacePointer),
+            _ => None,
+        }
+    }
+}
+
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ShaderVariableType {
     Void = 0,
     Bool = 1,
@@ -83,8 +135,14 @@ pub enum ShaderVariableType {
     Texture3D = 8,
     TextureCube = 9,
     Sampler = 10,
+    Sampler1D = 11,
+    Sampler2D = 12,
+    Sampler3D = 13,
+    SamplerCube = 14,
     PixelShader = 15,
     VertexShader = 16,
+    PixelFragment = 17,
+    VertexFragment = 18,
     UInt = 19,
     UInt8 = 20,
     GeometryShader = 21,
@@ -106,22 +164,94 @@ pub enum ShaderVariableType {
     InterfacePointer = 37,
     ComputeShader = 38,
     Double = 39,
-    ReadWriteTexture1D,
-    ReadWriteTexture1DArray,
-    ReadWriteTexture2D,
-    ReadWriteTexture2DArray,
-    ReadWriteTexture3D,
-    ReadWriteBuffer,
-    ByteAddressBuffer,
-    ReadWriteByteAddressBuffer,
-    StructuredBuffer,
-    ReadWriteStructuredBuffer,
-    AppendStructuredBuffer,
-    ConsumeStructuredBuffer,
+    ReadWriteTexture1D = 40,
+    ReadWriteTexture1DArray = 41,
+    ReadWriteTexture2D = 42,
+    ReadWriteTexture2DArray = 43,
+    ReadWriteTexture3D = 44,
+    ReadWriteBuffer = 45,
+    ByteAddressBuffer = 46,
+    ReadWriteByteAddressBuffer = 47,
+    StructuredBuffer = 48,
+    ReadWriteStructuredBuffer = 49,
+    AppendStructuredBuffer = 50,
+    ConsumeStructuredBuffer = 51,
+    Min8Float = 52,
+    Min10Float = 53,
+    Min16Float = 54,
+    Min12Int = 55,
+    Min16Int = 56,
+    Min16UInt = 57,
+}
+
+impl ShaderVariableType {
+    pub fn from_word(word: u32) -> Option<Self> {
+        match word {
+            0 => Some(ShaderVariableType::Void),
+            1 => Some(ShaderVariableType::Bool),
+            2 => Some(ShaderVariableType::Int),
+            3 => Some(ShaderVariableType::Float),
+            4 => Some(ShaderVariableType::String),
+            5 => Some(ShaderVariableType::Texture),
+            6 => Some(ShaderVariableType::Texture1D),
+            7 => Some(ShaderVariableType::Texture2D),
+            8 => Some(ShaderVariableType::Texture3D),
+            9 => Some(ShaderVariableType::TextureCube),
+            10 => Some(ShaderVariableType::Sampler),
+            11 => Some(ShaderVariableType::Sampler1D),
+            12 => Some(ShaderVariableType::Sampler2D),
+            13 => Some(ShaderVariableType::Sampler3D),
+            14 => Some(ShaderVariableType::SamplerCube),
+            15 => Some(ShaderVariableType::PixelShader),
+            16 => Some(ShaderVariableType::VertexShader),
+            17 => Some(ShaderVariableType::PixelFragment),
+            18 => Some(ShaderVariableType::VertexFragment),
+            19 => Some(ShaderVariableType::UInt),
+            20 => Some(ShaderVariableType::UInt8),
+            21 => Some(ShaderVariableType::GeometryShader),
+            22 => Some(ShaderVariableType::Rasterizer),
+            23 => Some(ShaderVariableType::DepthStencil),
+            24 => Some(ShaderVariableType::Blend),
+            25 => Some(ShaderVariableType::Buffer),
+            26 => Some(ShaderVariableType::CBuffer),
+            27 => Some(ShaderVariableType::TBuffer),
+            28 => Some(ShaderVariableType::Texture1DArray),
+            29 => Some(ShaderVariableType::Texture2DArray),
+            30 => Some(ShaderVariableType::RenderTargetView),
+            31 => Some(ShaderVariableType::DepthStencilView),
+            32 => Some(ShaderVariableType::Texture2DMultiSampled),
+            33 => Some(ShaderVariableType::Texture2DMultiSampledArray),
+            34 => Some(ShaderVariableType::TextureCubeArray),
+            35 => Some(ShaderVariableType::HullShader),
+            36 => Some(ShaderVariableType::DomainShader),
+            37 => Some(ShaderVariableType::InterfacePointer),
+            38 => Some(ShaderVariableType::ComputeShader),
+            39 => Some(ShaderVariableType::Double),
+            40 => Some(ShaderVariableType::ReadWriteTexture1D),
+            41 => Some(ShaderVariableType::ReadWriteTexture1DArray),
+            42 => Some(ShaderVariableType::ReadWriteTexture2D),
+            43 => Some(ShaderVariableType::ReadWriteTexture2DArray),
+            44 => Some(ShaderVariableType::ReadWriteTexture3D),
+            45 => Some(ShaderVariableType::ReadWriteBuffer),
+            46 => Some(ShaderVariableType::ByteAddressBuffer),
+            47 => Some(ShaderVariableType::ReadWriteByteAddressBuffer),
+            48 => Some(ShaderVariableType::StructuredBuffer),
+            49 => Some(ShaderVariableType::ReadWriteStructuredBuffer),
+            50 => Some(ShaderVariableType::AppendStructuredBuffer),
+            51 => Some(ShaderVariableType::ConsumeStructuredBuffer),
+            52 => Some(ShaderVariableType::Min8Float),
+            53 => Some(ShaderVariableType::Min10Float),
+            54 => Some(ShaderVariableType::Min16Float),
+            55 => Some(ShaderVariableType::Min12Int),
+            56 => Some(ShaderVariableType::Min16Int),
+            57 => Some(ShaderVariableType::Min16UInt),
+            _ => None,
+        }
+    }
 }
 
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ViewDimension {
     Unknown = 0,
     Buffer = 1,
@@ -137,36 +267,243 @@ pub enum ViewDimension {
     ExtendedBuffer = 11,
 }
 
+impl ViewDimension {
+    pub fn from_word(word: u32) -> Option<Self> {
+        match word {
+            0 => Some(ViewDimension::Unknown),
+            1 => Some(ViewDimension::Buffer),
+            2 => Some(ViewDimension::Texture1D),
+            3 => Some(ViewDimension::Texture1DArray),
+            4 => Some(ViewDimension::Texture2D),
+            5 => Some(ViewDimension::Texture2DArray),
+            6 => Some(ViewDimension::Texture2DMultiSampled),
+            7 => Some(ViewDimension::Texture2DMultiSampledArray),
+            8 => Some(ViewDimension::Texture3D),
+            9 => Some(ViewDimension::TextureCube),
+            10 => Some(ViewDimension::TextureCubeArray),
+            11 => Some(ViewDimension::ExtendedBuffer),
+            _ => None,
+        }
+    }
+}
+
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ShaderModel {
-    V5_0
+    V4_0,
+    V4_1,
+    V5_0,
+    V5_1,
+}
+
+impl ShaderModel {
+    pub fn from_version(major: u8, minor: u8) -> Option<Self> {
+        match (major, minor) {
+            (4, 0) => Some(ShaderModel::V4_0),
+            (4, 1) => Some(ShaderModel::V4_1),
+            (5, 0) => Some(ShaderModel::V5_0),
+            (5, 1) => Some(ShaderModel::V5_1),
+            _ => None,
+        }
+    }
+
+    pub fn major(&self) -> u8 {
+        match *self {
+            ShaderModel::V4_0 | ShaderModel::V4_1 => 4,
+            ShaderModel::V5_0 | ShaderModel::V5_1 => 5,
+        }
+    }
+
+    pub fn minor(&self) -> u8 {
+        match *self {
+            ShaderModel::V4_0 | ShaderModel::V5_0 => 0,
+            ShaderModel::V4_1 | ShaderModel::V5_1 => 1,
+        }
+    }
+}
+
+// nested struct types deeper than this are rejected
+const MAX_TYPE_DEPTH: usize = 64;
+// types may be shared between members, so the number of types decoded from a
+// chunk is bounded separately
+const MAX_TYPE_COUNT: u32 = 1 << 16;
+
+/// Keeps track of the types decoded from a chunk, to reject type trees that
+/// are cyclic or expand to an excessive number of types.
+#[derive(Debug, Default)]
+pub struct TypeTracker {
+    // offsets of the types enclosing the one being decoded
+    path: Vec<usize>,
+    count: u32,
+}
+
+impl TypeTracker {
+    pub fn new() -> Self {
+        TypeTracker::default()
+    }
+
+    fn enter(&mut self, offset: usize) -> Result<(), State> {
+        self.count += 1;
+
+        if self.path.len() >= MAX_TYPE_DEPTH || self.count > MAX_TYPE_COUNT || self.path.contains(&offset) {
+            return Err(State::ChunkIncorrect);
+        }
+
+        self.path.push(offset);
+        Ok(())
+    }
+
+    fn leave(&mut self) {
+        self.path.pop();
+    }
 }
 
-#[repr(C)]
 #[derive(Debug)]
 pub struct ShaderTypeMember<'a> {
-    name: &'a str,
-    ty: ShaderType<'a>,
+    pub name: &'a str,
+    /// Offset of the member from the start of the parent struct
+    pub offset: u32,
+    pub ty: ShaderType<'a>,
+}
+
+impl<'a> ShaderTypeMember<'a> {
+    pub fn parse(decoder: &mut decoder::Decoder<'a>, layout: &Rd11Header, types: &mut TypeTracker) -> Result<Self, State> {
+        let name_offset = decoder.read_u32()?;
+        let type_offset = decoder.read_u32()?;
+        let offset = decoder.read_u32()?;
+
+        let name = decoder.seek(name_offset as usize)?.str()?;
+        let ty = ShaderType::parse(&mut decoder.seek(type_offset as usize)?, layout, types)?;
+
+        Ok(ShaderTypeMember {
+            name,
+            offset,
+            ty,
+        })
+    }
 }
 
-#[repr(C)]
 #[derive(Debug)]
 pub struct ShaderType<'a> {
-    class: ShaderVariableClass,
-    ty: ShaderVariableType,
-    rows: u16,
-    columns: u16,
-    count: u16,
-    members: Vec<ShaderTypeMember<'a>>,
+    pub class: ShaderVariableClass,
+    pub ty: ShaderVariableType,
+    pub rows: u16,
+    pub columns: u16,
+    /// Number of array elements, or 0 if the type is not an array
+    pub elements: u16,
+    pub members: Vec<ShaderTypeMember<'a>>,
+    /// Type name, only present in SM5 reflection data
+    pub name: Option<&'a str>,
+}
+
+impl<'a> ShaderType<'a> {
+    pub fn parse(decoder: &mut decoder::Decoder<'a>, layout: &Rd11Header, types: &mut TypeTracker) -> Result<Self, State> {
+        types.enter(decoder.get_offset())?;
+
+        let class = ShaderVariableClass::from_word(decoder.read_u16()? as u32)
+            .ok_or(State::ChunkIncorrect)?;
+        let ty = ShaderVariableType::from_word(decoder.read_u16()? as u32)
+            .ok_or(State::ChunkIncorrect)?;
+        let rows = decoder.read_u16()?;
+        let columns = decoder.read_u16()?;
+        let elements = decoder.read_u16()?;
+        let member_count = decoder.read_u16()?;
+        let member_offset = decoder.read_u32()?;
+
+        let name = if layout.type_size >= 36 {
+            decoder.skip(16)?;
+            let name_offset = decoder.read_u32()?;
+
+            if name_offset != 0 {
+                Some(decoder.seek(name_offset as usize)?.str()?)
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        let mut members = Vec::new();
+        for i in 0..member_count as usize {
+            let offset = member_offset as usize + i * layout.member_size as usize;
+            members.push(ShaderTypeMember::parse(&mut decoder.seek(offset)?, layout, types)?);
+        }
+
+        types.leave();
+
+        Ok(ShaderType {
+            class,
+            ty,
+            rows,
+            columns,
+            elements,
+            members,
+            name,
+        })
+    }
+}
+
+/// Texture and sampler slots used by a variable, only present in SM5
+/// reflection data.
+#[derive(Debug, Copy, Clone)]
+pub struct ResourceSlots {
+    pub start_texture: u32,
+    pub texture_size: u32,
+    pub start_sampler: u32,
+    pub sampler_size: u32,
 }
 
-#[repr(C)]
 #[derive(Debug)]
 pub struct ShaderVariable<'a> {
-    name: &'a str,
-    byte_size: u32,
-    flags: ShaderVariableFlags,
+    pub name: &'a str,
+    /// Offset of the variable from the start of the constant buffer
+    pub start_offset: u32,
+    pub byte_size: u32,
+    pub flags: ShaderVariableFlags,
+    pub ty: ShaderType<'a>,
+    pub default_value: Option<&'a [u8]>,
+    pub slots: Option<ResourceSlots>,
+}
+
+impl<'a> ShaderVariable<'a> {
+    pub fn parse(decoder: &mut decoder::Decoder<'a>, layout: &Rd11Header, types: &mut TypeTracker) -> Result<Self, State> {
+        let name_offset = decoder.read_u32()?;
+        let start_offset = decoder.read_u32()?;
+        let byte_size = decoder.read_u32()?;
+        let flags = ShaderVariableFlags::from_bits_truncate(decoder.read_u32()?);
+        let type_offset = decoder.read_u32()?;
+        let default_value_offset = decoder.read_u32()?;
+
+        let slots = if layout.variable_size >= 40 {
+            Some(ResourceSlots {
+                start_texture: decoder.read_u32()?,
+                texture_size: decoder.read_u32()?,
+                start_sampler: decoder.read_u32()?,
+                sampler_size: decoder.read_u32()?,
+            })
+        } else {
+            None
+        };
+
+        let name = decoder.seek(name_offset as usize)?.str()?;
+        let ty = ShaderType::parse(&mut decoder.seek(type_offset as usize)?, layout, types)?;
+
+        let default_value = if default_value_offset != 0 {
+            Some(decoder.seek(default_value_offset as usize)?.bytes(byte_size as usize)?)
+        } else {
+            None
+        };
+
+        Ok(ShaderVariable {
+            name,
+            start_offset,
+            byte_size,
+            flags,
+            ty,
+            default_value,
+            slots,
+        })
+    }
 }
 
 #[repr(C)]
@@ -175,21 +512,27 @@ pub struct ConstantBuffer<'a> {
     pub name: &'a str,
     pub variables: Vec<ShaderVariable<'a>>,
     pub byte_size: u32,
-    pub flags: u32,
-    pub ty: u32,
+    pub flags: ConstantBufferFlags,
+    pub ty: ConstantBufferType,
 }
 
 impl<'a> ConstantBuffer<'a> {
-    pub fn parse(decoder: &mut decoder::Decoder<'a>) -> Result<Self, State> {
-        let name_offset = decoder.read_u32();
-        let var_count = decoder.read_u32();
-        let var_offset = decoder.read_u32();
-        let byte_size = decoder.read_u32();
-        let flags = decoder.read_u32();
-        let ty = decoder.read_u32();
-
-        let name = decoder.seek(name_offset as usize).str().map_err(|e| State::DecoderError(e))?;
-        let variables = Vec::new();
+    pub fn parse(decoder: &mut decoder::Decoder<'a>, layout: &Rd11Header, types: &mut TypeTracker) -> Result<Self, State> {
+        let name_offset = decoder.read_u32()?;
+        let var_count = decoder.read_u32()?;
+        let var_offset = decoder.read_u32()?;
+        let byte_size = decoder.read_u32()?;
+        let flags = ConstantBufferFlags::from_bits_truncate(decoder.read_u32()?);
+        let ty = ConstantBufferType::from_word(decoder.read_u32()?)
+            .ok_or(State::ChunkIncorrect)?;
+
+        let name = decoder.seek(name_offset as usize)?.str()?;
+
+        let mut variables = Vec::new();
+        for i in 0..var_count as usize {
+            let offset = var_offset as usize + i * layout.variable_size as usize;
+            variables.push(ShaderVariable::parse(&mut decoder.seek(offset)?, layout, types)?);
+        }
 
         Ok(Self {
             name,
@@ -205,27 +548,42 @@ impl<'a> ConstantBuffer<'a> {
 #[derive(Debug)]
 pub struct ResourceBinding<'a> {
     pub name: &'a str,
-    pub input_type: u32,
-    pub return_type: u32,
-    pub view_dimension: u32,
+    pub input_type: ShaderInputType,
+    pub return_type: ResourceReturnType,
+    pub view_dimension: ViewDimension,
     pub sample_count: u32,
     pub bind_point: u32,
     pub bind_count: u32,
-    pub input_flags: u32,
+    pub input_flags: ShaderInputFlags,
+    /// Register space, present in SM5.1 reflection data
+    pub space: Option<u32>,
+    /// Range ID used by the shader to refer to this binding, present in SM5.1
+    /// reflection data
+    pub id: Option<u32>,
 }
 
 impl<'a> ResourceBinding<'a> {
-    pub fn parse(decoder: &mut decoder::Decoder<'a>) -> Result<Self, State> {
-        let name_offset = decoder.read_u32();
-        let input_type = decoder.read_u32();
-        let return_type = decoder.read_u32();
-        let view_dimension = decoder.read_u32();
-        let sample_count = decoder.read_u32();
-        let bind_point = decoder.read_u32();
-        let bind_count = decoder.read_u32();
-        let input_flags = decoder.read_u32();
-
-        let name = decoder.seek(name_offset as usize).str().map_err(|e| State::DecoderError(e))?;
+    pub fn parse(decoder: &mut decoder::Decoder<'a>, layout: &Rd11Header) -> Result<Self, State> {
+        let name_offset = decoder.read_u32()?;
+        let input_type = ShaderInputType::from_word(decoder.read_u32()?)
+            .ok_or(State::ChunkIncorrect)?;
+        let return_type = ResourceReturnType::from_word(decoder.read_u32()?)
+            .ok_or(State::ChunkIncorrect)?;
+        let view_dimension = ViewDimension::from_word(decoder.read_u32()?)
+            .ok_or(State::ChunkIncorrect)?;
+        let sample_count = decoder.read_u32()?;
+        let bind_point = decoder.read_u32()?;
+        let bind_count = decoder.read_u32()?;
+        let input_flags = ShaderInputFlags::from_bits_truncate(decoder.read_u32()?);
+
+        let (space, id) = if layout.resource_binding_size >= 40 {
+            let space = decoder.read_u32()?;
+            (Some(space), Some(decoder.read_u32()?))
+        } else {
+            (None, None)
+        };
+
+        let name = decoder.seek(name_offset as usize)?.str()?;
 
         Ok(Self {
             name,
@@ -236,10 +594,96 @@ impl<'a> ResourceBinding<'a> {
             bind_point,
             bind_count,
             input_flags,
+            space,
+            id,
         })
     }
 }
 
+const RD11_MAGIC: u32 = 0x31314452;
+// SM5.1 compilers write 0x25441313 (bytes `13 13 44 25`) instead of `RD11`
+const RD11_MAGIC_5_1: u32 = 0x25441313;
+
+/// Sizes of the reflection descriptors, declared by the `RD11` header of
+/// SM5 reflection data. Newer compilers may emit larger descriptors than the
+/// ones decoded here, so tables are walked with the declared sizes.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Rd11Header {
+    pub rdef_header_size: u32,
+    pub constant_buffer_size: u32,
+    pub resource_binding_size: u32,
+    pub variable_size: u32,
+    pub type_size: u32,
+    pub member_size: u32,
+    pub interface_slot_count: u32,
+}
+
+impl Rd11Header {
+    /// Descriptor sizes of SM4 reflection data, which has no `RD11` header.
+    pub fn sm4() -> Self {
+        Rd11Header {
+            rdef_header_size: 28,
+            constant_buffer_size: 24,
+            resource_binding_size: 32,
+            variable_size: 24,
+            type_size: 16,
+            member_size: 12,
+            interface_slot_count: 0,
+        }
+    }
+
+    /// Descriptor sizes written by fxc for SM5.0.
+    pub fn sm5() -> Self {
+        Rd11Header {
+            rdef_header_size: 60,
+            constant_buffer_size: 24,
+            resource_binding_size: 32,
+            variable_size: 40,
+            type_size: 36,
+            member_size: 12,
+            interface_slot_count: 0,
+        }
+    }
+
+    /// Descriptor sizes written by fxc for SM5.1, which extends resource
+    /// bindings with a register space and range ID.
+    pub fn sm51() -> Self {
+        Rd11Header {
+            resource_binding_size: 40,
+            ..Rd11Header::sm5()
+        }
+    }
+
+    pub fn parse(decoder: &mut decoder::Decoder) -> Result<Self, State> {
+        match decoder.read_u32()? {
+            RD11_MAGIC | RD11_MAGIC_5_1 => {},
+            _ => return Err(State::ChunkIncorrect),
+        }
+
+        let header = Rd11Header {
+            rdef_header_size: decoder.read_u32()?,
+            constant_buffer_size: decoder.read_u32()?,
+            resource_binding_size: decoder.read_u32()?,
+            variable_size: decoder.read_u32()?,
+            type_size: decoder.read_u32()?,
+            member_size: decoder.read_u32()?,
+            interface_slot_count: decoder.read_u32()?,
+        };
+
+        // descriptors may grow, but never shrink below the SM4 layout
+        let min = Rd11Header::sm4();
+        if header.constant_buffer_size < min.constant_buffer_size ||
+           header.resource_binding_size < min.resource_binding_size ||
+           header.variable_size < min.variable_size ||
+           header.type_size < min.type_size ||
+           header.member_size < min.member_size {
+            return Err(State::ChunkIncorrect);
+        }
+
+        Ok(header)
+    }
+}
+
 #[repr(C)]
 #[derive(Debug)]
 pub struct RdefChunk<'a> {
@@ -250,55 +694,47 @@ pub struct RdefChunk<'a> {
     pub major: u8,
     pub flags: u32,
     pub author: &'a str,
-    pub rd11: Option<[u32; 7]>,
+    pub rd11: Option<Rd11Header>,
 }
 
 impl<'a> RdefChunk<'a> {
-    pub fn parse<'b>(decoder: &'b mut decoder::Decoder) -> Result<RdefChunk<'b>, State> {
-        let cb_count = decoder.read_u32();
-        let cb_offset = decoder.read_u32();
+    pub fn parse(decoder: &mut decoder::Decoder<'a>) -> Result<RdefChunk<'a>, State> {
+        let cb_count = decoder.read_u32()?;
+        let cb_offset = decoder.read_u32()?;
 
-        let bind_count = decoder.read_u32();
-        let bind_offset = decoder.read_u32();
+        let bind_count = decoder.read_u32()?;
+        let bind_offset = decoder.read_u32()?;
 
-        let minor = decoder.read_u8();
-        let major = decoder.read_u8();
+        let minor = decoder.read_u8()?;
+        let major = decoder.read_u8()?;
 
-        let shader_ty = decoder.read_u16();
+        let shader_ty = decoder.read_u16()?;
 
-        let flags = decoder.read_u32();
-        let author_offset = decoder.read_u32();
+        let flags = decoder.read_u32()?;
+        let author_offset = decoder.read_u32()?;
 
         let rd11 = if major >= 5 {
-            let magic = decoder.read_u32();
-            // assert_eq!(magic, b"RD11");
-
-            Some([
-                decoder.read_u32(),
-                decoder.read_u32(),
-                decoder.read_u32(),
-                decoder.read_u32(),
-                decoder.read_u32(),
-                decoder.read_u32(),
-                decoder.read_u32(),
-            ])
+            Some(Rd11Header::parse(decoder)?)
         } else {
             None
         };
 
-        decoder.seek_mut(cb_offset as usize);
+        let layout = rd11.unwrap_or_else(Rd11Header::sm4);
+
+        let mut types = TypeTracker::new();
         let mut constant_buffers = Vec::new();
-        for _ in 0..cb_count {
-            constant_buffers.push(ConstantBuffer::parse(decoder)?);
+        for i in 0..cb_count as usize {
+            let offset = cb_offset as usize + i * layout.constant_buffer_size as usize;
+            constant_buffers.push(ConstantBuffer::parse(&mut decoder.seek(offset)?, &layout, &mut types)?);
         }
 
-        decoder.seek_mut(bind_offset as usize);
         let mut resource_bindings = Vec::new();
-        for _ in 0..bind_count {
-            resource_bindings.push(ResourceBinding::parse(decoder)?);
+        for i in 0..bind_count as usize {
+            let offset = bind_offset as usize + i * layout.resource_binding_size as usize;
+            resource_bindings.push(ResourceBinding::parse(&mut decoder.seek(offset)?, &layout)?);
         }
 
-        let author = decoder.seek(author_offset as usize).str().map_err(|e| State::DecoderError(e))?;
+        let author = decoder.seek(author_offset as usize)?.str()?;
 
         Ok(RdefChunk {
             constant_buffers,
@@ -312,3 +748,145 @@ impl<'a> RdefChunk<'a> {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dr::DxbcModule;
+
+    fn rdef_data(bytes: &[u8]) -> &[u8] {
+        let entry = chunk_directory(bytes).unwrap()
+            .into_iter()
+            .find(|entry| &entry.fourcc == b"RDEF")
+            .unwrap();
+
+        &bytes[entry.offset as usize + 8..][..entry.size as usize]
+    }
+
+    // SM4 reflection data with a single variable of a struct type nested
+    // `levels` deep, where each struct has two members sharing the type of
+    // the next level. With `cycle`, the innermost members refer back to the
+    // outermost type instead.
+    fn nested_rdef(levels: u32, cycle: bool) -> Vec<u8> {
+        const NAME: u32 = 28;
+        const TYPES: u32 = 80;
+
+        let mut words = vec![1, 32, 0, 0, 0xfffe_0400, 0, NAME];
+        words.push(0x78);
+        words.extend_from_slice(&[NAME, 1, 56, 16, 0, 0]);
+        words.extend_from_slice(&[NAME, 0, 16, 0, TYPES, 0]);
+
+        for level in 0..levels {
+            let ty = TYPES + 40 * level;
+            let next = if cycle && level + 1 == levels { TYPES } else { ty + 40 };
+
+            words.extend_from_slice(&[
+                ShaderVariableClass::Struct as u32, 1 | 1 << 16, 2 << 16, ty + 16,
+                NAME, next, 0,
+                NAME, next, 0,
+            ]);
+        }
+        words.extend_from_slice(&[ShaderVariableClass::Scalar as u32 | (ShaderVariableType::Float as u32) << 16, 1 | 1 << 16, 0, 0]);
+
+        words.iter().flat_map(|word| (0..4).map(move |i| (word >> (8 * i)) as u8)).collect()
+    }
+
+    #[test]
+    fn nested_types() {
+        let bytes = nested_rdef(2, false);
+        let rdef = RdefChunk::parse(&mut decoder::Decoder::new(&bytes)).unwrap();
+        let ty = &rdef.constant_buffers[0].variables[0].ty;
+        assert_eq!(ty.members.len(), 2);
+        assert_eq!(ty.members[1].ty.members.len(), 2);
+        assert_eq!(ty.members[1].ty.members[0].ty.class, ShaderVariableClass::Scalar);
+
+        let bytes = nested_rdef(2, true);
+        assert!(RdefChunk::parse(&mut decoder::Decoder::new(&bytes)).is_err());
+
+        // shared types expanding to 2^20 leaves
+        let bytes = nested_rdef(20, false);
+        assert!(RdefChunk::parse(&mut decoder::Decoder::new(&bytes)).is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let bytes = include_bytes!("../../../dxbcd/complex_shader.dxbc");
+        let mut rdef = RdefChunk::parse(&mut decoder::Decoder::new(rdef_data(bytes))).unwrap();
+
+        // the shader has no structs, so add one to cover member descriptions
+        rdef.constant_buffers[0].variables[0].ty = ShaderType {
+            class: ShaderVariableClass::Struct,
+            ty: ShaderVariableType::Void,
+            rows: 1,
+            columns: 4,
+            elements: 0,
+            members: vec![ShaderTypeMember {
+                name: "member",
+                offset: 0,
+                ty: ShaderType {
+                    class: ShaderVariableClass::Vector,
+                    ty: ShaderVariableType::Float,
+                    rows: 1,
+                    columns: 4,
+                    elements: 0,
+                    members: Vec::new(),
+                    name: Some("float4"),
+                },
+            }],
+            name: Some("S"),
+        };
+
+        let mut module = DxbcModule::new();
+        module.write_rdef(&rdef);
+        let written = RdefChunk::parse(&mut decoder::Decoder::new(&module.as_bytes()[8..])).unwrap();
+
+        assert_eq!(format!("{:?}", written), format!("{:?}", rdef));
+    }
+
+    #[test]
+    fn declared_descriptor_sizes() {
+        let bytes = include_bytes!("../../../dxbcd/complex_shader.dxbc");
+        let mut rdef = RdefChunk::parse(&mut decoder::Decoder::new(rdef_data(bytes))).unwrap();
+        assert_eq!(rdef.rd11, Some(Rd11Header::sm5()));
+
+        rdef.rd11 = Some(Rd11Header {
+            resource_binding_size: 48,
+            variable_size: 48,
+            ..Rd11Header::sm5()
+        });
+        // bindings of 40 bytes or more carry a register space and range ID
+        for binding in &mut rdef.resource_bindings {
+            binding.space = Some(0);
+            binding.id = Some(0);
+        }
+
+        let mut module = DxbcModule::new();
+        module.write_rdef(&rdef);
+        let written = RdefChunk::parse(&mut decoder::Decoder::new(&module.as_bytes()[8..])).unwrap();
+
+        assert_eq!(format!("{:?}", written), format!("{:?}", rdef));
+    }
+
+    #[test]
+    fn register_spaces() {
+        let bytes = include_bytes!("../../../dxbcd/complex_shader.dxbc");
+        let mut rdef = RdefChunk::parse(&mut decoder::Decoder::new(rdef_data(bytes))).unwrap();
+        assert!(rdef.resource_bindings.iter().all(|binding| binding.space.is_none()));
+
+        rdef.minor = 1;
+        rdef.rd11 = Some(Rd11Header::sm51());
+        for (idx, binding) in rdef.resource_bindings.iter_mut().enumerate() {
+            binding.space = Some(2);
+            binding.id = Some(idx as u32);
+        }
+
+        let mut module = DxbcModule::new();
+        module.write_rdef(&rdef);
+        let written = RdefChunk::parse(&mut decoder::Decoder::new(&module.as_bytes()[8..])).unwrap();
+
+        assert_eq!(written.rd11, Some(Rd11Header::sm51()));
+        assert_eq!(written.resource_bindings[1].space, Some(2));
+        assert_eq!(written.resource_bindings[1].id, Some(1));
+        assert_eq!(format!("{:?}", written), format!("{:?}", rdef));
+    }
+}
@@ -1,10 +1,8 @@
 use d3d11tokenizedprogramformat::*;
 
 use binary::*;
+use dr::{MinPrecision, ShaderModel};
 
-use std::slice;
-use std::mem;
-use std::marker::PhantomData;
 
 #[repr(u32)]
 #[derive(Debug)]
@@ -14,7 +12,7 @@ pub enum ConstantBufferIndexPattern {
 }
 
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum OperandType {
     Temp = 0,
     Input = 1,
@@ -93,7 +91,7 @@ impl ComponentName {
 pub struct ComponentSwizzle(pub ComponentName, pub ComponentName, pub ComponentName, pub ComponentName);
 
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum IndexDimension {
     D0 = 0,
     D1 = 1,
@@ -111,15 +109,52 @@ pub enum IndexRepresentation {
     Immediate64PlusRelative = 4,
 }
 
-#[derive(Debug)]
-pub enum Immediate<'a> {
-    U32(u32),
-    U64(u64),
-    Relative(OperandToken0<'a>),
-    U32Relative(u32, OperandToken0<'a>),
-    U64Relative(u64, OperandToken0<'a>),
+/// An operand index, e.g. the register number of `r0` or the `r1.x + 3` of
+/// `cb0[r1.x + 3]`. Relative indices hold the operand whose value is added
+/// to the immediate part, which may itself be relatively indexed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Index<O> {
+    Imm32(u32),
+    Imm64(u64),
+    Relative(Box<O>),
+    RelativePlusImm32(Box<O>, u32),
+    RelativePlusImm64(Box<O>, u64),
+}
+
+impl<O> Index<O> {
+    pub fn get_representation(&self) -> IndexRepresentation {
+        match self {
+            Index::Imm32(..) => IndexRepresentation::Immediate32,
+            Index::Imm64(..) => IndexRepresentation::Immediate64,
+            Index::Relative(..) => IndexRepresentation::Relative,
+            Index::RelativePlusImm32(..) => IndexRepresentation::Immediate32PlusRelative,
+            Index::RelativePlusImm64(..) => IndexRepresentation::Immediate64PlusRelative,
+        }
+    }
+
+    /// Returns the immediate part of the index, which is 0 for purely
+    /// relative indices.
+    pub fn get_offset(&self) -> u64 {
+        match *self {
+            Index::Imm32(val) | Index::RelativePlusImm32(_, val) => val as u64,
+            Index::Imm64(val) | Index::RelativePlusImm64(_, val) => val,
+            Index::Relative(..) => 0,
+        }
+    }
+
+    pub fn get_relative(&self) -> Option<&O> {
+        match self {
+            Index::Relative(operand) |
+            Index::RelativePlusImm32(operand, _) |
+            Index::RelativePlusImm64(operand, _) => Some(operand),
+            _ => None,
+        }
+    }
 }
 
+/// Index of a decoded operand.
+pub type Immediate<'a> = Index<OperandToken0<'a>>;
+
 #[repr(u32)]
 #[derive(Debug)]
 pub enum ComponentSelectMode {
@@ -138,7 +173,7 @@ bitflags! {
 }
 
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum NameToken {
     Undefined = 0,
     Position = 1,
@@ -166,38 +201,38 @@ pub enum NameToken {
 }
 
 impl NameToken {
-    pub fn from_word(word: u32) -> Self {
+    pub fn from_word(word: u32) -> Option<Self> {
         match word {
-            0 => NameToken::Undefined,
-            1 => NameToken::Position,
-            2 => NameToken::ClipDistance,
-            3 => NameToken::CullDistance,
-            4 => NameToken::RenderTargetArrayIndex,
-            5 => NameToken::ViewportArrayIndex,
-            6 => NameToken::VertexId,
-            7 => NameToken::PrimitiveId,
-            8 => NameToken::InstanceId,
-            9 => NameToken::IsFrontFace,
-            10 => NameToken::SampleIndex,
-            11 => NameToken::FinalQuadUEq0EdgeTessfactor,
-            12 => NameToken::FinalQuadVEq0EdgeTessfactor,
-            13 => NameToken::FinalQuadUEq1EdgeTessfactor,
-            14 => NameToken::FinalQuadVEq1EdgeTessfactor,
-            15 => NameToken::FinalQuadUInsideTessfactor,
-            16 => NameToken::FinalQuadVInsideTessfactor,
-            17 => NameToken::FinalTriUEq0EdgeTessfactor,
-            18 => NameToken::FinalTriVEq0EdgeTessfactor,
-            19 => NameToken::FinalTriWEq0EdgeTessfactor,
-            20 => NameToken::FinalTriinsidetessfactor,
-            21 => NameToken::FinalLineDetailTessfactor,
-            22 => NameToken::FinalLineDensityTessfactor,
-            _ => unimplemented!(),
+            0 => Some(NameToken::Undefined),
+            1 => Some(NameToken::Position),
+            2 => Some(NameToken::ClipDistance),
+            3 => Some(NameToken::CullDistance),
+            4 => Some(NameToken::RenderTargetArrayIndex),
+            5 => Some(NameToken::ViewportArrayIndex),
+            6 => Some(NameToken::VertexId),
+            7 => Some(NameToken::PrimitiveId),
+            8 => Some(NameToken::InstanceId),
+            9 => Some(NameToken::IsFrontFace),
+            10 => Some(NameToken::SampleIndex),
+            11 => Some(NameToken::FinalQuadUEq0EdgeTessfactor),
+            12 => Some(NameToken::FinalQuadVEq0EdgeTessfactor),
+            13 => Some(NameToken::FinalQuadUEq1EdgeTessfactor),
+            14 => Some(NameToken::FinalQuadVEq1EdgeTessfactor),
+            15 => Some(NameToken::FinalQuadUInsideTessfactor),
+            16 => Some(NameToken::FinalQuadVInsideTessfactor),
+            17 => Some(NameToken::FinalTriUEq0EdgeTessfactor),
+            18 => Some(NameToken::FinalTriVEq0EdgeTessfactor),
+            19 => Some(NameToken::FinalTriWEq0EdgeTessfactor),
+            20 => Some(NameToken::FinalTriinsidetessfactor),
+            21 => Some(NameToken::FinalLineDetailTessfactor),
+            22 => Some(NameToken::FinalLineDensityTessfactor),
+            _ => None,
         }
     }
 }
 
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum OperandModifier {
     None,
     Neg,
@@ -218,7 +253,7 @@ impl OperandModifier {
 }
 
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum SamplerMode {
     Default,
     Comparison,
@@ -226,18 +261,18 @@ pub enum SamplerMode {
 }
 
 impl SamplerMode {
-    pub fn from_word(word: u32) -> Self {
+    pub fn from_word(word: u32) -> Option<Self> {
         match word {
-            0 => SamplerMode::Default,
-            1 => SamplerMode::Comparison,
-            2 => SamplerMode::Mono,
-            _ => unreachable!(),
+            0 => Some(SamplerMode::Default),
+            1 => Some(SamplerMode::Comparison),
+            2 => Some(SamplerMode::Mono),
+            _ => None,
         }
     }
 }
 
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum TestBoolean {
     Zero,
     NonZero,
@@ -253,70 +288,70 @@ impl TestBoolean {
     }
 }
 
-#[repr(C)]
+/// The opcode token of an instruction, followed by its extended opcode
+/// tokens.
 #[derive(Copy, Clone)]
 pub struct OpcodeToken0<'a> {
-    // TODO: these should probably be slices
-    pub word: *const u32,
-    _phantom: PhantomData<&'a ()>,
+    pub words: &'a [u32],
 }
 
 impl<'a> OpcodeToken0<'a> {
-    pub fn from_word(word: *const u32) -> Self {
+    /// `words` must start at the opcode token.
+    pub fn from_words(words: &'a [u32]) -> Self {
         OpcodeToken0 {
-            word,
-            _phantom: PhantomData,
+            words,
         }
     }
 
+    pub fn get_word(&self) -> u32 {
+        self.words[0]
+    }
+
     pub fn is_extended(&self) -> bool {
-        DECODE_IS_D3D10_SB_OPCODE_EXTENDED(unsafe { *self.word }) != 0
+        DECODE_IS_D3D10_SB_OPCODE_EXTENDED(self.get_word()) != 0
     }
 
     pub fn is_saturated(&self) -> bool {
-        DECODE_IS_D3D10_SB_INSTRUCTION_SATURATE_ENABLED(unsafe { *self.word }) != 0
+        DECODE_IS_D3D10_SB_INSTRUCTION_SATURATE_ENABLED(self.get_word()) != 0
     }
 
     pub fn get_test_type(&self) -> TestBoolean {
-        TestBoolean::from_word(unsafe { *self.word })
+        TestBoolean::from_word(self.get_word())
     }
 
     pub fn get_extended_opcode(&self) -> Option<OpcodeToken1<'a>> {
-        if self.is_extended() {
-            Some(OpcodeToken1 {
-                word: unsafe { self.word.offset(1) },
-                _phantom: self._phantom,
-            })
+        if self.is_extended() && self.words.len() > 1 {
+            Some(OpcodeToken1::from_words(&self.words[1..]))
         } else {
             None
         }
     }
 
     pub fn get_opcode_type(&self) -> u32 {
-        DECODE_D3D10_SB_OPCODE_TYPE(unsafe { *self.word })
+        DECODE_D3D10_SB_OPCODE_TYPE(self.get_word())
     }
 
     pub fn get_instruction_length(&self) -> u32 {
-        DECODE_D3D10_SB_TOKENIZED_INSTRUCTION_LENGTH(unsafe { *self.word })
+        DECODE_D3D10_SB_TOKENIZED_INSTRUCTION_LENGTH(self.get_word())
     }
 
-    pub fn get_resource_dimension(&self) -> ResourceDimension {
-        ResourceDimension::from_word(DECODE_D3D10_SB_RESOURCE_DIMENSION(unsafe { *(self.word.offset(0)) }))
+    pub fn get_resource_dimension(&self) -> Option<ResourceDimension> {
+        ResourceDimension::from_word(DECODE_D3D10_SB_RESOURCE_DIMENSION(self.get_word()))
     }
 
-    pub fn get_sampler_mode(&self) -> SamplerMode {
-        SamplerMode::from_word(DECODE_D3D10_SB_SAMPLER_MODE(unsafe { *(self.word.offset(0)) }))
+    pub fn get_sampler_mode(&self) -> Option<SamplerMode> {
+        SamplerMode::from_word(DECODE_D3D10_SB_SAMPLER_MODE(self.get_word()))
     }
 
-    pub fn get_interpolation_mode(&self) -> InterpolationMode {
-        InterpolationMode::from_word(DECODE_D3D10_SB_INPUT_INTERPOLATION_MODE(unsafe { *self.word }))
+    pub fn get_interpolation_mode(&self) -> Option<InterpolationMode> {
+        InterpolationMode::from_word(DECODE_D3D10_SB_INPUT_INTERPOLATION_MODE(self.get_word()))
     }
 }
 
 impl<'a> fmt::Debug for OpcodeToken0<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("OpcodeToken0")
-            .field("Raw", &unsafe { *self.word })
+            .field("Raw", &self.get_word())
             .field("Type", &self.get_opcode_type())
             .field("InstructionLength", &self.get_instruction_length())
             .field("IsSaturated", &self.is_saturated())
@@ -327,7 +362,7 @@ impl<'a> fmt::Debug for OpcodeToken0<'a> {
 }
 
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum InterpolationMode {
     Undefined = 0,
     Constant = 1,
@@ -340,15 +375,163 @@ pub enum InterpolationMode {
 }
 
 impl InterpolationMode {
-    pub fn from_word(word: u32) -> Self {
-        assert!(word <= InterpolationMode::LinearNoPerspectiveSample as u32);
+    pub fn from_word(word: u32) -> Option<Self> {
+        match word {
+            0 => Some(InterpolationMode::Undefined),
+            1 => Some(InterpolationMode::Constant),
+            2 => Some(InterpolationMode::Linear),
+            3 => Some(InterpolationMode::LinearCentroid),
+            4 => Some(InterpolationMode::LinearNoPerspective),
+            5 => Some(InterpolationMode::LinearNoPerspectiveCentroid),
+            6 => Some(InterpolationMode::LinearSample),
+            7 => Some(InterpolationMode::LinearNoPerspectiveSample),
+            _ => None,
+        }
+    }
+}
+
+/// Input primitive of a geometry shader, or a patch with 1 to 32 control
+/// points as consumed by hull shaders.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Primitive {
+    Undefined,
+    Point,
+    Line,
+    Triangle,
+    LineAdj,
+    TriangleAdj,
+    Patch(u32),
+}
+
+impl Primitive {
+    pub fn from_word(word: u32) -> Option<Self> {
+        match word {
+            0 => Some(Primitive::Undefined),
+            1 => Some(Primitive::Point),
+            2 => Some(Primitive::Line),
+            3 => Some(Primitive::Triangle),
+            6 => Some(Primitive::LineAdj),
+            7 => Some(Primitive::TriangleAdj),
+            8..=39 => Some(Primitive::Patch(word - 7)),
+            _ => None,
+        }
+    }
 
-        unsafe { mem::transmute(word) }
+    pub fn to_word(&self) -> u32 {
+        match *self {
+            Primitive::Undefined => 0,
+            Primitive::Point => 1,
+            Primitive::Line => 2,
+            Primitive::Triangle => 3,
+            Primitive::LineAdj => 6,
+            Primitive::TriangleAdj => 7,
+            Primitive::Patch(control_points) => control_points + 7,
+        }
     }
 }
 
 #[repr(u32)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PrimitiveTopology {
+    Undefined = 0,
+    PointList = 1,
+    LineList = 2,
+    LineStrip = 3,
+    TriangleList = 4,
+    TriangleStrip = 5,
+    LineListAdj = 10,
+    LineStripAdj = 11,
+    TriangleListAdj = 12,
+    TriangleStripAdj = 13,
+}
+
+impl PrimitiveTopology {
+    pub fn from_word(word: u32) -> Option<Self> {
+        match word {
+            0 => Some(PrimitiveTopology::Undefined),
+            1 => Some(PrimitiveTopology::PointList),
+            2 => Some(PrimitiveTopology::LineList),
+            3 => Some(PrimitiveTopology::LineStrip),
+            4 => Some(PrimitiveTopology::TriangleList),
+            5 => Some(PrimitiveTopology::TriangleStrip),
+            10 => Some(PrimitiveTopology::LineListAdj),
+            11 => Some(PrimitiveTopology::LineStripAdj),
+            12 => Some(PrimitiveTopology::TriangleListAdj),
+            13 => Some(PrimitiveTopology::TriangleStripAdj),
+            _ => None,
+        }
+    }
+}
+
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TessellatorDomain {
+    Undefined = 0,
+    Isoline = 1,
+    Tri = 2,
+    Quad = 3,
+}
+
+impl TessellatorDomain {
+    pub fn from_word(word: u32) -> Option<Self> {
+        match word {
+            0 => Some(TessellatorDomain::Undefined),
+            1 => Some(TessellatorDomain::Isoline),
+            2 => Some(TessellatorDomain::Tri),
+            3 => Some(TessellatorDomain::Quad),
+            _ => None,
+        }
+    }
+}
+
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TessellatorPartitioning {
+    Undefined = 0,
+    Integer = 1,
+    Pow2 = 2,
+    FractionalOdd = 3,
+    FractionalEven = 4,
+}
+
+impl TessellatorPartitioning {
+    pub fn from_word(word: u32) -> Option<Self> {
+        match word {
+            0 => Some(TessellatorPartitioning::Undefined),
+            1 => Some(TessellatorPartitioning::Integer),
+            2 => Some(TessellatorPartitioning::Pow2),
+            3 => Some(TessellatorPartitioning::FractionalOdd),
+            4 => Some(TessellatorPartitioning::FractionalEven),
+            _ => None,
+        }
+    }
+}
+
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TessellatorOutputPrimitive {
+    Undefined = 0,
+    Point = 1,
+    Line = 2,
+    TriangleCw = 3,
+    TriangleCcw = 4,
+}
+
+impl TessellatorOutputPrimitive {
+    pub fn from_word(word: u32) -> Option<Self> {
+        match word {
+            0 => Some(TessellatorOutputPrimitive::Undefined),
+            1 => Some(TessellatorOutputPrimitive::Point),
+            2 => Some(TessellatorOutputPrimitive::Line),
+            3 => Some(TessellatorOutputPrimitive::TriangleCw),
+            4 => Some(TessellatorOutputPrimitive::TriangleCcw),
+            _ => None,
+        }
+    }
+}
+
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ResourceDimension {
     Unknown = 0,
     Buffer = 1,
@@ -366,28 +549,28 @@ pub enum ResourceDimension {
 }
 
 impl ResourceDimension {
-    pub fn from_word(word: u32) -> Self {
+    pub fn from_word(word: u32) -> Option<Self> {
         match word {
-            0 => ResourceDimension::Unknown,
-            1 => ResourceDimension::Buffer,
-            2 => ResourceDimension::Texture1D,
-            3 => ResourceDimension::Texture2D,
-            4 => ResourceDimension::Texture2DMS,
-            5 => ResourceDimension::Texture3D,
-            6 => ResourceDimension::TextureCube,
-            7 => ResourceDimension::Texture1DArray,
-            8 => ResourceDimension::Texture2DArray,
-            9 => ResourceDimension::Texture2DMSArray,
-            10 => ResourceDimension::TextureCubeArray,
-            11 => ResourceDimension::RawBuffer,
-            12 => ResourceDimension::StructuredBuffer,
-            _ => unreachable!()
+            0 => Some(ResourceDimension::Unknown),
+            1 => Some(ResourceDimension::Buffer),
+            2 => Some(ResourceDimension::Texture1D),
+            3 => Some(ResourceDimension::Texture2D),
+            4 => Some(ResourceDimension::Texture2DMS),
+            5 => Some(ResourceDimension::Texture3D),
+            6 => Some(ResourceDimension::TextureCube),
+            7 => Some(ResourceDimension::Texture1DArray),
+            8 => Some(ResourceDimension::Texture2DArray),
+            9 => Some(ResourceDimension::Texture2DMSArray),
+            10 => Some(ResourceDimension::TextureCubeArray),
+            11 => Some(ResourceDimension::RawBuffer),
+            12 => Some(ResourceDimension::StructuredBuffer),
+            _ => None,
         }
     }
 }
 
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ExtendedOpcodeType {
     Empty = 0,
     SampleControls = 1,
@@ -396,19 +579,19 @@ pub enum ExtendedOpcodeType {
 }
 
 impl ExtendedOpcodeType {
-    pub fn from_word(word: u32) -> Self {
+    pub fn from_word(word: u32) -> Option<Self> {
         match DECODE_D3D10_SB_EXTENDED_OPCODE_TYPE(word) {
-            0 => ExtendedOpcodeType::Empty,
-            1 => ExtendedOpcodeType::SampleControls,
-            2 => ExtendedOpcodeType::ResourceDim,
-            3 => ExtendedOpcodeType::ResourceReturnType,
-            _ => unreachable!(),
+            0 => Some(ExtendedOpcodeType::Empty),
+            1 => Some(ExtendedOpcodeType::SampleControls),
+            2 => Some(ExtendedOpcodeType::ResourceDim),
+            3 => Some(ExtendedOpcodeType::ResourceReturnType),
+            _ => None,
         }
     }
 }
 
 #[repr(u32)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ResourceReturnType {
     Unorm = 1,
     Snorm = 2,
@@ -422,76 +605,137 @@ pub enum ResourceReturnType {
 }
 
 impl ResourceReturnType {
-    pub fn from_word(word: u32) -> Self {
+    pub fn from_word(word: u32) -> Option<Self> {
         match word {
-            1 => ResourceReturnType::Unorm,
-            2 => ResourceReturnType::Snorm,
-            3 => ResourceReturnType::Sint,
-            4 => ResourceReturnType::Uint,
-            5 => ResourceReturnType::Float,
-            6 => ResourceReturnType::Mixed,
-            7 => ResourceReturnType::Double,
-            8 => ResourceReturnType::Continued,
-            9 => ResourceReturnType::Unused,
-            _ => unreachable!(),
+            1 => Some(ResourceReturnType::Unorm),
+            2 => Some(ResourceReturnType::Snorm),
+            3 => Some(ResourceReturnType::Sint),
+            4 => Some(ResourceReturnType::Uint),
+            5 => Some(ResourceReturnType::Float),
+            6 => Some(ResourceReturnType::Mixed),
+            7 => Some(ResourceReturnType::Double),
+            8 => Some(ResourceReturnType::Continued),
+            9 => Some(ResourceReturnType::Unused),
+            _ => None,
         }
     }
 }
 
-#[repr(C)]
 #[derive(Debug, Copy, Clone)]
-pub struct ResourceReturnTypeToken0<'a> {
-    pub word: *const u32,
-    _phantom: PhantomData<&'a ()>,
+pub struct ResourceReturnTypeToken0 {
+    pub word: u32,
 }
 
-impl<'a> ResourceReturnTypeToken0<'a> {
-    pub fn from_word(word: *const u32) -> Self {
+impl ResourceReturnTypeToken0 {
+    pub fn from_word(word: u32) -> Self {
         ResourceReturnTypeToken0 {
             word,
-            _phantom: PhantomData,
         }
     }
 
-    pub fn get_return_type(&self, name: ComponentName) -> ResourceReturnType {
-        ResourceReturnType::from_word(DECODE_D3D10_SB_RESOURCE_RETURN_TYPE(unsafe { *(self.word.offset(0)) }, name as u32))
+    pub fn get_return_type(&self, name: ComponentName) -> Option<ResourceReturnType> {
+        ResourceReturnType::from_word(DECODE_D3D10_SB_RESOURCE_RETURN_TYPE(self.word, name as u32))
     }
 }
 
-#[repr(C)]
+/// Decoded extended opcode token.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ExtendedOpcode {
+    Empty,
+    /// Immediate `(u, v, w)` texel offsets, each in the range `-8..=7`
+    SampleControls(i8, i8, i8),
+    /// Resource dimension and structure stride
+    ResourceDim(ResourceDimension, u32),
+    ResourceReturnType(ResourceReturnType, ResourceReturnType, ResourceReturnType, ResourceReturnType),
+}
+
+/// An extended opcode token, followed by any further extended opcode tokens.
 #[derive(Copy, Clone)]
 pub struct OpcodeToken1<'a> {
-    pub word: *const u32,
-    _phantom: PhantomData<&'a ()>,
+    pub words: &'a [u32],
 }
 
 impl<'a> OpcodeToken1<'a> {
-    pub fn from_word(word: *const u32) -> Self {
+    /// `words` must start at the extended opcode token.
+    pub fn from_words(words: &'a [u32]) -> Self {
         OpcodeToken1 {
-            word,
-            _phantom: PhantomData,
+            words,
         }
     }
 
-    pub fn get_extended_opcode_type(&self) -> ExtendedOpcodeType {
-        ExtendedOpcodeType::from_word(DECODE_D3D10_SB_TOKENIZED_INSTRUCTION_LENGTH(unsafe { *self.word }))
+    pub fn get_word(&self) -> u32 {
+        self.words[0]
+    }
+
+    pub fn get_extended_opcode_type(&self) -> Option<ExtendedOpcodeType> {
+        ExtendedOpcodeType::from_word(self.get_word())
+    }
+
+    /// Returns the signed `(u, v, w)` texel offsets of a sample controls
+    /// token.
+    pub fn get_texel_offsets(&self) -> (i8, i8, i8) {
+        let offset = |coord| {
+            let bits = DECODE_IMMEDIATE_D3D10_SB_ADDRESS_OFFSET(coord, self.get_word());
+
+            // sign extend from 4 bits
+            ((bits << 28) as i32 >> 28) as i8
+        };
+
+        (
+            offset(D3D10_SB_IMMEDIATE_ADDRESS_OFFSET_U),
+            offset(D3D10_SB_IMMEDIATE_ADDRESS_OFFSET_V),
+            offset(D3D10_SB_IMMEDIATE_ADDRESS_OFFSET_W),
+        )
+    }
+
+    pub fn get_resource_dimension(&self) -> Option<ResourceDimension> {
+        ResourceDimension::from_word(DECODE_D3D11_SB_EXTENDED_RESOURCE_DIMENSION(self.get_word()))
+    }
+
+    pub fn get_structure_stride(&self) -> u32 {
+        DECODE_D3D11_SB_EXTENDED_RESOURCE_DIMENSION_STRUCTURE_STRIDE(self.get_word())
+    }
+
+    pub fn get_return_type(&self, name: ComponentName) -> Option<ResourceReturnType> {
+        ResourceReturnType::from_word(DECODE_D3D11_SB_EXTENDED_RESOURCE_RETURN_TYPE(self.get_word(), name as u32))
+    }
+
+    /// Decodes this token according to its type, or returns `None` if its
+    /// type, resource dimension or return types are unknown.
+    pub fn get_extended_opcode_data(&self) -> Option<ExtendedOpcode> {
+        Some(match self.get_extended_opcode_type()? {
+            ExtendedOpcodeType::Empty => ExtendedOpcode::Empty,
+            ExtendedOpcodeType::SampleControls => {
+                let (u, v, w) = self.get_texel_offsets();
+
+                ExtendedOpcode::SampleControls(u, v, w)
+            }
+            ExtendedOpcodeType::ResourceDim => {
+                ExtendedOpcode::ResourceDim(self.get_resource_dimension()?, self.get_structure_stride())
+            }
+            ExtendedOpcodeType::ResourceReturnType => {
+                ExtendedOpcode::ResourceReturnType(
+                    self.get_return_type(ComponentName::X)?,
+                    self.get_return_type(ComponentName::Y)?,
+                    self.get_return_type(ComponentName::Z)?,
+                    self.get_return_type(ComponentName::W)?,
+                )
+            }
+        })
     }
 
     pub fn get_opcode_modifier(&self) -> u32 {
-        DECODE_D3D10_SB_TOKENIZED_INSTRUCTION_LENGTH(unsafe { *self.word })
+        DECODE_D3D10_SB_TOKENIZED_INSTRUCTION_LENGTH(self.get_word())
     }
 
 
     pub fn is_extended(&self) -> bool {
-        DECODE_IS_D3D10_SB_OPCODE_EXTENDED(unsafe { *self.word }) != 0
+        DECODE_IS_D3D10_SB_OPCODE_EXTENDED(self.get_word()) != 0
     }
 
     pub fn get_extended_opcode(&self) -> Option<OpcodeToken1<'a>> {
-        if self.is_extended() {
-            Some(OpcodeToken1 {
-                word: unsafe { self.word.offset(1) },
-                _phantom: self._phantom,
-            })
+        if self.is_extended() && self.words.len() > 1 {
+            Some(OpcodeToken1::from_words(&self.words[1..]))
         } else {
             None
         }
@@ -508,266 +752,317 @@ impl<'a> fmt::Debug for OpcodeToken1<'a> {
     }
 }
 
-#[repr(C)]
-#[derive(Copy, Clone)]
-pub struct OperandToken1<'a> {
-    pub word: *const u32,
-    _phantom: PhantomData<&'a ()>,
+#[derive(Debug, Copy, Clone)]
+pub struct OperandToken1 {
+    pub word: u32,
 }
 
-impl<'a> OperandToken1<'a> {
-    pub fn from_word(word: *const u32) -> Self {
+impl OperandToken1 {
+    pub fn from_word(word: u32) -> Self {
         OperandToken1 {
             word,
-            _phantom: PhantomData,
         }
     }
 
     pub fn get_operand_modifier(&self) -> OperandModifier {
-        OperandModifier::from_word(unsafe { *self.word })
+        OperandModifier::from_word(self.word)
+    }
+
+    /// Returns the minimum precision the operand may be evaluated at, as
+    /// allowed by `DclGlobalFlags::is_minimum_precision_enabled`.
+    pub fn get_min_precision(&self) -> Option<MinPrecision> {
+        MinPrecision::from_word(DECODE_D3D11_SB_OPERAND_MIN_PRECISION(self.word))
     }
 }
 
-#[repr(C)]
-#[derive(Copy, Clone)]
+/// Maximum nesting of relative indices, e.g. 1 for `cb0[r0.x]` and 2 for
+/// `cb0[x0[r0.x].x]`, bounding the recursion on malformed operands.
+const MAX_RELATIVE_DEPTH: u32 = 8;
+
+/// An operand token together with its extended operand token and indices.
+#[derive(Copy, Clone, PartialEq)]
 pub struct OperandToken0<'a> {
-    pub word: *const u32,
-    _phantom: PhantomData<&'a ()>,
+    pub words: &'a [u32],
 }
 
 impl<'a> OperandToken0<'a> {
-    pub fn from_word(word: *const u32) -> Self {
+    /// `words` must start at the operand token and contain all of its
+    /// indices, as returned by `OperandToken0::parse`.
+    pub fn from_words(words: &'a [u32]) -> Self {
         OperandToken0 {
-            word,
-            _phantom: PhantomData,
+            words,
         }
     }
 
-    pub fn parse<'b>(decoder: &mut decoder::Decoder<'b>) -> OperandToken0<'b> {
-        let operand = OperandToken0::from_word(decoder.read_u32_address());
+    pub fn parse<'b>(decoder: &mut decoder::WordDecoder<'b>) -> DecoderResult<OperandToken0<'b>> {
+        OperandToken0::parse_nested(decoder, 0)
+    }
+
+    // `depth` counts the relative operands this operand is nested in
+    fn parse_nested<'b>(decoder: &mut decoder::WordDecoder<'b>, depth: u32) -> DecoderResult<OperandToken0<'b>> {
+        let start = decoder.get_offset();
+        let word = decoder.read_u32()?;
 
-        if operand.is_extended() {
-            decoder.skip(4);
+        if depth > MAX_RELATIVE_DEPTH {
+            return Err(Error::InvalidOperand(4 * start));
         }
 
-        let len = match operand.get_index_dimension() {
-            IndexDimension::D0 => {
-                let ty = operand.get_operand_type();
-
-                match ty {
-                    OperandType::Immediate32 | OperandType::Immediate64 => operand.get_num_components_u32(),
-                    _ => 0
-                }
+        if DECODE_IS_D3D10_SB_OPERAND_EXTENDED(word) != 0 {
+            if DECODE_D3D10_SB_OPERAND_MODIFIER(decoder.read_u32()?) > D3D10_SB_OPERAND_MODIFIER_ABSNEG {
+                return Err(Error::InvalidOperand(4 * start));
             }
-            IndexDimension::D1 => 1,
-            IndexDimension::D2 => 2,
-            IndexDimension::D3 => 3,
-        };
-
+        }
 
-        for i in 0..len {
-            let repr = operand.get_index_representation(i);
+        if DECODE_D3D10_SB_OPERAND_TYPE(word) > D3D11_SB_OPERAND_TYPE_CYCLE_COUNTER {
+            return Err(Error::InvalidOperand(4 * start));
+        }
 
-            match repr {
-                IndexRepresentation::Immediate32 => {
-                    decoder.skip(4);
-                },
-                IndexRepresentation::Immediate64 => {
-                    decoder.skip(8);
-                },
-                IndexRepresentation::Relative => {
-                    let _ = OperandToken0::parse(decoder);
+        match DECODE_D3D10_SB_OPERAND_TYPE(word) {
+            D3D10_SB_OPERAND_TYPE_IMMEDIATE32 |
+            D3D10_SB_OPERAND_TYPE_IMMEDIATE64 => {
+                let count = match DECODE_D3D10_SB_OPERAND_NUM_COMPONENTS(word) {
+                    D3D10_SB_OPERAND_0_COMPONENT => 0,
+                    D3D10_SB_OPERAND_1_COMPONENT => 1,
+                    D3D10_SB_OPERAND_4_COMPONENT => 4,
+                    _ => return Err(Error::InvalidOperand(4 * start)),
+                };
+
+                if DECODE_D3D10_SB_OPERAND_TYPE(word) == D3D10_SB_OPERAND_TYPE_IMMEDIATE64 {
+                    decoder.skip(2 * count)?;
+                } else {
+                    decoder.skip(count)?;
+                }
+            }
+            _ => {
+                for i in 0..DECODE_D3D10_SB_OPERAND_INDEX_DIMENSION(word) {
+                    match DECODE_D3D10_SB_OPERAND_INDEX_REPRESENTATION(i, word) {
+                        D3D10_SB_OPERAND_INDEX_IMMEDIATE32 => {
+                            decoder.skip(1)?;
+                        },
+                        D3D10_SB_OPERAND_INDEX_IMMEDIATE64 => {
+                            decoder.skip(2)?;
+                        },
+                        D3D10_SB_OPERAND_INDEX_RELATIVE => {
+                            let _ = OperandToken0::parse_nested(decoder, depth + 1)?;
+                        }
+                        D3D10_SB_OPERAND_INDEX_IMMEDIATE32_PLUS_RELATIVE => {
+                            decoder.skip(1)?;
+                            let _ = OperandToken0::parse_nested(decoder, depth + 1)?;
+                        },
+                        D3D10_SB_OPERAND_INDEX_IMMEDIATE64_PLUS_RELATIVE => {
+                            decoder.skip(2)?;
+                            let _ = OperandToken0::parse_nested(decoder, depth + 1)?;
+                        },
+                        _ => return Err(Error::InvalidOperand(4 * start)),
+                    }
                 }
-                IndexRepresentation::Immediate32PlusRelative => {
-                    decoder.skip(4);
-                    let _ = OperandToken0::parse(decoder);
-                },
-                IndexRepresentation::Immediate64PlusRelative => {
-                    decoder.skip(8);
-                    let _ = OperandToken0::parse(decoder);
-                },
             }
         }
 
-        operand
+        Ok(OperandToken0::from_words(decoder.words_from(start)))
     }
 
-    pub fn len(&self) -> u32 {
-        let mut len = 0;
-        if self.is_extended() {
-            len += 1;
-        }
-
-        let dim_len = match self.get_index_dimension() {
-            IndexDimension::D0 => {
-                let ty = self.get_operand_type();
-
-                match ty {
-                    OperandType::Immediate32 | OperandType::Immediate64 => self.get_num_components_u32(),
-                    _ => 0
-                }
-            }
-            IndexDimension::D1 => 1,
-            IndexDimension::D2 => 2,
-            IndexDimension::D3 => 3,
-        };
+    pub fn get_word(&self) -> u32 {
+        self.words[0]
+    }
 
+    /// Returns the length of the operand in words, or `None` if `words` is
+    /// too short to hold all of its indices.
+    pub fn len(&self) -> Option<u32> {
+        let mut len = self.get_immediate_offset();
 
-        for i in 0..dim_len {
-            let repr = self.get_index_representation(i);
+        if self.is_immediate() {
+            let size = match self.get_operand_type() {
+                OperandType::Immediate64 => 2,
+                _ => 1,
+            };
 
-            match repr {
-                IndexRepresentation::Immediate32 => {
-                    len += 1;
-                },
-                IndexRepresentation::Immediate64 => {
-                    len += 2;
-                },
-                IndexRepresentation::Relative => {
-                    len += OperandToken0::from_word(unsafe { self.word.offset(len as isize) }).len();
-                }
-                IndexRepresentation::Immediate32PlusRelative => {
-                    len += 1;
-                    len += OperandToken0::from_word(unsafe { self.word.offset(len as isize) }).len();
-                },
-                IndexRepresentation::Immediate64PlusRelative => {
-                    len += 2;
-                    len += OperandToken0::from_word(unsafe { self.word.offset(len as isize) }).len();
-                },
+            len += size * self.get_num_components_u32()?;
+        } else {
+            for i in 0..self.get_index_count() {
+                len += self.get_index_len(i, len)?;
             }
         }
 
-        len
+        if len as usize <= self.words.len() {
+            Some(len)
+        } else {
+            None
+        }
     }
 
-    pub fn get_extended_operand(&self) -> Option<OperandToken1<'a>> {
+    pub fn get_extended_operand(&self) -> Option<OperandToken1> {
         if self.is_extended() {
-            Some(OperandToken1 {
-                word: unsafe { self.word.offset(1) },
-                _phantom: self._phantom,
-            })
+            Some(OperandToken1::from_word(self.words[1]))
         } else {
             None
         }
     }
 
-    fn get_immediate_offset(&self) -> *const u32 {
+    /// Returns the minimum precision of the operand, which is only set
+    /// through an extended operand token.
+    pub fn get_min_precision(&self) -> MinPrecision {
+        self.get_extended_operand()
+            .and_then(|operand| operand.get_min_precision())
+            .unwrap_or(MinPrecision::Default)
+    }
+
+    fn get_immediate_offset(&self) -> u32 {
         if self.is_extended() {
-            unsafe { self.word.offset(2) }
+            2
         } else {
-            unsafe { self.word.offset(1) }
+            1
         }
     }
 
-    pub fn get_immediates(&self) -> Vec<Immediate<'a>> {
-        let mut immediates = Vec::new();
+    fn is_immediate(&self) -> bool {
+        match DECODE_D3D10_SB_OPERAND_TYPE(self.get_word()) {
+            D3D10_SB_OPERAND_TYPE_IMMEDIATE32 |
+            D3D10_SB_OPERAND_TYPE_IMMEDIATE64 => true,
+            _ => false,
+        }
+    }
 
-        let len = match self.get_index_dimension() {
-            IndexDimension::D0 => {
-                let ty = self.get_operand_type();
+    fn get_index_count(&self) -> u32 {
+        if self.is_immediate() {
+            return self.get_num_components_u32().unwrap_or(0);
+        }
 
-                match ty {
-                    OperandType::Immediate32 | OperandType::Immediate64 => self.get_num_components_u32(),
-                    _ => 0
-                }
-            },
+        match self.get_index_dimension() {
+            IndexDimension::D0 => 0,
             IndexDimension::D1 => 1,
             IndexDimension::D2 => 2,
             IndexDimension::D3 => 3,
+        }
+    }
+
+    // length in words of the index at `index`, which starts at word `offset`
+    fn get_index_len(&self, index: u32, offset: u32) -> Option<u32> {
+        let relative_len = |offset: u32| {
+            self.get_relative(offset).and_then(|operand| operand.len())
+        };
+
+        match self.get_index_representation(index) {
+            IndexRepresentation::Immediate32 => Some(1),
+            IndexRepresentation::Immediate64 => Some(2),
+            IndexRepresentation::Relative => relative_len(offset),
+            IndexRepresentation::Immediate32PlusRelative => relative_len(offset + 1).map(|len| 1 + len),
+            IndexRepresentation::Immediate64PlusRelative => relative_len(offset + 2).map(|len| 2 + len),
+        }
+    }
+
+    fn get_relative(&self, offset: u32) -> Option<OperandToken0<'a>> {
+        let words = match self.words.get(offset as usize..) {
+            Some(words) if !words.is_empty() => words,
+            _ => return None,
         };
+        let len = OperandToken0::from_words(words).len()? as usize;
+
+        Some(OperandToken0::from_words(&words[..len]))
+    }
+
+    fn read_u32(&self, offset: u32) -> Option<u32> {
+        self.words.get(offset as usize).cloned()
+    }
 
-        for i in 0..len {
-            immediates.push(self.get_immediate(i));
+    fn read_u64(&self, offset: u32) -> Option<u64> {
+        let low = self.read_u32(offset)?;
+        let high = self.read_u32(offset + 1)?;
+
+        Some((low as u64) | ((high as u64) << 32))
+    }
+
+    /// Returns the decoded indices, stopping at the first index missing
+    /// from a truncated operand.
+    pub fn get_immediates(&self) -> Vec<Immediate<'a>> {
+        let mut immediates = Vec::new();
+
+        for i in 0..self.get_index_count() {
+            match self.get_immediate(i) {
+                Some(immediate) => immediates.push(immediate),
+                None => break,
+            }
         }
 
         immediates
     }
 
-    pub fn get_immediate(&self, index: u32) -> Immediate<'a> {
-        let len = match self.get_index_dimension() {
-            IndexDimension::D0 => {
-                let ty = self.get_operand_type();
+    /// Returns the index at `index`, or `None` if the operand has fewer
+    /// indices or its words are truncated.
+    pub fn get_immediate(&self, index: u32) -> Option<Immediate<'a>> {
+        if index >= self.get_index_count() {
+            return None;
+        }
+
+        let imm = self.get_immediate_offset();
 
-                match ty {
-                    OperandType::Immediate32 | OperandType::Immediate64 => self.get_num_components_u32(),
-                    _ => 0
-                }
+        if self.is_immediate() {
+            return match self.get_operand_type() {
+                OperandType::Immediate64 => self.read_u64(imm + 2 * index).map(Index::Imm64),
+                _ => self.read_u32(imm + index).map(Index::Imm32),
+            };
+        }
+
+        let mut offset = imm;
+
+        for i in 0..index {
+            offset += self.get_index_len(i, offset)?;
+        }
+
+        let index = match self.get_index_representation(index) {
+            IndexRepresentation::Immediate32 => {
+                Index::Imm32(self.read_u32(offset)?)
+            },
+            IndexRepresentation::Immediate64 => {
+                Index::Imm64(self.read_u64(offset)?)
+            },
+            IndexRepresentation::Relative => {
+                Index::Relative(Box::new(self.get_relative(offset)?))
+            },
+            IndexRepresentation::Immediate32PlusRelative => {
+                Index::RelativePlusImm32(
+                    Box::new(self.get_relative(offset + 1)?),
+                    self.read_u32(offset)?
+                )
+            },
+            IndexRepresentation::Immediate64PlusRelative => {
+                Index::RelativePlusImm64(
+                    Box::new(self.get_relative(offset + 2)?),
+                    self.read_u64(offset)?
+                )
             },
-            IndexDimension::D1 => 1,
-            IndexDimension::D2 => 2,
-            IndexDimension::D3 => 3,
         };
 
-        let imm = self.get_immediate_offset();
-        let mut offset = 0;
-
-        use self::IndexRepresentation::*;
-
-        for i in 0..len {
-            let repr = self.get_index_representation(i);
-
-            if i == index {
-                match repr {
-                    Immediate32 => {
-                        return Immediate::U32(
-                            unsafe { *imm.offset(offset as isize) }
-                        );
-                    },
-                    Immediate64 => {
-                        return Immediate::U64(
-                            unsafe { *(imm.offset(offset as isize) as *const u64) }
-                        );
-                    },
-                    Relative => {
-                        return Immediate::Relative(
-                            OperandToken0::from_word(unsafe { imm.offset(offset as isize) })
-                        );
-                    },
-                    Immediate32PlusRelative => {
-                        return Immediate::U32Relative(
-                            unsafe { *imm.offset(offset as isize) },
-                            OperandToken0::from_word(unsafe { imm.offset(1 + offset as isize) })
-                        );
-                    },
-                    Immediate64PlusRelative => {
-                        return Immediate::U64Relative(
-                            unsafe { *(imm.offset(offset as isize) as *const u64) },
-                            OperandToken0::from_word(unsafe { imm.offset(2 + offset as isize) })
-                        );
-                    },
-                }
-            } else {
-                match repr {
-                    Immediate32 => {
-                        offset += 1;
-                    },
-                    Immediate64 => {
-                        offset += 2;
-                    },
-                    Relative => {
-                        offset += OperandToken0::from_word(unsafe { imm.offset(offset as isize) }).len();
-                    },
-                    Immediate32PlusRelative => {
-                        offset += 1;
-                        offset += OperandToken0::from_word(unsafe { imm.offset(offset as isize) }).len();
-                    },
-                    Immediate64PlusRelative => {
-                        offset += 2;
-                        offset += OperandToken0::from_word(unsafe { imm.offset(offset as isize) }).len();
-                    },
-                }
-            }
-        }
+        Some(index)
+    }
 
-        unreachable!()
+    /// Returns the register number at `index`, or `None` if the index is
+    /// missing or not a 32-bit immediate, e.g. a relative `x0[r0.x]`.
+    pub fn get_register(&self, index: u32) -> Option<u32> {
+        match self.get_immediate(index)? {
+            Index::Imm32(reg) => Some(reg),
+            _ => None,
+        }
     }
 
     pub fn is_extended(&self) -> bool {
-        DECODE_IS_D3D10_SB_OPERAND_EXTENDED(unsafe { *self.word }) != 0
+        DECODE_IS_D3D10_SB_OPERAND_EXTENDED(self.get_word()) != 0
+    }
+
+    /// Returns true for SM5.1 resource range operands, which are indexed by
+    /// range ID, lower and upper bound.
+    pub fn is_range(&self) -> bool {
+        match self.get_operand_type() {
+            OperandType::Resource |
+            OperandType::Sampler |
+            OperandType::ConstantBuffer |
+            OperandType::UnorderedAccessView => self.get_index_dimension() == IndexDimension::D3,
+            _ => false,
+        }
     }
 
     pub fn get_num_components(&self) -> NumComponents {
-        match DECODE_D3D10_SB_OPERAND_NUM_COMPONENTS(unsafe { *self.word }) {
+        match DECODE_D3D10_SB_OPERAND_NUM_COMPONENTS(self.get_word()) {
             0 => NumComponents::Zero,
             1 => NumComponents::One,
             2 => NumComponents::Four,
@@ -776,33 +1071,34 @@ impl<'a> OperandToken0<'a> {
         }
     }
 
-    pub fn get_num_components_u32(&self) -> u32 {
-        match DECODE_D3D10_SB_OPERAND_NUM_COMPONENTS(unsafe { *self.word }) {
-            0 => 0,
-            1 => 1,
-            2 => 4,
-            _ => unreachable!()
+    /// Returns `None` for `NumComponents::N`, which has no fixed count.
+    pub fn get_num_components_u32(&self) -> Option<u32> {
+        match DECODE_D3D10_SB_OPERAND_NUM_COMPONENTS(self.get_word()) {
+            0 => Some(0),
+            1 => Some(1),
+            2 => Some(4),
+            _ => None,
         }
     }
 
-    pub fn get_component_select_mode(&self) -> ComponentSelectMode {
-        match DECODE_D3D10_SB_OPERAND_4_COMPONENT_SELECTION_MODE(unsafe { *self.word }) {
-            0 => ComponentSelectMode::Mask,
-            1 => ComponentSelectMode::Swizzle,
-            2 => ComponentSelectMode::Select1,
-            _ => unreachable!()
+    pub fn get_component_select_mode(&self) -> Option<ComponentSelectMode> {
+        match DECODE_D3D10_SB_OPERAND_4_COMPONENT_SELECTION_MODE(self.get_word()) {
+            0 => Some(ComponentSelectMode::Mask),
+            1 => Some(ComponentSelectMode::Swizzle),
+            2 => Some(ComponentSelectMode::Select1),
+            _ => None,
         }
     }
 
     pub fn get_component_mask(&self) -> ComponentMask {
-        ComponentMask::from_bits_truncate(DECODE_D3D10_SB_OPERAND_4_COMPONENT_MASK(unsafe { *self.word }))
+        ComponentMask::from_bits_truncate(DECODE_D3D10_SB_OPERAND_4_COMPONENT_MASK(self.get_word()))
     }
 
     pub fn get_component_swizzle(&self) -> ComponentSwizzle {
-        let x = DECODE_D3D10_SB_OPERAND_4_COMPONENT_SWIZZLE_SOURCE(unsafe { *self.word }, D3D10_SB_4_COMPONENT_X) as u8;
-        let y = DECODE_D3D10_SB_OPERAND_4_COMPONENT_SWIZZLE_SOURCE(unsafe { *self.word }, D3D10_SB_4_COMPONENT_Y) as u8;
-        let z = DECODE_D3D10_SB_OPERAND_4_COMPONENT_SWIZZLE_SOURCE(unsafe { *self.word }, D3D10_SB_4_COMPONENT_Z) as u8;
-        let w = DECODE_D3D10_SB_OPERAND_4_COMPONENT_SWIZZLE_SOURCE(unsafe { *self.word }, D3D10_SB_4_COMPONENT_W) as u8;
+        let x = DECODE_D3D10_SB_OPERAND_4_COMPONENT_SWIZZLE_SOURCE(self.get_word(), D3D10_SB_4_COMPONENT_X) as u8;
+        let y = DECODE_D3D10_SB_OPERAND_4_COMPONENT_SWIZZLE_SOURCE(self.get_word(), D3D10_SB_4_COMPONENT_Y) as u8;
+        let z = DECODE_D3D10_SB_OPERAND_4_COMPONENT_SWIZZLE_SOURCE(self.get_word(), D3D10_SB_4_COMPONENT_Z) as u8;
+        let w = DECODE_D3D10_SB_OPERAND_4_COMPONENT_SWIZZLE_SOURCE(self.get_word(), D3D10_SB_4_COMPONENT_W) as u8;
 
         ComponentSwizzle(
             ComponentName::from_word(x),
@@ -813,7 +1109,7 @@ impl<'a> OperandToken0<'a> {
     }
 
     pub fn get_operand_type(&self) -> OperandType {
-        match DECODE_D3D10_SB_OPERAND_TYPE(unsafe { *self.word }) {
+        match DECODE_D3D10_SB_OPERAND_TYPE(self.get_word()) {
             0 => OperandType::Temp,
             1 => OperandType::Input,
             2 => OperandType::Output,
@@ -860,7 +1156,7 @@ impl<'a> OperandToken0<'a> {
     }
 
     pub fn get_index_dimension(&self) -> IndexDimension {
-        match DECODE_D3D10_SB_OPERAND_INDEX_DIMENSION(unsafe { *self.word }) {
+        match DECODE_D3D10_SB_OPERAND_INDEX_DIMENSION(self.get_word()) {
             0 => IndexDimension::D0,
             1 => IndexDimension::D1,
             2 => IndexDimension::D2,
@@ -870,7 +1166,7 @@ impl<'a> OperandToken0<'a> {
     }
 
     pub fn get_index_representation(&self, index: u32) -> IndexRepresentation {
-        match DECODE_D3D10_SB_OPERAND_INDEX_REPRESENTATION(index, unsafe { *self.word }) {
+        match DECODE_D3D10_SB_OPERAND_INDEX_REPRESENTATION(index, self.get_word()) {
             0 => IndexRepresentation::Immediate32,
             1 => IndexRepresentation::Immediate64,
             2 => IndexRepresentation::Relative,
@@ -886,7 +1182,7 @@ use std::fmt;
 impl<'a> fmt::Debug for OperandToken0<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("OperandToken0")
-            .field("Raw", &unsafe { *self.word })
+            .field("Raw", &self.get_word())
             .field("NumComponents", &self.get_num_components())
             .field("ComponentSelect", &self.get_component_select_mode())
             .field("ComponentMask", &self.get_component_mask())
@@ -944,11 +1240,8 @@ pub struct DclInput<'a> {
 }
 
 impl<'a> DclInput<'a> {
-    pub fn get_input_register(&self) -> u32 {
-        match self.operand.get_immediate(0) {
-            Immediate::U32(reg) => reg,
-            _ => !0
-        }
+    pub fn get_input_register(&self) -> Option<u32> {
+        self.operand.get_register(0)
     }
 }
 
@@ -958,11 +1251,8 @@ pub struct DclInputPs<'a> {
 }
 
 impl<'a> DclInputPs<'a> {
-    pub fn get_input_register(&self) -> u32 {
-        match self.operand.get_immediate(0) {
-            Immediate::U32(reg) => reg,
-            _ => !0
-        }
+    pub fn get_input_register(&self) -> Option<u32> {
+        self.operand.get_register(0)
     }
 }
 
@@ -972,11 +1262,30 @@ pub struct DclOutput<'a> {
 }
 
 impl<'a> DclOutput<'a> {
-    pub fn get_output_register(&self) -> u32 {
-        match self.operand.get_immediate(0) {
-            Immediate::U32(reg) => reg,
-            _ => !0
-        }
+    pub fn get_output_register(&self) -> Option<u32> {
+        self.operand.get_register(0)
+    }
+}
+
+/// Range of registers in a register space, declared by SM5.1 resources.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RegisterRange {
+    pub lower: u32,
+    /// Inclusive upper bound, `0xffffffff` for unbounded ranges
+    pub upper: u32,
+    pub space: u32,
+}
+
+impl RegisterRange {
+    // SM5.1 declarations use a 3D operand of (range ID, lower, upper)
+    fn from_operand(operand: &OperandToken0, space: Option<u32>) -> Option<Self> {
+        let space = space?;
+
+        Some(RegisterRange {
+            lower: operand.get_register(1)?,
+            upper: operand.get_register(2)?,
+            space,
+        })
     }
 }
 
@@ -984,6 +1293,10 @@ impl<'a> DclOutput<'a> {
 pub struct DclConstantBuffer<'a> {
     pub operand: OperandToken0<'a>,
     access: u32,
+    /// Size in vectors, declared separately from the operand in SM5.1
+    pub size: Option<u32>,
+    /// Register space, SM5.1 only
+    pub space: Option<u32>,
 }
 
 impl<'a> DclConstantBuffer<'a> {
@@ -995,47 +1308,58 @@ impl<'a> DclConstantBuffer<'a> {
         }
     }
 
-    pub fn get_binding(&self) -> u32 {
-        match self.operand.get_immediate(0) {
-            Immediate::U32(reg) => reg,
-            _ => !0
-        }
+    /// Returns the register, or the range ID in SM5.1.
+    pub fn get_binding(&self) -> Option<u32> {
+        self.operand.get_register(0)
     }
 
-    pub fn get_size(&self) -> u32 {
-        match self.operand.get_immediate(1) {
-            Immediate::U32(reg) => reg,
-            _ => !0
+    pub fn get_size(&self) -> Option<u32> {
+        if let Some(size) = self.size {
+            return Some(size);
         }
+
+        self.operand.get_register(1)
+    }
+
+    pub fn get_range(&self) -> Option<RegisterRange> {
+        RegisterRange::from_operand(&self.operand, self.space)
     }
 }
 
 #[derive(Debug)]
 pub struct DclResource<'a> {
     pub register: OperandToken0<'a>,
-    pub return_type: ResourceReturnTypeToken0<'a>,
+    pub return_type: ResourceReturnTypeToken0,
+    /// Register space, SM5.1 only
+    pub space: Option<u32>,
 }
 
 impl<'a> DclResource<'a> {
-    pub fn get_register(&self) -> u32 {
-        match self.register.get_immediate(0) {
-            Immediate::U32(reg) => reg,
-            _ => !0
-        }
+    /// Returns the register, or the range ID in SM5.1.
+    pub fn get_register(&self) -> Option<u32> {
+        self.register.get_register(0)
+    }
+
+    pub fn get_range(&self) -> Option<RegisterRange> {
+        RegisterRange::from_operand(&self.register, self.space)
     }
 }
 
 #[derive(Debug)]
 pub struct DclSampler<'a> {
     pub operand: OperandToken0<'a>,
+    /// Register space, SM5.1 only
+    pub space: Option<u32>,
 }
 
 impl<'a> DclSampler<'a> {
-    pub fn get_register(&self) -> u32 {
-        match self.operand.get_immediate(0) {
-            Immediate::U32(reg) => reg,
-            _ => !0
-        }
+    /// Returns the register, or the range ID in SM5.1.
+    pub fn get_register(&self) -> Option<u32> {
+        self.operand.get_register(0)
+    }
+
+    pub fn get_range(&self) -> Option<RegisterRange> {
+        RegisterRange::from_operand(&self.operand, self.space)
     }
 }
 
@@ -1046,15 +1370,12 @@ pub struct DclOutputSiv<'a> {
 }
 
 impl<'a> DclOutputSiv<'a> {
-    pub fn get_output_register(&self) -> u32 {
-        match self.operand.get_immediate(0) {
-            Immediate::U32(reg) => reg,
-            _ => !0
-        }
+    pub fn get_output_register(&self) -> Option<u32> {
+        self.operand.get_register(0)
     }
 
-    pub fn get_system_name(&self) -> NameToken {
-        NameToken::from_word(DECODE_D3D10_SB_NAME(unsafe { *self.operand_2.word }))
+    pub fn get_system_name(&self) -> Option<NameToken> {
+        NameToken::from_word(DECODE_D3D10_SB_NAME(self.operand_2.get_word()))
     }
 }
 
@@ -1065,15 +1386,12 @@ pub struct DclOutputSgv<'a> {
 }
 
 impl<'a> DclOutputSgv<'a> {
-    pub fn get_output_register(&self) -> u32 {
-        match self.operand.get_immediate(0) {
-            Immediate::U32(reg) => reg,
-            _ => !0
-        }
+    pub fn get_output_register(&self) -> Option<u32> {
+        self.operand.get_register(0)
     }
 
-    pub fn get_system_name(&self) -> NameToken {
-        NameToken::from_word(DECODE_D3D10_SB_NAME(unsafe { *self.operand_2.word }))
+    pub fn get_system_name(&self) -> Option<NameToken> {
+        NameToken::from_word(DECODE_D3D10_SB_NAME(self.operand_2.get_word()))
     }
 }
 
@@ -1086,15 +1404,12 @@ pub struct DclInputPsSiv<'a> {
 }
 
 impl<'a> DclInputPsSiv<'a> {
-    pub fn get_input_register(&self) -> u32 {
-        match self.operand.get_immediate(0) {
-            Immediate::U32(reg) => reg,
-            _ => !0
-        }
+    pub fn get_input_register(&self) -> Option<u32> {
+        self.operand.get_register(0)
     }
 
-    pub fn get_system_name(&self) -> NameToken {
-        NameToken::from_word(DECODE_D3D10_SB_NAME(unsafe { *self.operand_2.word }))
+    pub fn get_system_name(&self) -> Option<NameToken> {
+        NameToken::from_word(DECODE_D3D10_SB_NAME(self.operand_2.get_word()))
     }
 }
 
@@ -1105,15 +1420,12 @@ pub struct DclInputPsSgv<'a> {
 }
 
 impl<'a> DclInputPsSgv<'a> {
-    pub fn get_input_register(&self) -> u32 {
-        match self.operand.get_immediate(0) {
-            Immediate::U32(reg) => reg,
-            _ => !0
-        }
+    pub fn get_input_register(&self) -> Option<u32> {
+        self.operand.get_register(0)
     }
 
-    pub fn get_system_name(&self) -> NameToken {
-        NameToken::from_word(DECODE_D3D10_SB_NAME(unsafe { *self.operand_2.word }))
+    pub fn get_system_name(&self) -> Option<NameToken> {
+        NameToken::from_word(DECODE_D3D10_SB_NAME(self.operand_2.get_word()))
     }
 }
 
@@ -1215,273 +1527,5050 @@ pub struct Sample<'a> {
     pub src_sampler: OperandToken0<'a>,
 }
 
-#[repr(C)]
 #[derive(Debug)]
-pub struct ShexHeader {
-    pub minor: u8,
-    pub major: u8,
-    pub program_type: u16,
-    pub instruction_length: u32,
+pub struct Call<'a> {
+    pub src_label: OperandToken0<'a>,
 }
 
-impl ShexHeader {
-    pub fn parse<'b>(decoder: &'b mut decoder::Decoder) -> Result<Self, State> {
-        let version = decoder.read_u8();
-        let minor = version & 0x0f;
-        let major = version >> 0x4;
-        decoder.skip(1);
-        let program_type = decoder.read_u16();
-        let instruction_length = decoder.read_u32() - 2;
+#[derive(Debug)]
+pub struct CallC<'a> {
+    pub src: OperandToken0<'a>,
+    pub src_label: OperandToken0<'a>,
+}
 
-        Ok(ShexHeader {
-            minor,
-            major,
-            program_type,
-            instruction_length
-        })
-    }
+#[derive(Debug)]
+pub struct Case<'a> {
+    pub src: OperandToken0<'a>,
 }
 
 #[derive(Debug)]
-pub struct SparseInstruction<'a> {
-    pub opcode: OpcodeToken0<'a>,
-    pub operands: Operands<'a>,
+pub struct ContinueC<'a> {
+    pub src: OperandToken0<'a>,
 }
 
 #[derive(Debug)]
-pub enum Operands<'a> {
-    DclGlobalFlags(DclGlobalFlags),
-    DclInput(DclInput<'a>),
-    DclInputPs(DclInputPs<'a>),
-    DclOutput(DclOutput<'a>),
-    DclConstantBuffer(DclConstantBuffer<'a>),
-    DclResource(DclResource<'a>),
-    DclSampler(DclSampler<'a>),
-    DclOutputSiv(DclOutputSiv<'a>),
-    DclOutputSgv(DclOutputSgv<'a>),
-    DclInputPsSiv(DclInputPsSiv<'a>),
-    DclInputPsSgv(DclInputPsSgv<'a>),
-    DclTemps(DclTemps),
-    DclIndexableTemp(DclIndexableTemp),
-    Add(Add<'a>),
-    And(And<'a>),
-    Mul(Mul<'a>),
-    Mad(Mad<'a>),
-    Mov(Mov<'a>),
-    Itof(Itof<'a>),
-    Utof(Utof<'a>),
-    Ftou(Ftou<'a>),
-    If(If<'a>),
-    Else,
-    EndIf,
-    Loop,
-    EndLoop,
-    Break,
-    BreakC(BreakC<'a>),
-    Sample(Sample<'a>),
-    SampleL(SampleL<'a>),
-    Ret,
-    Unknown
+pub struct DerivRtx<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
 }
 
-impl<'a> SparseInstruction<'a> {
-    pub fn parse<'b>(decoder: &'b mut decoder::Decoder) -> SparseInstruction<'b> {
-        let opcode = OpcodeToken0::from_word(decoder.read_u32_address());
-        let ty = opcode.get_opcode_type();
-        let len = opcode.get_instruction_length();
+#[derive(Debug)]
+pub struct DerivRty<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
 
-        let mut ex = opcode.get_extended_opcode();
-        while let Some(opc) = ex {
-            // println!("{:?}", opcode);
-            // println!("{:?}", opc);
-            ex = opc.get_extended_opcode();
-            decoder.skip(4);
-        }
+#[derive(Debug)]
+pub struct Discard<'a> {
+    pub src: OperandToken0<'a>,
+}
 
-        let operands = match ty {
-            D3D10_SB_OPCODE_DCL_GLOBAL_FLAGS => {
-                Operands::DclGlobalFlags(DclGlobalFlags {
-                    global_flags: DECODE_D3D10_SB_GLOBAL_FLAGS(unsafe { *opcode.word }),
+#[derive(Debug)]
+pub struct Div<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Dp2<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Dp3<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Dp4<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Eq<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Exp<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Frc<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Ftoi<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Ge<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct IAdd<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct IEq<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct IGe<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct ILt<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct IMad<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+    pub c: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct IMax<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct IMin<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct IMul<'a> {
+    pub dst_hi: OperandToken0<'a>,
+    pub dst_lo: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct INe<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct INeg<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct IShl<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct IShr<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Label<'a> {
+    pub src_label: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Ld<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src_address: OperandToken0<'a>,
+    pub src_resource: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct LdMs<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src_address: OperandToken0<'a>,
+    pub src_resource: OperandToken0<'a>,
+    pub src_sample_index: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Log<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Lt<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Min<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Max<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct MovC<'a> {
+    pub dst: OperandToken0<'a>,
+    pub cond: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Ne<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Not<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Or<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct ResInfo<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src_mip_level: OperandToken0<'a>,
+    pub src_resource: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct RetC<'a> {
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct RoundNe<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct RoundNi<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct RoundPi<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct RoundZ<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Rsq<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct SampleC<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src_address: OperandToken0<'a>,
+    pub src_resource: OperandToken0<'a>,
+    pub src_sampler: OperandToken0<'a>,
+    pub src_reference: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct SampleCLz<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src_address: OperandToken0<'a>,
+    pub src_resource: OperandToken0<'a>,
+    pub src_sampler: OperandToken0<'a>,
+    pub src_reference: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct SampleD<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src_address: OperandToken0<'a>,
+    pub src_resource: OperandToken0<'a>,
+    pub src_sampler: OperandToken0<'a>,
+    pub src_ddx: OperandToken0<'a>,
+    pub src_ddy: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct SampleB<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src_address: OperandToken0<'a>,
+    pub src_resource: OperandToken0<'a>,
+    pub src_sampler: OperandToken0<'a>,
+    pub src_bias: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Sqrt<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Switch<'a> {
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct SinCos<'a> {
+    pub dst_sin: OperandToken0<'a>,
+    pub dst_cos: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct UDiv<'a> {
+    pub dst_quot: OperandToken0<'a>,
+    pub dst_rem: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct ULt<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct UGe<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct UMul<'a> {
+    pub dst_hi: OperandToken0<'a>,
+    pub dst_lo: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct UMad<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+    pub c: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct UMax<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct UMin<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct UShr<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Xor<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct DclIndexRange<'a> {
+    pub operand: OperandToken0<'a>,
+    pub register_count: u32,
+}
+
+#[derive(Debug)]
+pub struct DclGsOutputPrimitiveTopology {
+    pub topology: u32,
+}
+
+impl DclGsOutputPrimitiveTopology {
+    pub fn get_topology(&self) -> Option<PrimitiveTopology> {
+        PrimitiveTopology::from_word(self.topology)
+    }
+}
+
+#[derive(Debug)]
+pub struct DclGsInputPrimitive {
+    pub primitive: u32,
+}
+
+impl DclGsInputPrimitive {
+    pub fn get_primitive(&self) -> Option<Primitive> {
+        Primitive::from_word(self.primitive)
+    }
+}
+
+#[derive(Debug)]
+pub struct DclMaxOutputVertexCount {
+    pub count: u32,
+}
+
+#[derive(Debug)]
+pub struct DclInputSgv<'a> {
+    pub operand: OperandToken0<'a>,
+    pub operand_2: OperandToken0<'a>,
+}
+
+impl<'a> DclInputSgv<'a> {
+    pub fn get_input_register(&self) -> Option<u32> {
+        self.operand.get_register(0)
+    }
+
+    pub fn get_system_name(&self) -> Option<NameToken> {
+        NameToken::from_word(DECODE_D3D10_SB_NAME(self.operand_2.get_word()))
+    }
+}
+
+#[derive(Debug)]
+pub struct DclInputSiv<'a> {
+    pub operand: OperandToken0<'a>,
+    pub operand_2: OperandToken0<'a>,
+}
+
+impl<'a> DclInputSiv<'a> {
+    pub fn get_input_register(&self) -> Option<u32> {
+        self.operand.get_register(0)
+    }
+
+    pub fn get_system_name(&self) -> Option<NameToken> {
+        NameToken::from_word(DECODE_D3D10_SB_NAME(self.operand_2.get_word()))
+    }
+}
+
+#[derive(Debug)]
+pub struct Lod<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src_address: OperandToken0<'a>,
+    pub src_resource: OperandToken0<'a>,
+    pub src_sampler: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Gather4<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src_address: OperandToken0<'a>,
+    pub src_resource: OperandToken0<'a>,
+    pub src_sampler: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct SamplePos<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src_resource: OperandToken0<'a>,
+    pub src_sample_index: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct SampleInfo<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src_resource: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct EmitStream<'a> {
+    pub stream: OperandToken0<'a>,
+}
+
+impl<'a> EmitStream<'a> {
+    pub fn get_stream(&self) -> Option<u32> {
+        self.stream.get_register(0)
+    }
+}
+
+#[derive(Debug)]
+pub struct CutStream<'a> {
+    pub stream: OperandToken0<'a>,
+}
+
+impl<'a> CutStream<'a> {
+    pub fn get_stream(&self) -> Option<u32> {
+        self.stream.get_register(0)
+    }
+}
+
+#[derive(Debug)]
+pub struct EmitThenCutStream<'a> {
+    pub stream: OperandToken0<'a>,
+}
+
+impl<'a> EmitThenCutStream<'a> {
+    pub fn get_stream(&self) -> Option<u32> {
+        self.stream.get_register(0)
+    }
+}
+
+#[derive(Debug)]
+pub struct InterfaceCall<'a> {
+    pub function_index: u32,
+    pub interface: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct BufInfo<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src_resource: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct DerivRtxCoarse<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct DerivRtxFine<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct DerivRtyCoarse<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct DerivRtyFine<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Gather4C<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src_address: OperandToken0<'a>,
+    pub src_resource: OperandToken0<'a>,
+    pub src_sampler: OperandToken0<'a>,
+    pub src_reference: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Gather4Po<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src_address: OperandToken0<'a>,
+    pub src_offset: OperandToken0<'a>,
+    pub src_resource: OperandToken0<'a>,
+    pub src_sampler: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Gather4PoC<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src_address: OperandToken0<'a>,
+    pub src_offset: OperandToken0<'a>,
+    pub src_resource: OperandToken0<'a>,
+    pub src_sampler: OperandToken0<'a>,
+    pub src_reference: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Rcp<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct F32tof16<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct F16tof32<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct UAddC<'a> {
+    pub dst: OperandToken0<'a>,
+    pub dst_carry: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct USubB<'a> {
+    pub dst: OperandToken0<'a>,
+    pub dst_borrow: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct CountBits<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct FirstBitHi<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct FirstBitLo<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct FirstBitShi<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct UBfe<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src_width: OperandToken0<'a>,
+    pub src_offset: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct IBfe<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src_width: OperandToken0<'a>,
+    pub src_offset: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Bfi<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src_width: OperandToken0<'a>,
+    pub src_offset: OperandToken0<'a>,
+    pub src_insert: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct BfRev<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct SwapC<'a> {
+    pub dst_a: OperandToken0<'a>,
+    pub dst_b: OperandToken0<'a>,
+    pub cond: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct DclStream<'a> {
+    pub operand: OperandToken0<'a>,
+}
+
+impl<'a> DclStream<'a> {
+    pub fn get_stream(&self) -> Option<u32> {
+        self.operand.get_register(0)
+    }
+}
+
+#[derive(Debug)]
+pub struct DclFunctionBody {
+    pub body: u32,
+}
+
+#[derive(Debug)]
+pub struct DclInputControlPointCount {
+    pub count: u32,
+}
+
+#[derive(Debug)]
+pub struct DclOutputControlPointCount {
+    pub count: u32,
+}
+
+#[derive(Debug)]
+pub struct DclTessDomain {
+    pub domain: u32,
+}
+
+impl DclTessDomain {
+    pub fn get_domain(&self) -> Option<TessellatorDomain> {
+        TessellatorDomain::from_word(self.domain)
+    }
+}
+
+#[derive(Debug)]
+pub struct DclTessPartitioning {
+    pub partitioning: u32,
+}
+
+impl DclTessPartitioning {
+    pub fn get_partitioning(&self) -> Option<TessellatorPartitioning> {
+        TessellatorPartitioning::from_word(self.partitioning)
+    }
+}
+
+#[derive(Debug)]
+pub struct DclTessOutputPrimitive {
+    pub primitive: u32,
+}
+
+impl DclTessOutputPrimitive {
+    pub fn get_primitive(&self) -> Option<TessellatorOutputPrimitive> {
+        TessellatorOutputPrimitive::from_word(self.primitive)
+    }
+}
+
+#[derive(Debug)]
+pub struct DclHsMaxTessFactor {
+    pub max_tess_factor: f32,
+}
+
+#[derive(Debug)]
+pub struct DclHsForkPhaseInstanceCount {
+    pub instance_count: u32,
+}
+
+#[derive(Debug)]
+pub struct DclHsJoinPhaseInstanceCount {
+    pub instance_count: u32,
+}
+
+#[derive(Debug)]
+pub struct DclThreadGroup {
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+}
+
+#[derive(Debug)]
+pub struct DclTgsmRaw<'a> {
+    pub operand: OperandToken0<'a>,
+    pub byte_count: u32,
+}
+
+impl<'a> DclTgsmRaw<'a> {
+    pub fn get_register(&self) -> Option<u32> {
+        self.operand.get_register(0)
+    }
+}
+
+#[derive(Debug)]
+pub struct DclTgsmStructured<'a> {
+    pub operand: OperandToken0<'a>,
+    pub stride: u32,
+    pub count: u32,
+}
+
+impl<'a> DclTgsmStructured<'a> {
+    pub fn get_register(&self) -> Option<u32> {
+        self.operand.get_register(0)
+    }
+}
+
+#[derive(Debug)]
+pub struct LdUavTyped<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src_address: OperandToken0<'a>,
+    pub src_uav: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct StoreUavTyped<'a> {
+    pub dst_uav: OperandToken0<'a>,
+    pub dst_address: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct LdRaw<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src_byte_offset: OperandToken0<'a>,
+    pub src_resource: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct StoreRaw<'a> {
+    pub dst_resource: OperandToken0<'a>,
+    pub dst_byte_offset: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct LdStructured<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src_index: OperandToken0<'a>,
+    pub src_byte_offset: OperandToken0<'a>,
+    pub src_resource: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct StoreStructured<'a> {
+    pub dst_resource: OperandToken0<'a>,
+    pub dst_index: OperandToken0<'a>,
+    pub dst_byte_offset: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct AtomicAnd<'a> {
+    pub dst_resource: OperandToken0<'a>,
+    pub dst_address: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct AtomicOr<'a> {
+    pub dst_resource: OperandToken0<'a>,
+    pub dst_address: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct AtomicXor<'a> {
+    pub dst_resource: OperandToken0<'a>,
+    pub dst_address: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct AtomicCmpStore<'a> {
+    pub dst_resource: OperandToken0<'a>,
+    pub dst_address: OperandToken0<'a>,
+    pub src_compare: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct AtomicIAdd<'a> {
+    pub dst_resource: OperandToken0<'a>,
+    pub dst_address: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct AtomicIMax<'a> {
+    pub dst_resource: OperandToken0<'a>,
+    pub dst_address: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct AtomicIMin<'a> {
+    pub dst_resource: OperandToken0<'a>,
+    pub dst_address: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct AtomicUMax<'a> {
+    pub dst_resource: OperandToken0<'a>,
+    pub dst_address: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct AtomicUMin<'a> {
+    pub dst_resource: OperandToken0<'a>,
+    pub dst_address: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct ImmAtomicAlloc<'a> {
+    pub dst: OperandToken0<'a>,
+    pub dst_resource: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct ImmAtomicConsume<'a> {
+    pub dst: OperandToken0<'a>,
+    pub dst_resource: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct ImmAtomicIAdd<'a> {
+    pub dst: OperandToken0<'a>,
+    pub dst_resource: OperandToken0<'a>,
+    pub dst_address: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct ImmAtomicAnd<'a> {
+    pub dst: OperandToken0<'a>,
+    pub dst_resource: OperandToken0<'a>,
+    pub dst_address: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct ImmAtomicOr<'a> {
+    pub dst: OperandToken0<'a>,
+    pub dst_resource: OperandToken0<'a>,
+    pub dst_address: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct ImmAtomicXor<'a> {
+    pub dst: OperandToken0<'a>,
+    pub dst_resource: OperandToken0<'a>,
+    pub dst_address: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct ImmAtomicExch<'a> {
+    pub dst: OperandToken0<'a>,
+    pub dst_resource: OperandToken0<'a>,
+    pub dst_address: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct ImmAtomicCmpExch<'a> {
+    pub dst: OperandToken0<'a>,
+    pub dst_resource: OperandToken0<'a>,
+    pub dst_address: OperandToken0<'a>,
+    pub src_compare: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct ImmAtomicIMax<'a> {
+    pub dst: OperandToken0<'a>,
+    pub dst_resource: OperandToken0<'a>,
+    pub dst_address: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct ImmAtomicIMin<'a> {
+    pub dst: OperandToken0<'a>,
+    pub dst_resource: OperandToken0<'a>,
+    pub dst_address: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct ImmAtomicUMax<'a> {
+    pub dst: OperandToken0<'a>,
+    pub dst_resource: OperandToken0<'a>,
+    pub dst_address: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct ImmAtomicUMin<'a> {
+    pub dst: OperandToken0<'a>,
+    pub dst_resource: OperandToken0<'a>,
+    pub dst_address: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Sync {
+    pub flags: u32,
+}
+
+impl Sync {
+    /// Returns true if all threads in the group wait at this point.
+    pub fn is_thread_group_sync(&self) -> bool {
+        (self.flags & D3D11_SB_SYNC_THREADS_IN_GROUP) != 0
+    }
+
+    pub fn is_tgsm_sync(&self) -> bool {
+        (self.flags & D3D11_SB_SYNC_THREAD_GROUP_SHARED_MEMORY) != 0
+    }
+
+    pub fn is_uav_group_sync(&self) -> bool {
+        (self.flags & D3D11_SB_SYNC_UNORDERED_ACCESS_VIEW_MEMORY_GROUP) != 0
+    }
+
+    pub fn is_uav_global_sync(&self) -> bool {
+        (self.flags & D3D11_SB_SYNC_UNORDERED_ACCESS_VIEW_MEMORY_GLOBAL) != 0
+    }
+}
+
+#[derive(Debug)]
+pub struct DAdd<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct DMax<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct DMin<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct DMul<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct DEq<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct DGe<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct DLt<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct DNe<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct DMov<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct DMovC<'a> {
+    pub dst: OperandToken0<'a>,
+    pub cond: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Dtof<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Ftod<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct EvalSnapped<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+    pub src_offset: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct EvalSampleIndex<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+    pub src_sample_index: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct EvalCentroid<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct DclGsInstanceCount {
+    pub instance_count: u32,
+}
+
+#[derive(Debug)]
+pub struct DDiv<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct DFma<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+    pub c: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct DRcp<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Msad<'a> {
+    pub dst: OperandToken0<'a>,
+    pub a: OperandToken0<'a>,
+    pub b: OperandToken0<'a>,
+    pub c: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Dtoi<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Dtou<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Itod<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct Utod<'a> {
+    pub dst: OperandToken0<'a>,
+    pub src: OperandToken0<'a>,
+}
+
+#[derive(Debug)]
+pub struct DclFunctionTable<'a> {
+    pub table: u32,
+    pub bodies: &'a [u32],
+}
+
+#[derive(Debug)]
+pub struct DclInterface<'a> {
+    pub interface: u32,
+    pub expected_table_length: u32,
+    pub array_length: u32,
+    pub tables: &'a [u32],
+    pub dynamically_indexed: bool,
+}
+
+#[derive(Debug)]
+pub struct DclUavTyped<'a> {
+    pub operand: OperandToken0<'a>,
+    pub return_type: ResourceReturnTypeToken0,
+    flags: u32,
+    /// Register space, SM5.1 only
+    pub space: Option<u32>,
+}
+
+impl<'a> DclUavTyped<'a> {
+    pub fn get_register(&self) -> Option<u32> {
+        self.operand.get_register(0)
+    }
+
+    pub fn get_range(&self) -> Option<RegisterRange> {
+        RegisterRange::from_operand(&self.operand, self.space)
+    }
+
+    pub fn is_globally_coherent(&self) -> bool {
+        (self.flags & D3D11_SB_GLOBALLY_COHERENT_ACCESS) != 0
+    }
+}
+
+#[derive(Debug)]
+pub struct DclUavRaw<'a> {
+    pub operand: OperandToken0<'a>,
+    flags: u32,
+    /// Register space, SM5.1 only
+    pub space: Option<u32>,
+}
+
+impl<'a> DclUavRaw<'a> {
+    pub fn get_register(&self) -> Option<u32> {
+        self.operand.get_register(0)
+    }
+
+    pub fn get_range(&self) -> Option<RegisterRange> {
+        RegisterRange::from_operand(&self.operand, self.space)
+    }
+
+    pub fn is_globally_coherent(&self) -> bool {
+        (self.flags & D3D11_SB_GLOBALLY_COHERENT_ACCESS) != 0
+    }
+}
+
+#[derive(Debug)]
+pub struct DclUavStructured<'a> {
+    pub operand: OperandToken0<'a>,
+    pub stride: u32,
+    flags: u32,
+    /// Register space, SM5.1 only
+    pub space: Option<u32>,
+}
+
+impl<'a> DclUavStructured<'a> {
+    pub fn get_register(&self) -> Option<u32> {
+        self.operand.get_register(0)
+    }
+
+    pub fn get_range(&self) -> Option<RegisterRange> {
+        RegisterRange::from_operand(&self.operand, self.space)
+    }
+
+    pub fn is_globally_coherent(&self) -> bool {
+        (self.flags & D3D11_SB_GLOBALLY_COHERENT_ACCESS) != 0
+    }
+
+    pub fn has_order_preserving_counter(&self) -> bool {
+        (self.flags & D3D11_SB_UAV_HAS_ORDER_PRESERVING_COUNTER) != 0
+    }
+}
+
+#[derive(Debug)]
+pub struct DclResourceRaw<'a> {
+    pub operand: OperandToken0<'a>,
+    /// Register space, SM5.1 only
+    pub space: Option<u32>,
+}
+
+impl<'a> DclResourceRaw<'a> {
+    pub fn get_register(&self) -> Option<u32> {
+        self.operand.get_register(0)
+    }
+
+    pub fn get_range(&self) -> Option<RegisterRange> {
+        RegisterRange::from_operand(&self.operand, self.space)
+    }
+}
+
+#[derive(Debug)]
+pub struct DclResourceStructured<'a> {
+    pub operand: OperandToken0<'a>,
+    pub stride: u32,
+    /// Register space, SM5.1 only
+    pub space: Option<u32>,
+}
+
+impl<'a> DclResourceStructured<'a> {
+    pub fn get_register(&self) -> Option<u32> {
+        self.operand.get_register(0)
+    }
+
+    pub fn get_range(&self) -> Option<RegisterRange> {
+        RegisterRange::from_operand(&self.operand, self.space)
+    }
+}
+
+/// Chunk a shader program was stored in.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ShaderChunkKind {
+    /// `SHDR`, written for shader model 4.x
+    Shdr,
+    /// `SHEX`, written for shader model 5.x
+    Shex,
+}
+
+impl ShaderChunkKind {
+    pub fn from_fourcc(fourcc: &[u8; 4]) -> Option<Self> {
+        match fourcc {
+            b"SHDR" => Some(ShaderChunkKind::Shdr),
+            b"SHEX" => Some(ShaderChunkKind::Shex),
+            _ => None,
+        }
+    }
+
+    pub fn fourcc(&self) -> &'static [u8; 4] {
+        match *self {
+            ShaderChunkKind::Shdr => b"SHDR",
+            ShaderChunkKind::Shex => b"SHEX",
+        }
+    }
+}
+
+/// Shader stage of a program, as stored in its version token.
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ProgramType {
+    Pixel = 0,
+    Vertex = 1,
+    Geometry = 2,
+    Hull = 3,
+    Domain = 4,
+    Compute = 5,
+}
+
+impl ProgramType {
+    pub fn from_word(word: u32) -> Option<Self> {
+        match word {
+            0 => Some(ProgramType::Pixel),
+            1 => Some(ProgramType::Vertex),
+            2 => Some(ProgramType::Geometry),
+            3 => Some(ProgramType::Hull),
+            4 => Some(ProgramType::Domain),
+            5 => Some(ProgramType::Compute),
+            _ => None,
+        }
+    }
+
+    /// Returns the prefix of the stage's target profiles, e.g. `vs`.
+    pub fn get_prefix(&self) -> &'static str {
+        match *self {
+            ProgramType::Pixel => "ps",
+            ProgramType::Vertex => "vs",
+            ProgramType::Geometry => "gs",
+            ProgramType::Hull => "hs",
+            ProgramType::Domain => "ds",
+            ProgramType::Compute => "cs",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ShexHeader {
+    pub kind: ShaderChunkKind,
+    pub minor: u8,
+    pub major: u8,
+    pub program_type: u16,
+    pub instruction_length: u32,
+}
+
+impl ShexHeader {
+    pub fn parse<'b>(decoder: &'b mut decoder::Decoder, kind: ShaderChunkKind) -> Result<Self, State> {
+        let version = decoder.read_u8()?;
+        let minor = version & 0x0f;
+        let major = version >> 0x4;
+        decoder.skip(1)?;
+        let program_type = decoder.read_u16()?;
+        let instruction_length = decoder.read_u32()?
+            .checked_sub(2)
+            .ok_or(State::ChunkIncorrect)?;
+
+        Ok(ShexHeader {
+            kind,
+            minor,
+            major,
+            program_type,
+            instruction_length
+        })
+    }
+
+    pub fn get_program_type(&self) -> Option<ProgramType> {
+        ProgramType::from_word(self.program_type as u32)
+    }
+
+    /// Returns the target profile of the program, e.g. `vs_4_0`, or `None`
+    /// for an unknown program type.
+    pub fn get_profile(&self) -> Option<String> {
+        let program_type = self.get_program_type()?;
+
+        Some(format!("{}_{}_{}", program_type.get_prefix(), self.major, self.minor))
+    }
+
+    pub fn get_shader_model(&self) -> Option<ShaderModel> {
+        ShaderModel::from_version(self.major, self.minor)
+    }
+
+    /// Returns true if resources are declared with register spaces and range
+    /// IDs, as introduced in SM5.1.
+    pub fn has_register_spaces(&self) -> bool {
+        (self.major, self.minor) >= (5, 1)
+    }
+}
+
+/// Memory operated on by an atomic instruction.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AtomicTarget<'a> {
+    /// UAV register `u#`, which may be relatively indexed in SM5.1
+    Uav(Immediate<'a>),
+    /// Thread group shared memory register `g#`
+    Tgsm(Immediate<'a>),
+}
+
+/// How an instruction uses one of its operands.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum OperandRole {
+    /// Register written by the instruction
+    Dst,
+    /// Register read by the instruction, including the addresses of stores
+    /// and atomics
+    Src,
+    /// Resource, UAV, constant buffer or thread group shared memory, whether
+    /// read or written
+    Resource,
+    Sampler,
+    /// Immediate literal, e.g. `l(1.0, 0, 0, 0)`
+    Immediate,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct InstructionOperand<'a> {
+    pub role: OperandRole,
+    pub operand: OperandToken0<'a>,
+}
+
+impl<'a> InstructionOperand<'a> {
+    fn read(operand: OperandToken0<'a>) -> Self {
+        Self::classify(operand, OperandRole::Src)
+    }
+
+    fn written(operand: OperandToken0<'a>) -> Self {
+        Self::classify(operand, OperandRole::Dst)
+    }
+
+    // registers declared by a dcl_* instruction are reported as written for
+    // outputs and read for everything else
+    fn declared(operand: OperandToken0<'a>) -> Self {
+        let role = match operand.get_operand_type() {
+            OperandType::Output |
+            OperandType::OutputDepth |
+            OperandType::OutputCoverageMask |
+            OperandType::OutputControlPoint |
+            OperandType::OutputDepthGreaterEqual |
+            OperandType::OutputDepthLessEqual => OperandRole::Dst,
+            _ => OperandRole::Src,
+        };
+
+        Self::classify(operand, role)
+    }
+
+    fn classify(operand: OperandToken0<'a>, role: OperandRole) -> Self {
+        let role = match operand.get_operand_type() {
+            OperandType::Immediate32 |
+            OperandType::Immediate64 => OperandRole::Immediate,
+            OperandType::Sampler => OperandRole::Sampler,
+            OperandType::Resource |
+            OperandType::ConstantBuffer |
+            OperandType::ImmediateConstantBuffer |
+            OperandType::UnorderedAccessView |
+            OperandType::ThreadGroupSharedMemory => OperandRole::Resource,
+            _ => role,
+        };
+
+        InstructionOperand {
+            role,
+            operand,
+        }
+    }
+}
+
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CustomDataClass {
+    Comment = 0,
+    DebugInfo = 1,
+    Opaque = 2,
+    ImmediateConstantBuffer = 3,
+    ShaderMessage = 4,
+    ClipPlaneConstantMappings = 5,
+}
+
+impl CustomDataClass {
+    pub fn from_word(word: u32) -> Option<Self> {
+        match word {
+            0 => Some(CustomDataClass::Comment),
+            1 => Some(CustomDataClass::DebugInfo),
+            2 => Some(CustomDataClass::Opaque),
+            3 => Some(CustomDataClass::ImmediateConstantBuffer),
+            4 => Some(CustomDataClass::ShaderMessage),
+            5 => Some(CustomDataClass::ClipPlaneConstantMappings),
+            _ => None,
+        }
+    }
+}
+
+/// Table declared by `dcl_immediateConstantBuffer` and referenced by `icb`
+/// operands.
+#[derive(Debug)]
+pub struct ImmediateConstantBuffer<'a> {
+    pub data: &'a [u32],
+}
+
+impl<'a> ImmediateConstantBuffer<'a> {
+    /// Returns the number of 4-component vectors in the table.
+    pub fn get_vector_count(&self) -> usize {
+        self.data.len() / 4
+    }
+
+    pub fn get_vector(&self, index: usize) -> Option<[u32; 4]> {
+        self.data.get(index * 4..index * 4 + 4).map(|v| [v[0], v[1], v[2], v[3]])
+    }
+}
+
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ShaderMessageType {
+    Message = 0x00200102,
+    Error = 0x00200103,
+}
+
+impl ShaderMessageType {
+    pub fn from_word(word: u32) -> Option<Self> {
+        match word {
+            0x00200102 => Some(ShaderMessageType::Message),
+            0x00200103 => Some(ShaderMessageType::Error),
+            _ => None,
+        }
+    }
+}
+
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ShaderMessageFormat {
+    AnsiText = 0,
+    AnsiPrintf = 1,
+}
+
+impl ShaderMessageFormat {
+    pub fn from_word(word: u32) -> Option<Self> {
+        match word {
+            0 => Some(ShaderMessageFormat::AnsiText),
+            1 => Some(ShaderMessageFormat::AnsiPrintf),
+            _ => None,
+        }
+    }
+}
+
+/// Message emitted by `printf` or `errorf`, with the operands used as format
+/// arguments.
+#[derive(Debug)]
+pub struct ShaderMessage<'a> {
+    pub id: u32,
+    pub format: u32,
+    pub operands: Vec<OperandToken0<'a>>,
+    pub message: String,
+}
+
+impl<'a> ShaderMessage<'a> {
+    fn parse(decoder: &mut decoder::WordDecoder<'a>) -> DecoderResult<Self> {
+        let id = decoder.read_u32()?;
+        let format = decoder.read_u32()?;
+        let char_count = decoder.read_u32()? as usize;
+        let operand_count = decoder.read_u32()?;
+        let operand_len = decoder.read_u32()? as usize;
+
+        // every operand takes at least one word, so don't trust the count
+        // further than the data actually present
+        let operand_start = decoder.get_offset();
+        if operand_count as usize > decoder.remaining().len() {
+            return Err(Error::InvalidOperand(4 * operand_start));
+        }
+
+        let mut operands = Vec::with_capacity(operand_count as usize);
+        for _ in 0..operand_count {
+            operands.push(OperandToken0::parse(decoder)?);
+        }
+        decoder.seek_mut(operand_start + operand_len)?;
+
+        // null terminated and padded to a whole word
+        let words = decoder.words((char_count + 4) / 4)?;
+        let bytes = words.iter()
+            .flat_map(|word| (0..4).map(move |i| (word >> (8 * i)) as u8))
+            .take(char_count)
+            .collect::<Vec<_>>();
+
+        Ok(ShaderMessage {
+            id,
+            format,
+            operands,
+            message: String::from_utf8_lossy(&bytes).into_owned(),
+        })
+    }
+
+    pub fn get_type(&self) -> Option<ShaderMessageType> {
+        ShaderMessageType::from_word(self.id)
+    }
+
+    pub fn get_format(&self) -> Option<ShaderMessageFormat> {
+        ShaderMessageFormat::from_word(self.format)
+    }
+}
+
+/// Block of data embedded in the instruction stream. Unlike other
+/// instructions its length is stored in the word following the opcode.
+#[derive(Debug)]
+pub enum CustomData<'a> {
+    Comment(&'a [u32]),
+    DebugInfo(&'a [u32]),
+    Opaque(&'a [u32]),
+    ImmediateConstantBuffer(ImmediateConstantBuffer<'a>),
+    ShaderMessage(ShaderMessage<'a>),
+    ClipPlaneConstantMappings(&'a [u32]),
+    Unknown(u32, &'a [u32]),
+}
+
+impl<'a> CustomData<'a> {
+    fn parse(decoder: &mut decoder::WordDecoder<'a>, class: u32, data: &'a [u32]) -> DecoderResult<Self> {
+        Ok(match CustomDataClass::from_word(class) {
+            Some(CustomDataClass::Comment) => CustomData::Comment(data),
+            Some(CustomDataClass::DebugInfo) => CustomData::DebugInfo(data),
+            Some(CustomDataClass::Opaque) => CustomData::Opaque(data),
+            Some(CustomDataClass::ImmediateConstantBuffer) => {
+                CustomData::ImmediateConstantBuffer(ImmediateConstantBuffer { data })
+            }
+            Some(CustomDataClass::ShaderMessage) => {
+                CustomData::ShaderMessage(ShaderMessage::parse(decoder)?)
+            }
+            Some(CustomDataClass::ClipPlaneConstantMappings) => CustomData::ClipPlaneConstantMappings(data),
+            None => CustomData::Unknown(class, data),
+        })
+    }
+
+    pub fn get_class(&self) -> Option<CustomDataClass> {
+        match self {
+            CustomData::Comment(..) => Some(CustomDataClass::Comment),
+            CustomData::DebugInfo(..) => Some(CustomDataClass::DebugInfo),
+            CustomData::Opaque(..) => Some(CustomDataClass::Opaque),
+            CustomData::ImmediateConstantBuffer(..) => Some(CustomDataClass::ImmediateConstantBuffer),
+            CustomData::ShaderMessage(..) => Some(CustomDataClass::ShaderMessage),
+            CustomData::ClipPlaneConstantMappings(..) => Some(CustomDataClass::ClipPlaneConstantMappings),
+            CustomData::Unknown(..) => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SparseInstruction<'a> {
+    pub opcode: OpcodeToken0<'a>,
+    /// Extended opcode tokens following the opcode, in encoding order
+    pub extended: Vec<ExtendedOpcode>,
+    pub operands: Operands<'a>,
+}
+
+#[derive(Debug)]
+pub enum Operands<'a> {
+    DclGlobalFlags(DclGlobalFlags),
+    DclInput(DclInput<'a>),
+    DclInputPs(DclInputPs<'a>),
+    DclOutput(DclOutput<'a>),
+    DclConstantBuffer(DclConstantBuffer<'a>),
+    DclResource(DclResource<'a>),
+    DclSampler(DclSampler<'a>),
+    DclOutputSiv(DclOutputSiv<'a>),
+    DclOutputSgv(DclOutputSgv<'a>),
+    DclInputPsSiv(DclInputPsSiv<'a>),
+    DclInputPsSgv(DclInputPsSgv<'a>),
+    DclTemps(DclTemps),
+    DclIndexableTemp(DclIndexableTemp),
+    Add(Add<'a>),
+    And(And<'a>),
+    Mul(Mul<'a>),
+    Mad(Mad<'a>),
+    Mov(Mov<'a>),
+    Itof(Itof<'a>),
+    Utof(Utof<'a>),
+    Ftou(Ftou<'a>),
+    If(If<'a>),
+    Else,
+    EndIf,
+    Loop,
+    EndLoop,
+    Break,
+    BreakC(BreakC<'a>),
+    Sample(Sample<'a>),
+    SampleL(SampleL<'a>),
+    Ret,
+    Call(Call<'a>),
+    CallC(CallC<'a>),
+    Case(Case<'a>),
+    Continue,
+    ContinueC(ContinueC<'a>),
+    Cut,
+    Default,
+    DerivRtx(DerivRtx<'a>),
+    DerivRty(DerivRty<'a>),
+    Discard(Discard<'a>),
+    Div(Div<'a>),
+    Dp2(Dp2<'a>),
+    Dp3(Dp3<'a>),
+    Dp4(Dp4<'a>),
+    Emit,
+    EmitThenCut,
+    EndSwitch,
+    Eq(Eq<'a>),
+    Exp(Exp<'a>),
+    Frc(Frc<'a>),
+    Ftoi(Ftoi<'a>),
+    Ge(Ge<'a>),
+    IAdd(IAdd<'a>),
+    IEq(IEq<'a>),
+    IGe(IGe<'a>),
+    ILt(ILt<'a>),
+    IMad(IMad<'a>),
+    IMax(IMax<'a>),
+    IMin(IMin<'a>),
+    IMul(IMul<'a>),
+    INe(INe<'a>),
+    INeg(INeg<'a>),
+    IShl(IShl<'a>),
+    IShr(IShr<'a>),
+    Label(Label<'a>),
+    Ld(Ld<'a>),
+    LdMs(LdMs<'a>),
+    Log(Log<'a>),
+    Lt(Lt<'a>),
+    Min(Min<'a>),
+    Max(Max<'a>),
+    MovC(MovC<'a>),
+    Ne(Ne<'a>),
+    Nop,
+    Not(Not<'a>),
+    Or(Or<'a>),
+    ResInfo(ResInfo<'a>),
+    RetC(RetC<'a>),
+    RoundNe(RoundNe<'a>),
+    RoundNi(RoundNi<'a>),
+    RoundPi(RoundPi<'a>),
+    RoundZ(RoundZ<'a>),
+    Rsq(Rsq<'a>),
+    SampleC(SampleC<'a>),
+    SampleCLz(SampleCLz<'a>),
+    SampleD(SampleD<'a>),
+    SampleB(SampleB<'a>),
+    Sqrt(Sqrt<'a>),
+    Switch(Switch<'a>),
+    SinCos(SinCos<'a>),
+    UDiv(UDiv<'a>),
+    ULt(ULt<'a>),
+    UGe(UGe<'a>),
+    UMul(UMul<'a>),
+    UMad(UMad<'a>),
+    UMax(UMax<'a>),
+    UMin(UMin<'a>),
+    UShr(UShr<'a>),
+    Xor(Xor<'a>),
+    DclIndexRange(DclIndexRange<'a>),
+    DclGsOutputPrimitiveTopology(DclGsOutputPrimitiveTopology),
+    DclGsInputPrimitive(DclGsInputPrimitive),
+    DclMaxOutputVertexCount(DclMaxOutputVertexCount),
+    DclInputSgv(DclInputSgv<'a>),
+    DclInputSiv(DclInputSiv<'a>),
+    Lod(Lod<'a>),
+    Gather4(Gather4<'a>),
+    SamplePos(SamplePos<'a>),
+    SampleInfo(SampleInfo<'a>),
+    HsDecls,
+    HsControlPointPhase,
+    HsForkPhase,
+    HsJoinPhase,
+    EmitStream(EmitStream<'a>),
+    CutStream(CutStream<'a>),
+    EmitThenCutStream(EmitThenCutStream<'a>),
+    InterfaceCall(InterfaceCall<'a>),
+    BufInfo(BufInfo<'a>),
+    DerivRtxCoarse(DerivRtxCoarse<'a>),
+    DerivRtxFine(DerivRtxFine<'a>),
+    DerivRtyCoarse(DerivRtyCoarse<'a>),
+    DerivRtyFine(DerivRtyFine<'a>),
+    Gather4C(Gather4C<'a>),
+    Gather4Po(Gather4Po<'a>),
+    Gather4PoC(Gather4PoC<'a>),
+    Rcp(Rcp<'a>),
+    F32tof16(F32tof16<'a>),
+    F16tof32(F16tof32<'a>),
+    UAddC(UAddC<'a>),
+    USubB(USubB<'a>),
+    CountBits(CountBits<'a>),
+    FirstBitHi(FirstBitHi<'a>),
+    FirstBitLo(FirstBitLo<'a>),
+    FirstBitShi(FirstBitShi<'a>),
+    UBfe(UBfe<'a>),
+    IBfe(IBfe<'a>),
+    Bfi(Bfi<'a>),
+    BfRev(BfRev<'a>),
+    SwapC(SwapC<'a>),
+    DclStream(DclStream<'a>),
+    DclFunctionBody(DclFunctionBody),
+    DclFunctionTable(DclFunctionTable<'a>),
+    DclInterface(DclInterface<'a>),
+    DclInputControlPointCount(DclInputControlPointCount),
+    DclOutputControlPointCount(DclOutputControlPointCount),
+    DclTessDomain(DclTessDomain),
+    DclTessPartitioning(DclTessPartitioning),
+    DclTessOutputPrimitive(DclTessOutputPrimitive),
+    DclHsMaxTessFactor(DclHsMaxTessFactor),
+    DclHsForkPhaseInstanceCount(DclHsForkPhaseInstanceCount),
+    DclHsJoinPhaseInstanceCount(DclHsJoinPhaseInstanceCount),
+    DclThreadGroup(DclThreadGroup),
+    DclUavTyped(DclUavTyped<'a>),
+    DclUavRaw(DclUavRaw<'a>),
+    DclUavStructured(DclUavStructured<'a>),
+    DclTgsmRaw(DclTgsmRaw<'a>),
+    DclTgsmStructured(DclTgsmStructured<'a>),
+    DclResourceRaw(DclResourceRaw<'a>),
+    DclResourceStructured(DclResourceStructured<'a>),
+    LdUavTyped(LdUavTyped<'a>),
+    StoreUavTyped(StoreUavTyped<'a>),
+    LdRaw(LdRaw<'a>),
+    StoreRaw(StoreRaw<'a>),
+    LdStructured(LdStructured<'a>),
+    StoreStructured(StoreStructured<'a>),
+    AtomicAnd(AtomicAnd<'a>),
+    AtomicOr(AtomicOr<'a>),
+    AtomicXor(AtomicXor<'a>),
+    AtomicCmpStore(AtomicCmpStore<'a>),
+    AtomicIAdd(AtomicIAdd<'a>),
+    AtomicIMax(AtomicIMax<'a>),
+    AtomicIMin(AtomicIMin<'a>),
+    AtomicUMax(AtomicUMax<'a>),
+    AtomicUMin(AtomicUMin<'a>),
+    ImmAtomicAlloc(ImmAtomicAlloc<'a>),
+    ImmAtomicConsume(ImmAtomicConsume<'a>),
+    ImmAtomicIAdd(ImmAtomicIAdd<'a>),
+    ImmAtomicAnd(ImmAtomicAnd<'a>),
+    ImmAtomicOr(ImmAtomicOr<'a>),
+    ImmAtomicXor(ImmAtomicXor<'a>),
+    ImmAtomicExch(ImmAtomicExch<'a>),
+    ImmAtomicCmpExch(ImmAtomicCmpExch<'a>),
+    ImmAtomicIMax(ImmAtomicIMax<'a>),
+    ImmAtomicIMin(ImmAtomicIMin<'a>),
+    ImmAtomicUMax(ImmAtomicUMax<'a>),
+    ImmAtomicUMin(ImmAtomicUMin<'a>),
+    Sync(Sync),
+    DAdd(DAdd<'a>),
+    DMax(DMax<'a>),
+    DMin(DMin<'a>),
+    DMul(DMul<'a>),
+    DEq(DEq<'a>),
+    DGe(DGe<'a>),
+    DLt(DLt<'a>),
+    DNe(DNe<'a>),
+    DMov(DMov<'a>),
+    DMovC(DMovC<'a>),
+    Dtof(Dtof<'a>),
+    Ftod(Ftod<'a>),
+    EvalSnapped(EvalSnapped<'a>),
+    EvalSampleIndex(EvalSampleIndex<'a>),
+    EvalCentroid(EvalCentroid<'a>),
+    DclGsInstanceCount(DclGsInstanceCount),
+    Abort,
+    DebugBreak,
+    DDiv(DDiv<'a>),
+    DFma(DFma<'a>),
+    DRcp(DRcp<'a>),
+    Msad(Msad<'a>),
+    Dtoi(Dtoi<'a>),
+    Dtou(Dtou<'a>),
+    Itod(Itod<'a>),
+    Utod(Utod<'a>),
+    CustomData(CustomData<'a>),
+    Unknown
+}
+
+impl<'a> SparseInstruction<'a> {
+    pub fn parse<'b>(decoder: &mut decoder::WordDecoder<'b>) -> DecoderResult<SparseInstruction<'b>> {
+        let start = decoder.get_offset();
+        let word = decoder.read_u32()?;
+        let ty = DECODE_D3D10_SB_OPCODE_TYPE(word);
+
+        // the class of a custom data block overlaps the length and extended
+        // bits, its length is stored in the next word instead
+        if ty == D3D10_SB_OPCODE_CUSTOMDATA {
+            return Self::parse_custom_data(decoder, start, word);
+        }
+
+        let len = DECODE_D3D10_SB_TOKENIZED_INSTRUCTION_LENGTH(word);
+
+        // only custom data blocks may leave the length field empty
+        if len == 0 {
+            return Err(Error::InvalidOpcode(4 * start));
+        }
+
+        // operands may not extend past the declared instruction length
+        decoder.set_limit(start + len as usize);
+
+        let mut extended = DECODE_IS_D3D10_SB_OPCODE_EXTENDED(word) != 0;
+        while extended {
+            extended = DECODE_IS_D3D10_SB_OPCODE_EXTENDED(decoder.read_u32()?) != 0;
+        }
+
+        let opcode = OpcodeToken0::from_words(decoder.words_from(start));
+
+        let mut extended_opcodes = Vec::new();
+        let mut token = opcode.get_extended_opcode();
+        while let Some(ex) = token {
+            extended_opcodes.push(ex.get_extended_opcode_data().ok_or(Error::InvalidOpcode(4 * start))?);
+            token = ex.get_extended_opcode();
+        }
+
+        let operands = match ty {
+            D3D10_SB_OPCODE_DCL_GLOBAL_FLAGS => {
+                Operands::DclGlobalFlags(DclGlobalFlags {
+                    global_flags: DECODE_D3D10_SB_GLOBAL_FLAGS(opcode.get_word()),
+                })
+            }
+            D3D10_SB_OPCODE_DCL_INPUT => {
+                Operands::DclInput(DclInput {
+                    operand: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_DCL_INPUT_PS => {
+                Operands::DclInputPs(DclInputPs {
+                    operand: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_DCL_INPUT_PS_SIV => {
+                Operands::DclInputPsSiv(DclInputPsSiv {
+                    operand: OperandToken0::parse(decoder)?,
+                    operand_2: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_DCL_INPUT_PS_SGV => {
+                Operands::DclInputPsSgv(DclInputPsSgv {
+                    operand: OperandToken0::parse(decoder)?,
+                    operand_2: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_DCL_OUTPUT => {
+                Operands::DclOutput(DclOutput {
+                    operand: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_DCL_CONSTANT_BUFFER => {
+                let operand = OperandToken0::parse(decoder)?;
+                let (size, space) = if operand.is_range() {
+                    let size = decoder.read_u32()?;
+                    (Some(size), Some(decoder.read_u32()?))
+                } else {
+                    (None, None)
+                };
+
+                Operands::DclConstantBuffer(DclConstantBuffer {
+                    operand,
+                    access: DECODE_D3D10_SB_CONSTANT_BUFFER_ACCESS_PATTERN(opcode.get_word()),
+                    size,
+                    space,
+                })
+            }
+            D3D10_SB_OPCODE_DCL_RESOURCE => {
+                let register = OperandToken0::parse(decoder)?;
+                let return_type = ResourceReturnTypeToken0::from_word(decoder.read_u32()?);
+                let space = if register.is_range() {
+                    Some(decoder.read_u32()?)
+                } else {
+                    None
+                };
+
+                Operands::DclResource(DclResource {
+                    register,
+                    return_type,
+                    space,
+                })
+            }
+            D3D10_SB_OPCODE_DCL_SAMPLER => {
+                let operand = OperandToken0::parse(decoder)?;
+                let space = if operand.is_range() {
+                    Some(decoder.read_u32()?)
+                } else {
+                    None
+                };
+
+                Operands::DclSampler(DclSampler {
+                    operand,
+                    space,
+                })
+            }
+            D3D10_SB_OPCODE_DCL_TEMPS => {
+                Operands::DclTemps(DclTemps {
+                    register_count: decoder.read_u32()?,
+                })
+            }
+            D3D10_SB_OPCODE_DCL_INDEXABLE_TEMP => {
+                Operands::DclIndexableTemp(DclIndexableTemp {
+                    register_index: decoder.read_u32()?,
+                    register_count: decoder.read_u32()?,
+                    num_components: decoder.read_u32()?,
+                })
+            }
+            D3D10_SB_OPCODE_DCL_OUTPUT_SIV => {
+                Operands::DclOutputSiv(DclOutputSiv {
+                    operand: OperandToken0::parse(decoder)?,
+                    operand_2: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_DCL_OUTPUT_SGV => {
+                Operands::DclOutputSgv(DclOutputSgv {
+                    operand: OperandToken0::parse(decoder)?,
+                    operand_2: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_ADD => {
+                Operands::Add(Add {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_AND => {
+                Operands::And(And {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_MUL => {
+                Operands::Mul(Mul {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_MAD => {
+                Operands::Mad(Mad {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                    c: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_MOV => {
+                Operands::Mov(Mov {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_ITOF => {
+                Operands::Itof(Itof {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_UTOF => {
+                Operands::Utof(Utof {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_FTOU => {
+                Operands::Ftou(Ftou {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_IF => {
+                Operands::If(If {
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_ELSE => {
+                Operands::Else
+            }
+            D3D10_SB_OPCODE_ENDIF => {
+                Operands::EndIf
+            }
+            D3D10_SB_OPCODE_LOOP => {
+                Operands::Loop
+            }
+            D3D10_SB_OPCODE_ENDLOOP => {
+                Operands::EndLoop
+            }
+            D3D10_SB_OPCODE_BREAK => {
+                Operands::Break
+            }
+            D3D10_SB_OPCODE_BREAKC => {
+                Operands::BreakC(BreakC {
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_SAMPLE => {
+                Operands::Sample(Sample {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_address: OperandToken0::parse(decoder)?,
+                    src_resource: OperandToken0::parse(decoder)?,
+                    src_sampler: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_SAMPLE_L => {
+                Operands::SampleL(SampleL {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_address: OperandToken0::parse(decoder)?,
+                    src_resource: OperandToken0::parse(decoder)?,
+                    src_sampler: OperandToken0::parse(decoder)?,
+                    src_lod: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_RET => {
+                Operands::Ret
+            }
+            D3D10_SB_OPCODE_CALL => {
+                Operands::Call(Call {
+                    src_label: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_CALLC => {
+                Operands::CallC(CallC {
+                    src: OperandToken0::parse(decoder)?,
+                    src_label: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_CASE => {
+                Operands::Case(Case {
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_CONTINUE => {
+                Operands::Continue
+            }
+            D3D10_SB_OPCODE_CONTINUEC => {
+                Operands::ContinueC(ContinueC {
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_CUT => {
+                Operands::Cut
+            }
+            D3D10_SB_OPCODE_DEFAULT => {
+                Operands::Default
+            }
+            D3D10_SB_OPCODE_DERIV_RTX => {
+                Operands::DerivRtx(DerivRtx {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_DERIV_RTY => {
+                Operands::DerivRty(DerivRty {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_DISCARD => {
+                Operands::Discard(Discard {
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_DIV => {
+                Operands::Div(Div {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_DP2 => {
+                Operands::Dp2(Dp2 {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_DP3 => {
+                Operands::Dp3(Dp3 {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_DP4 => {
+                Operands::Dp4(Dp4 {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_EMIT => {
+                Operands::Emit
+            }
+            D3D10_SB_OPCODE_EMITTHENCUT => {
+                Operands::EmitThenCut
+            }
+            D3D10_SB_OPCODE_ENDSWITCH => {
+                Operands::EndSwitch
+            }
+            D3D10_SB_OPCODE_EQ => {
+                Operands::Eq(Eq {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_EXP => {
+                Operands::Exp(Exp {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_FRC => {
+                Operands::Frc(Frc {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_FTOI => {
+                Operands::Ftoi(Ftoi {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_GE => {
+                Operands::Ge(Ge {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_IADD => {
+                Operands::IAdd(IAdd {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_IEQ => {
+                Operands::IEq(IEq {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_IGE => {
+                Operands::IGe(IGe {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_ILT => {
+                Operands::ILt(ILt {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_IMAD => {
+                Operands::IMad(IMad {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                    c: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_IMAX => {
+                Operands::IMax(IMax {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_IMIN => {
+                Operands::IMin(IMin {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_IMUL => {
+                Operands::IMul(IMul {
+                    dst_hi: OperandToken0::parse(decoder)?,
+                    dst_lo: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_INE => {
+                Operands::INe(INe {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_INEG => {
+                Operands::INeg(INeg {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_ISHL => {
+                Operands::IShl(IShl {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_ISHR => {
+                Operands::IShr(IShr {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_LABEL => {
+                Operands::Label(Label {
+                    src_label: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_LD => {
+                Operands::Ld(Ld {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_address: OperandToken0::parse(decoder)?,
+                    src_resource: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_LD_MS => {
+                Operands::LdMs(LdMs {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_address: OperandToken0::parse(decoder)?,
+                    src_resource: OperandToken0::parse(decoder)?,
+                    src_sample_index: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_LOG => {
+                Operands::Log(Log {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_LT => {
+                Operands::Lt(Lt {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_MIN => {
+                Operands::Min(Min {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_MAX => {
+                Operands::Max(Max {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_MOVC => {
+                Operands::MovC(MovC {
+                    dst: OperandToken0::parse(decoder)?,
+                    cond: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_NE => {
+                Operands::Ne(Ne {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_NOP => {
+                Operands::Nop
+            }
+            D3D10_SB_OPCODE_NOT => {
+                Operands::Not(Not {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_OR => {
+                Operands::Or(Or {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_RESINFO => {
+                Operands::ResInfo(ResInfo {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_mip_level: OperandToken0::parse(decoder)?,
+                    src_resource: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_RETC => {
+                Operands::RetC(RetC {
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_ROUND_NE => {
+                Operands::RoundNe(RoundNe {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_ROUND_NI => {
+                Operands::RoundNi(RoundNi {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_ROUND_PI => {
+                Operands::RoundPi(RoundPi {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_ROUND_Z => {
+                Operands::RoundZ(RoundZ {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_RSQ => {
+                Operands::Rsq(Rsq {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_SAMPLE_C => {
+                Operands::SampleC(SampleC {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_address: OperandToken0::parse(decoder)?,
+                    src_resource: OperandToken0::parse(decoder)?,
+                    src_sampler: OperandToken0::parse(decoder)?,
+                    src_reference: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_SAMPLE_C_LZ => {
+                Operands::SampleCLz(SampleCLz {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_address: OperandToken0::parse(decoder)?,
+                    src_resource: OperandToken0::parse(decoder)?,
+                    src_sampler: OperandToken0::parse(decoder)?,
+                    src_reference: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_SAMPLE_D => {
+                Operands::SampleD(SampleD {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_address: OperandToken0::parse(decoder)?,
+                    src_resource: OperandToken0::parse(decoder)?,
+                    src_sampler: OperandToken0::parse(decoder)?,
+                    src_ddx: OperandToken0::parse(decoder)?,
+                    src_ddy: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_SAMPLE_B => {
+                Operands::SampleB(SampleB {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_address: OperandToken0::parse(decoder)?,
+                    src_resource: OperandToken0::parse(decoder)?,
+                    src_sampler: OperandToken0::parse(decoder)?,
+                    src_bias: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_SQRT => {
+                Operands::Sqrt(Sqrt {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_SWITCH => {
+                Operands::Switch(Switch {
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_SINCOS => {
+                Operands::SinCos(SinCos {
+                    dst_sin: OperandToken0::parse(decoder)?,
+                    dst_cos: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_UDIV => {
+                Operands::UDiv(UDiv {
+                    dst_quot: OperandToken0::parse(decoder)?,
+                    dst_rem: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_ULT => {
+                Operands::ULt(ULt {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_UGE => {
+                Operands::UGe(UGe {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_UMUL => {
+                Operands::UMul(UMul {
+                    dst_hi: OperandToken0::parse(decoder)?,
+                    dst_lo: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_UMAD => {
+                Operands::UMad(UMad {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                    c: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_UMAX => {
+                Operands::UMax(UMax {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_UMIN => {
+                Operands::UMin(UMin {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_USHR => {
+                Operands::UShr(UShr {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_XOR => {
+                Operands::Xor(Xor {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_DCL_INDEX_RANGE => {
+                Operands::DclIndexRange(DclIndexRange {
+                    operand: OperandToken0::parse(decoder)?,
+                    register_count: decoder.read_u32()?,
+                })
+            }
+            D3D10_SB_OPCODE_DCL_GS_OUTPUT_PRIMITIVE_TOPOLOGY => {
+                Operands::DclGsOutputPrimitiveTopology(DclGsOutputPrimitiveTopology {
+                    topology: DECODE_D3D10_SB_GS_OUTPUT_PRIMITIVE_TOPOLOGY(opcode.get_word()),
+                })
+            }
+            D3D10_SB_OPCODE_DCL_GS_INPUT_PRIMITIVE => {
+                Operands::DclGsInputPrimitive(DclGsInputPrimitive {
+                    primitive: DECODE_D3D10_SB_GS_INPUT_PRIMITIVE(opcode.get_word()),
+                })
+            }
+            D3D10_SB_OPCODE_DCL_MAX_OUTPUT_VERTEX_COUNT => {
+                Operands::DclMaxOutputVertexCount(DclMaxOutputVertexCount {
+                    count: decoder.read_u32()?,
+                })
+            }
+            D3D10_SB_OPCODE_DCL_INPUT_SGV => {
+                Operands::DclInputSgv(DclInputSgv {
+                    operand: OperandToken0::parse(decoder)?,
+                    operand_2: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_SB_OPCODE_DCL_INPUT_SIV => {
+                Operands::DclInputSiv(DclInputSiv {
+                    operand: OperandToken0::parse(decoder)?,
+                    operand_2: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_1_SB_OPCODE_LOD => {
+                Operands::Lod(Lod {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_address: OperandToken0::parse(decoder)?,
+                    src_resource: OperandToken0::parse(decoder)?,
+                    src_sampler: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_1_SB_OPCODE_GATHER4 => {
+                Operands::Gather4(Gather4 {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_address: OperandToken0::parse(decoder)?,
+                    src_resource: OperandToken0::parse(decoder)?,
+                    src_sampler: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_1_SB_OPCODE_SAMPLE_POS => {
+                Operands::SamplePos(SamplePos {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_resource: OperandToken0::parse(decoder)?,
+                    src_sample_index: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D10_1_SB_OPCODE_SAMPLE_INFO => {
+                Operands::SampleInfo(SampleInfo {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_resource: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_HS_DECLS => {
+                Operands::HsDecls
+            }
+            D3D11_SB_OPCODE_HS_CONTROL_POINT_PHASE => {
+                Operands::HsControlPointPhase
+            }
+            D3D11_SB_OPCODE_HS_FORK_PHASE => {
+                Operands::HsForkPhase
+            }
+            D3D11_SB_OPCODE_HS_JOIN_PHASE => {
+                Operands::HsJoinPhase
+            }
+            D3D11_SB_OPCODE_EMIT_STREAM => {
+                Operands::EmitStream(EmitStream {
+                    stream: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_CUT_STREAM => {
+                Operands::CutStream(CutStream {
+                    stream: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_EMITTHENCUT_STREAM => {
+                Operands::EmitThenCutStream(EmitThenCutStream {
+                    stream: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_INTERFACE_CALL => {
+                Operands::InterfaceCall(InterfaceCall {
+                    function_index: decoder.read_u32()?,
+                    interface: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_BUFINFO => {
+                Operands::BufInfo(BufInfo {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_resource: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_DERIV_RTX_COARSE => {
+                Operands::DerivRtxCoarse(DerivRtxCoarse {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_DERIV_RTX_FINE => {
+                Operands::DerivRtxFine(DerivRtxFine {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_DERIV_RTY_COARSE => {
+                Operands::DerivRtyCoarse(DerivRtyCoarse {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_DERIV_RTY_FINE => {
+                Operands::DerivRtyFine(DerivRtyFine {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_GATHER4_C => {
+                Operands::Gather4C(Gather4C {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_address: OperandToken0::parse(decoder)?,
+                    src_resource: OperandToken0::parse(decoder)?,
+                    src_sampler: OperandToken0::parse(decoder)?,
+                    src_reference: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_GATHER4_PO => {
+                Operands::Gather4Po(Gather4Po {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_address: OperandToken0::parse(decoder)?,
+                    src_offset: OperandToken0::parse(decoder)?,
+                    src_resource: OperandToken0::parse(decoder)?,
+                    src_sampler: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_GATHER4_PO_C => {
+                Operands::Gather4PoC(Gather4PoC {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_address: OperandToken0::parse(decoder)?,
+                    src_offset: OperandToken0::parse(decoder)?,
+                    src_resource: OperandToken0::parse(decoder)?,
+                    src_sampler: OperandToken0::parse(decoder)?,
+                    src_reference: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_RCP => {
+                Operands::Rcp(Rcp {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_F32TOF16 => {
+                Operands::F32tof16(F32tof16 {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_F16TOF32 => {
+                Operands::F16tof32(F16tof32 {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_UADDC => {
+                Operands::UAddC(UAddC {
+                    dst: OperandToken0::parse(decoder)?,
+                    dst_carry: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_USUBB => {
+                Operands::USubB(USubB {
+                    dst: OperandToken0::parse(decoder)?,
+                    dst_borrow: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_COUNTBITS => {
+                Operands::CountBits(CountBits {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_FIRSTBIT_HI => {
+                Operands::FirstBitHi(FirstBitHi {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_FIRSTBIT_LO => {
+                Operands::FirstBitLo(FirstBitLo {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_FIRSTBIT_SHI => {
+                Operands::FirstBitShi(FirstBitShi {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_UBFE => {
+                Operands::UBfe(UBfe {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_width: OperandToken0::parse(decoder)?,
+                    src_offset: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_IBFE => {
+                Operands::IBfe(IBfe {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_width: OperandToken0::parse(decoder)?,
+                    src_offset: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_BFI => {
+                Operands::Bfi(Bfi {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_width: OperandToken0::parse(decoder)?,
+                    src_offset: OperandToken0::parse(decoder)?,
+                    src_insert: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_BFREV => {
+                Operands::BfRev(BfRev {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_SWAPC => {
+                Operands::SwapC(SwapC {
+                    dst_a: OperandToken0::parse(decoder)?,
+                    dst_b: OperandToken0::parse(decoder)?,
+                    cond: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_DCL_STREAM => {
+                Operands::DclStream(DclStream {
+                    operand: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_DCL_FUNCTION_BODY => {
+                Operands::DclFunctionBody(DclFunctionBody {
+                    body: decoder.read_u32()?,
+                })
+            }
+            D3D11_SB_OPCODE_DCL_FUNCTION_TABLE => {
+                let table = decoder.read_u32()?;
+                let count = decoder.read_u32()?;
+
+                Operands::DclFunctionTable(DclFunctionTable {
+                    table,
+                    bodies: decoder.words(count as usize)?,
+                })
+            }
+            D3D11_SB_OPCODE_DCL_INTERFACE => {
+                let interface = decoder.read_u32()?;
+                let expected_table_length = decoder.read_u32()?;
+                let lengths = decoder.read_u32()?;
+                let table_length = DECODE_D3D11_SB_INTERFACE_TABLE_LENGTH(lengths);
+
+                Operands::DclInterface(DclInterface {
+                    interface,
+                    expected_table_length,
+                    array_length: DECODE_D3D11_SB_INTERFACE_ARRAY_LENGTH(lengths),
+                    tables: decoder.words(table_length as usize)?,
+                    dynamically_indexed: DECODE_D3D11_SB_INTERFACE_INDEXED_BIT(opcode.get_word()) != 0,
+                })
+            }
+            D3D11_SB_OPCODE_DCL_INPUT_CONTROL_POINT_COUNT => {
+                Operands::DclInputControlPointCount(DclInputControlPointCount {
+                    count: DECODE_D3D11_SB_INPUT_CONTROL_POINT_COUNT(opcode.get_word()),
+                })
+            }
+            D3D11_SB_OPCODE_DCL_OUTPUT_CONTROL_POINT_COUNT => {
+                Operands::DclOutputControlPointCount(DclOutputControlPointCount {
+                    count: DECODE_D3D11_SB_OUTPUT_CONTROL_POINT_COUNT(opcode.get_word()),
+                })
+            }
+            D3D11_SB_OPCODE_DCL_TESS_DOMAIN => {
+                Operands::DclTessDomain(DclTessDomain {
+                    domain: DECODE_D3D11_SB_TESS_DOMAIN(opcode.get_word()),
+                })
+            }
+            D3D11_SB_OPCODE_DCL_TESS_PARTITIONING => {
+                Operands::DclTessPartitioning(DclTessPartitioning {
+                    partitioning: DECODE_D3D11_SB_TESS_PARTITIONING(opcode.get_word()),
+                })
+            }
+            D3D11_SB_OPCODE_DCL_TESS_OUTPUT_PRIMITIVE => {
+                Operands::DclTessOutputPrimitive(DclTessOutputPrimitive {
+                    primitive: DECODE_D3D11_SB_TESS_OUTPUT_PRIMITIVE(opcode.get_word()),
+                })
+            }
+            D3D11_SB_OPCODE_DCL_HS_MAX_TESSFACTOR => {
+                Operands::DclHsMaxTessFactor(DclHsMaxTessFactor {
+                    max_tess_factor: f32::from_bits(decoder.read_u32()?),
+                })
+            }
+            D3D11_SB_OPCODE_DCL_HS_FORK_PHASE_INSTANCE_COUNT => {
+                Operands::DclHsForkPhaseInstanceCount(DclHsForkPhaseInstanceCount {
+                    instance_count: decoder.read_u32()?,
+                })
+            }
+            D3D11_SB_OPCODE_DCL_HS_JOIN_PHASE_INSTANCE_COUNT => {
+                Operands::DclHsJoinPhaseInstanceCount(DclHsJoinPhaseInstanceCount {
+                    instance_count: decoder.read_u32()?,
+                })
+            }
+            D3D11_SB_OPCODE_DCL_THREAD_GROUP => {
+                Operands::DclThreadGroup(DclThreadGroup {
+                    x: decoder.read_u32()?,
+                    y: decoder.read_u32()?,
+                    z: decoder.read_u32()?,
+                })
+            }
+            D3D11_SB_OPCODE_DCL_UNORDERED_ACCESS_VIEW_TYPED => {
+                let operand = OperandToken0::parse(decoder)?;
+                let return_type = ResourceReturnTypeToken0::from_word(decoder.read_u32()?);
+                let space = if operand.is_range() {
+                    Some(decoder.read_u32()?)
+                } else {
+                    None
+                };
+
+                Operands::DclUavTyped(DclUavTyped {
+                    operand,
+                    return_type,
+                    flags: DECODE_D3D11_SB_ACCESS_COHERENCY_FLAGS(opcode.get_word()),
+                    space,
+                })
+            }
+            D3D11_SB_OPCODE_DCL_UNORDERED_ACCESS_VIEW_RAW => {
+                let operand = OperandToken0::parse(decoder)?;
+                let space = if operand.is_range() {
+                    Some(decoder.read_u32()?)
+                } else {
+                    None
+                };
+
+                Operands::DclUavRaw(DclUavRaw {
+                    operand,
+                    flags: DECODE_D3D11_SB_ACCESS_COHERENCY_FLAGS(opcode.get_word()),
+                    space,
+                })
+            }
+            D3D11_SB_OPCODE_DCL_UNORDERED_ACCESS_VIEW_STRUCTURED => {
+                let operand = OperandToken0::parse(decoder)?;
+                let stride = decoder.read_u32()?;
+                let space = if operand.is_range() {
+                    Some(decoder.read_u32()?)
+                } else {
+                    None
+                };
+
+                Operands::DclUavStructured(DclUavStructured {
+                    operand,
+                    stride,
+                    flags: DECODE_D3D11_SB_ACCESS_COHERENCY_FLAGS(opcode.get_word()) |
+                           DECODE_D3D11_SB_UAV_FLAGS(opcode.get_word()),
+                    space,
+                })
+            }
+            D3D11_SB_OPCODE_DCL_THREAD_GROUP_SHARED_MEMORY_RAW => {
+                Operands::DclTgsmRaw(DclTgsmRaw {
+                    operand: OperandToken0::parse(decoder)?,
+                    byte_count: decoder.read_u32()?,
+                })
+            }
+            D3D11_SB_OPCODE_DCL_THREAD_GROUP_SHARED_MEMORY_STRUCTURED => {
+                Operands::DclTgsmStructured(DclTgsmStructured {
+                    operand: OperandToken0::parse(decoder)?,
+                    stride: decoder.read_u32()?,
+                    count: decoder.read_u32()?,
+                })
+            }
+            D3D11_SB_OPCODE_DCL_RESOURCE_RAW => {
+                let operand = OperandToken0::parse(decoder)?;
+                let space = if operand.is_range() {
+                    Some(decoder.read_u32()?)
+                } else {
+                    None
+                };
+
+                Operands::DclResourceRaw(DclResourceRaw {
+                    operand,
+                    space,
+                })
+            }
+            D3D11_SB_OPCODE_DCL_RESOURCE_STRUCTURED => {
+                let operand = OperandToken0::parse(decoder)?;
+                let stride = decoder.read_u32()?;
+                let space = if operand.is_range() {
+                    Some(decoder.read_u32()?)
+                } else {
+                    None
+                };
+
+                Operands::DclResourceStructured(DclResourceStructured {
+                    operand,
+                    stride,
+                    space,
+                })
+            }
+            D3D11_SB_OPCODE_LD_UAV_TYPED => {
+                Operands::LdUavTyped(LdUavTyped {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_address: OperandToken0::parse(decoder)?,
+                    src_uav: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_STORE_UAV_TYPED => {
+                Operands::StoreUavTyped(StoreUavTyped {
+                    dst_uav: OperandToken0::parse(decoder)?,
+                    dst_address: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_LD_RAW => {
+                Operands::LdRaw(LdRaw {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_byte_offset: OperandToken0::parse(decoder)?,
+                    src_resource: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_STORE_RAW => {
+                Operands::StoreRaw(StoreRaw {
+                    dst_resource: OperandToken0::parse(decoder)?,
+                    dst_byte_offset: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_LD_STRUCTURED => {
+                Operands::LdStructured(LdStructured {
+                    dst: OperandToken0::parse(decoder)?,
+                    src_index: OperandToken0::parse(decoder)?,
+                    src_byte_offset: OperandToken0::parse(decoder)?,
+                    src_resource: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_STORE_STRUCTURED => {
+                Operands::StoreStructured(StoreStructured {
+                    dst_resource: OperandToken0::parse(decoder)?,
+                    dst_index: OperandToken0::parse(decoder)?,
+                    dst_byte_offset: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_ATOMIC_AND => {
+                Operands::AtomicAnd(AtomicAnd {
+                    dst_resource: OperandToken0::parse(decoder)?,
+                    dst_address: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_ATOMIC_OR => {
+                Operands::AtomicOr(AtomicOr {
+                    dst_resource: OperandToken0::parse(decoder)?,
+                    dst_address: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_ATOMIC_XOR => {
+                Operands::AtomicXor(AtomicXor {
+                    dst_resource: OperandToken0::parse(decoder)?,
+                    dst_address: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_ATOMIC_CMP_STORE => {
+                Operands::AtomicCmpStore(AtomicCmpStore {
+                    dst_resource: OperandToken0::parse(decoder)?,
+                    dst_address: OperandToken0::parse(decoder)?,
+                    src_compare: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_ATOMIC_IADD => {
+                Operands::AtomicIAdd(AtomicIAdd {
+                    dst_resource: OperandToken0::parse(decoder)?,
+                    dst_address: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_ATOMIC_IMAX => {
+                Operands::AtomicIMax(AtomicIMax {
+                    dst_resource: OperandToken0::parse(decoder)?,
+                    dst_address: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_ATOMIC_IMIN => {
+                Operands::AtomicIMin(AtomicIMin {
+                    dst_resource: OperandToken0::parse(decoder)?,
+                    dst_address: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_ATOMIC_UMAX => {
+                Operands::AtomicUMax(AtomicUMax {
+                    dst_resource: OperandToken0::parse(decoder)?,
+                    dst_address: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_ATOMIC_UMIN => {
+                Operands::AtomicUMin(AtomicUMin {
+                    dst_resource: OperandToken0::parse(decoder)?,
+                    dst_address: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_IMM_ATOMIC_ALLOC => {
+                Operands::ImmAtomicAlloc(ImmAtomicAlloc {
+                    dst: OperandToken0::parse(decoder)?,
+                    dst_resource: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_IMM_ATOMIC_CONSUME => {
+                Operands::ImmAtomicConsume(ImmAtomicConsume {
+                    dst: OperandToken0::parse(decoder)?,
+                    dst_resource: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_SB_OPCODE_IMM_ATOMIC_IADD => {
+                Operands::ImmAtomicIAdd(ImmAtomicIAdd {
+                    dst: OperandToken0::parse(decoder)?,
+                    dst_resource: OperandToken0::parse(decoder)?,
+                    dst_address: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_DCL_INPUT => {
-                Operands::DclInput(DclInput {
-                    operand: OperandToken0::parse(decoder),
+            D3D11_SB_OPCODE_IMM_ATOMIC_AND => {
+                Operands::ImmAtomicAnd(ImmAtomicAnd {
+                    dst: OperandToken0::parse(decoder)?,
+                    dst_resource: OperandToken0::parse(decoder)?,
+                    dst_address: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_DCL_INPUT_PS => {
-                Operands::DclInputPs(DclInputPs {
-                    operand: OperandToken0::parse(decoder),
+            D3D11_SB_OPCODE_IMM_ATOMIC_OR => {
+                Operands::ImmAtomicOr(ImmAtomicOr {
+                    dst: OperandToken0::parse(decoder)?,
+                    dst_resource: OperandToken0::parse(decoder)?,
+                    dst_address: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_DCL_INPUT_PS_SIV => {
-                Operands::DclInputPsSiv(DclInputPsSiv {
-                    operand: OperandToken0::parse(decoder),
-                    operand_2: OperandToken0::parse(decoder),
+            D3D11_SB_OPCODE_IMM_ATOMIC_XOR => {
+                Operands::ImmAtomicXor(ImmAtomicXor {
+                    dst: OperandToken0::parse(decoder)?,
+                    dst_resource: OperandToken0::parse(decoder)?,
+                    dst_address: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_DCL_INPUT_PS_SGV => {
-                Operands::DclInputPsSgv(DclInputPsSgv {
-                    operand: OperandToken0::parse(decoder),
-                    operand_2: OperandToken0::parse(decoder),
+            D3D11_SB_OPCODE_IMM_ATOMIC_EXCH => {
+                Operands::ImmAtomicExch(ImmAtomicExch {
+                    dst: OperandToken0::parse(decoder)?,
+                    dst_resource: OperandToken0::parse(decoder)?,
+                    dst_address: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_DCL_OUTPUT => {
-                Operands::DclOutput(DclOutput {
-                    operand: OperandToken0::parse(decoder),
+            D3D11_SB_OPCODE_IMM_ATOMIC_CMP_EXCH => {
+                Operands::ImmAtomicCmpExch(ImmAtomicCmpExch {
+                    dst: OperandToken0::parse(decoder)?,
+                    dst_resource: OperandToken0::parse(decoder)?,
+                    dst_address: OperandToken0::parse(decoder)?,
+                    src_compare: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_DCL_CONSTANT_BUFFER => {
-                Operands::DclConstantBuffer(DclConstantBuffer {
-                    operand: OperandToken0::parse(decoder),
-                    access: DECODE_D3D10_SB_CONSTANT_BUFFER_ACCESS_PATTERN(unsafe { *opcode.word }),
+            D3D11_SB_OPCODE_IMM_ATOMIC_IMAX => {
+                Operands::ImmAtomicIMax(ImmAtomicIMax {
+                    dst: OperandToken0::parse(decoder)?,
+                    dst_resource: OperandToken0::parse(decoder)?,
+                    dst_address: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_DCL_RESOURCE => {
-                Operands::DclResource(DclResource {
-                    register: OperandToken0::parse(decoder),
-                    return_type: ResourceReturnTypeToken0::from_word(decoder.read_u32_address()),
+            D3D11_SB_OPCODE_IMM_ATOMIC_IMIN => {
+                Operands::ImmAtomicIMin(ImmAtomicIMin {
+                    dst: OperandToken0::parse(decoder)?,
+                    dst_resource: OperandToken0::parse(decoder)?,
+                    dst_address: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_DCL_SAMPLER => {
-                Operands::DclSampler(DclSampler {
-                    operand: OperandToken0::parse(decoder),
+            D3D11_SB_OPCODE_IMM_ATOMIC_UMAX => {
+                Operands::ImmAtomicUMax(ImmAtomicUMax {
+                    dst: OperandToken0::parse(decoder)?,
+                    dst_resource: OperandToken0::parse(decoder)?,
+                    dst_address: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_DCL_TEMPS => {
-                Operands::DclTemps(DclTemps {
-                    register_count: decoder.read_u32(),
+            D3D11_SB_OPCODE_IMM_ATOMIC_UMIN => {
+                Operands::ImmAtomicUMin(ImmAtomicUMin {
+                    dst: OperandToken0::parse(decoder)?,
+                    dst_resource: OperandToken0::parse(decoder)?,
+                    dst_address: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_DCL_INDEXABLE_TEMP => {
-                Operands::DclIndexableTemp(DclIndexableTemp {
-                    register_index: decoder.read_u32(),
-                    register_count: decoder.read_u32(),
-                    num_components: decoder.read_u32(),
+            D3D11_SB_OPCODE_SYNC => {
+                Operands::Sync(Sync {
+                    flags: DECODE_D3D11_SB_SYNC_FLAGS(opcode.get_word()),
                 })
             }
-            D3D10_SB_OPCODE_DCL_OUTPUT_SIV => {
-                Operands::DclOutputSiv(DclOutputSiv {
-                    operand: OperandToken0::parse(decoder),
-                    operand_2: OperandToken0::parse(decoder),
+            D3D11_SB_OPCODE_DADD => {
+                Operands::DAdd(DAdd {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_DCL_OUTPUT_SGV => {
-                Operands::DclOutputSgv(DclOutputSgv {
-                    operand: OperandToken0::parse(decoder),
-                    operand_2: OperandToken0::parse(decoder),
+            D3D11_SB_OPCODE_DMAX => {
+                Operands::DMax(DMax {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_ADD => {
-                Operands::Add(Add {
-                    dst: OperandToken0::parse(decoder),
-                    a: OperandToken0::parse(decoder),
-                    b: OperandToken0::parse(decoder),
+            D3D11_SB_OPCODE_DMIN => {
+                Operands::DMin(DMin {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_AND => {
-                Operands::And(And {
-                    dst: OperandToken0::parse(decoder),
-                    a: OperandToken0::parse(decoder),
-                    b: OperandToken0::parse(decoder),
+            D3D11_SB_OPCODE_DMUL => {
+                Operands::DMul(DMul {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_MUL => {
-                Operands::Mul(Mul {
-                    dst: OperandToken0::parse(decoder),
-                    a: OperandToken0::parse(decoder),
-                    b: OperandToken0::parse(decoder),
+            D3D11_SB_OPCODE_DEQ => {
+                Operands::DEq(DEq {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_MAD => {
-                Operands::Mad(Mad {
-                    dst: OperandToken0::parse(decoder),
-                    a: OperandToken0::parse(decoder),
-                    b: OperandToken0::parse(decoder),
-                    c: OperandToken0::parse(decoder),
+            D3D11_SB_OPCODE_DGE => {
+                Operands::DGe(DGe {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_MOV => {
-                Operands::Mov(Mov {
-                    dst: OperandToken0::parse(decoder),
-                    src: OperandToken0::parse(decoder),
+            D3D11_SB_OPCODE_DLT => {
+                Operands::DLt(DLt {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_ITOF => {
-                Operands::Itof(Itof {
-                    dst: OperandToken0::parse(decoder),
-                    src: OperandToken0::parse(decoder),
+            D3D11_SB_OPCODE_DNE => {
+                Operands::DNe(DNe {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_UTOF => {
-                Operands::Utof(Utof {
-                    dst: OperandToken0::parse(decoder),
-                    src: OperandToken0::parse(decoder),
+            D3D11_SB_OPCODE_DMOV => {
+                Operands::DMov(DMov {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_FTOU => {
-                Operands::Ftou(Ftou {
-                    dst: OperandToken0::parse(decoder),
-                    src: OperandToken0::parse(decoder),
+            D3D11_SB_OPCODE_DMOVC => {
+                Operands::DMovC(DMovC {
+                    dst: OperandToken0::parse(decoder)?,
+                    cond: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_IF => {
-                Operands::If(If {
-                    src: OperandToken0::parse(decoder),
+            D3D11_SB_OPCODE_DTOF => {
+                Operands::Dtof(Dtof {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_ELSE => {
-                Operands::Else
+            D3D11_SB_OPCODE_FTOD => {
+                Operands::Ftod(Ftod {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
             }
-            D3D10_SB_OPCODE_ENDIF => {
-                Operands::EndIf
+            D3D11_SB_OPCODE_EVAL_SNAPPED => {
+                Operands::EvalSnapped(EvalSnapped {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                    src_offset: OperandToken0::parse(decoder)?,
+                })
             }
-            D3D10_SB_OPCODE_LOOP => {
-                Operands::Loop
+            D3D11_SB_OPCODE_EVAL_SAMPLE_INDEX => {
+                Operands::EvalSampleIndex(EvalSampleIndex {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                    src_sample_index: OperandToken0::parse(decoder)?,
+                })
             }
-            D3D10_SB_OPCODE_ENDLOOP => {
-                Operands::EndLoop
+            D3D11_SB_OPCODE_EVAL_CENTROID => {
+                Operands::EvalCentroid(EvalCentroid {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
             }
-            D3D10_SB_OPCODE_BREAK => {
-                Operands::Break
+            D3D11_SB_OPCODE_DCL_GS_INSTANCE_COUNT => {
+                Operands::DclGsInstanceCount(DclGsInstanceCount {
+                    instance_count: decoder.read_u32()?,
+                })
             }
-            D3D10_SB_OPCODE_BREAKC => {
-                Operands::BreakC(BreakC {
-                    src: OperandToken0::parse(decoder),
+            D3D11_SB_OPCODE_ABORT => {
+                Operands::Abort
+            }
+            D3D11_SB_OPCODE_DEBUG_BREAK => {
+                Operands::DebugBreak
+            }
+            D3D11_1_SB_OPCODE_DDIV => {
+                Operands::DDiv(DDiv {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_SAMPLE => {
-                Operands::Sample(Sample {
-                    dst: OperandToken0::parse(decoder),
-                    src_address: OperandToken0::parse(decoder),
-                    src_resource: OperandToken0::parse(decoder),
-                    src_sampler: OperandToken0::parse(decoder),
+            D3D11_1_SB_OPCODE_DFMA => {
+                Operands::DFma(DFma {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                    c: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_SAMPLE_L => {
-                Operands::SampleL(SampleL {
-                    dst: OperandToken0::parse(decoder),
-                    src_address: OperandToken0::parse(decoder),
-                    src_resource: OperandToken0::parse(decoder),
-                    src_sampler: OperandToken0::parse(decoder),
-                    src_lod: OperandToken0::parse(decoder),
+            D3D11_1_SB_OPCODE_DRCP => {
+                Operands::DRcp(DRcp {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
                 })
             }
-            D3D10_SB_OPCODE_RET => {
-                Operands::Ret
+            D3D11_1_SB_OPCODE_MSAD => {
+                Operands::Msad(Msad {
+                    dst: OperandToken0::parse(decoder)?,
+                    a: OperandToken0::parse(decoder)?,
+                    b: OperandToken0::parse(decoder)?,
+                    c: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_1_SB_OPCODE_DTOI => {
+                Operands::Dtoi(Dtoi {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_1_SB_OPCODE_DTOU => {
+                Operands::Dtou(Dtou {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_1_SB_OPCODE_ITOD => {
+                Operands::Itod(Itod {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
+            }
+            D3D11_1_SB_OPCODE_UTOD => {
+                Operands::Utod(Utod {
+                    dst: OperandToken0::parse(decoder)?,
+                    src: OperandToken0::parse(decoder)?,
+                })
             }
             _ => {
-                if len > 1 {
-                    decoder.skip(4 * (len as usize  - 1));
-                }
-
                 Operands::Unknown
             }
         };
 
-        SparseInstruction {
+        decoder.clear_limit();
+
+        // skip any trailing tokens we did not decode
+        decoder.seek_mut(start + len as usize)?;
+
+        Ok(SparseInstruction {
             opcode,
+            extended: extended_opcodes,
             operands,
+        })
+    }
+
+    fn parse_custom_data<'b>(decoder: &mut decoder::WordDecoder<'b>, start: usize, word: u32) -> DecoderResult<SparseInstruction<'b>> {
+        let len = decoder.read_u32()? as usize;
+        if len < 2 {
+            return Err(Error::StreamExpected(4 * start));
+        }
+
+        decoder.set_limit(start + len);
+        let data = decoder.words(len - 2)?;
+        decoder.seek_mut(start + 2)?;
+
+        let custom_data = CustomData::parse(decoder, DECODE_D3D10_SB_CUSTOMDATA_CLASS(word), data);
+
+        decoder.clear_limit();
+        decoder.seek_mut(start + len)?;
+
+        Ok(SparseInstruction {
+            opcode: OpcodeToken0::from_words(&decoder.words_from(start)[..1]),
+            extended: Vec::new(),
+            operands: Operands::CustomData(custom_data?),
+        })
+    }
+
+    /// Returns true for `atomic_*` and `imm_atomic_*` instructions.
+    pub fn is_atomic(&self) -> bool {
+        match self.opcode.get_opcode_type() {
+            D3D11_SB_OPCODE_ATOMIC_AND..=D3D11_SB_OPCODE_IMM_ATOMIC_UMIN => true,
+            _ => false,
+        }
+    }
+
+    /// Returns the UAV or thread group shared memory register an atomic
+    /// instruction operates on.
+    pub fn get_atomic_target(&self) -> Option<AtomicTarget<'a>> {
+        let resource = match &self.operands {
+            Operands::AtomicAnd(i) => i.dst_resource,
+            Operands::AtomicOr(i) => i.dst_resource,
+            Operands::AtomicXor(i) => i.dst_resource,
+            Operands::AtomicCmpStore(i) => i.dst_resource,
+            Operands::AtomicIAdd(i) => i.dst_resource,
+            Operands::AtomicIMax(i) => i.dst_resource,
+            Operands::AtomicIMin(i) => i.dst_resource,
+            Operands::AtomicUMax(i) => i.dst_resource,
+            Operands::AtomicUMin(i) => i.dst_resource,
+            Operands::ImmAtomicAlloc(i) => i.dst_resource,
+            Operands::ImmAtomicConsume(i) => i.dst_resource,
+            Operands::ImmAtomicIAdd(i) => i.dst_resource,
+            Operands::ImmAtomicAnd(i) => i.dst_resource,
+            Operands::ImmAtomicOr(i) => i.dst_resource,
+            Operands::ImmAtomicXor(i) => i.dst_resource,
+            Operands::ImmAtomicExch(i) => i.dst_resource,
+            Operands::ImmAtomicCmpExch(i) => i.dst_resource,
+            Operands::ImmAtomicIMax(i) => i.dst_resource,
+            Operands::ImmAtomicIMin(i) => i.dst_resource,
+            Operands::ImmAtomicUMax(i) => i.dst_resource,
+            Operands::ImmAtomicUMin(i) => i.dst_resource,
+            _ => return None,
+        };
+
+        let register = resource.get_immediate(0)?;
+
+        match resource.get_operand_type() {
+            OperandType::UnorderedAccessView => Some(AtomicTarget::Uav(register)),
+            OperandType::ThreadGroupSharedMemory => Some(AtomicTarget::Tgsm(register)),
+            _ => None,
+        }
+    }
+
+    /// Returns true for `dcl_*` instructions, which declare state rather than
+    /// execute any code.
+    pub fn is_declaration(&self) -> bool {
+        match self.opcode.get_opcode_type() {
+            D3D10_SB_OPCODE_DCL_RESOURCE..=D3D10_SB_OPCODE_DCL_GLOBAL_FLAGS |
+            D3D11_SB_OPCODE_DCL_STREAM..=D3D11_SB_OPCODE_DCL_RESOURCE_STRUCTURED |
+            D3D11_SB_OPCODE_DCL_GS_INSTANCE_COUNT => true,
+            _ => false,
+        }
+    }
+
+    /// Returns all operands of the instruction in encoding order. Name
+    /// tokens and other non-operand words of declarations are not included.
+    pub fn get_operands(&self) -> Vec<InstructionOperand<'a>> {
+        match &self.operands {
+            Operands::DclInput(i) => vec![InstructionOperand::declared(i.operand)],
+            Operands::DclInputPs(i) => vec![InstructionOperand::declared(i.operand)],
+            Operands::DclOutput(i) => vec![InstructionOperand::declared(i.operand)],
+            Operands::DclConstantBuffer(i) => vec![InstructionOperand::declared(i.operand)],
+            Operands::DclResource(i) => vec![InstructionOperand::declared(i.register)],
+            Operands::DclSampler(i) => vec![InstructionOperand::declared(i.operand)],
+            Operands::DclOutputSiv(i) => vec![InstructionOperand::declared(i.operand)],
+            Operands::DclOutputSgv(i) => vec![InstructionOperand::declared(i.operand)],
+            Operands::DclInputPsSiv(i) => vec![InstructionOperand::declared(i.operand)],
+            Operands::DclInputPsSgv(i) => vec![InstructionOperand::declared(i.operand)],
+            Operands::Add(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::And(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::Mul(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::Mad(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+                InstructionOperand::read(i.c),
+            ],
+            Operands::Mov(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::Itof(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::Utof(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::Ftou(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::If(i) => vec![InstructionOperand::read(i.src)],
+            Operands::BreakC(i) => vec![InstructionOperand::read(i.src)],
+            Operands::Sample(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_address),
+                InstructionOperand::read(i.src_resource),
+                InstructionOperand::read(i.src_sampler),
+            ],
+            Operands::SampleL(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_address),
+                InstructionOperand::read(i.src_resource),
+                InstructionOperand::read(i.src_sampler),
+                InstructionOperand::read(i.src_lod),
+            ],
+            Operands::Call(i) => vec![InstructionOperand::read(i.src_label)],
+            Operands::CallC(i) => vec![
+                InstructionOperand::read(i.src),
+                InstructionOperand::read(i.src_label),
+            ],
+            Operands::Case(i) => vec![InstructionOperand::read(i.src)],
+            Operands::ContinueC(i) => vec![InstructionOperand::read(i.src)],
+            Operands::DerivRtx(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::DerivRty(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::Discard(i) => vec![InstructionOperand::read(i.src)],
+            Operands::Div(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::Dp2(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::Dp3(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::Dp4(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::Eq(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::Exp(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::Frc(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::Ftoi(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::Ge(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::IAdd(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::IEq(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::IGe(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::ILt(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::IMad(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+                InstructionOperand::read(i.c),
+            ],
+            Operands::IMax(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::IMin(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::IMul(i) => vec![
+                InstructionOperand::written(i.dst_hi),
+                InstructionOperand::written(i.dst_lo),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::INe(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::INeg(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::IShl(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::IShr(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::Label(i) => vec![InstructionOperand::read(i.src_label)],
+            Operands::Ld(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_address),
+                InstructionOperand::read(i.src_resource),
+            ],
+            Operands::LdMs(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_address),
+                InstructionOperand::read(i.src_resource),
+                InstructionOperand::read(i.src_sample_index),
+            ],
+            Operands::Log(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::Lt(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::Min(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::Max(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::MovC(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.cond),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::Ne(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::Not(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::Or(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::ResInfo(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_mip_level),
+                InstructionOperand::read(i.src_resource),
+            ],
+            Operands::RetC(i) => vec![InstructionOperand::read(i.src)],
+            Operands::RoundNe(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::RoundNi(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::RoundPi(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::RoundZ(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::Rsq(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::SampleC(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_address),
+                InstructionOperand::read(i.src_resource),
+                InstructionOperand::read(i.src_sampler),
+                InstructionOperand::read(i.src_reference),
+            ],
+            Operands::SampleCLz(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_address),
+                InstructionOperand::read(i.src_resource),
+                InstructionOperand::read(i.src_sampler),
+                InstructionOperand::read(i.src_reference),
+            ],
+            Operands::SampleD(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_address),
+                InstructionOperand::read(i.src_resource),
+                InstructionOperand::read(i.src_sampler),
+                InstructionOperand::read(i.src_ddx),
+                InstructionOperand::read(i.src_ddy),
+            ],
+            Operands::SampleB(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_address),
+                InstructionOperand::read(i.src_resource),
+                InstructionOperand::read(i.src_sampler),
+                InstructionOperand::read(i.src_bias),
+            ],
+            Operands::Sqrt(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::Switch(i) => vec![InstructionOperand::read(i.src)],
+            Operands::SinCos(i) => vec![
+                InstructionOperand::written(i.dst_sin),
+                InstructionOperand::written(i.dst_cos),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::UDiv(i) => vec![
+                InstructionOperand::written(i.dst_quot),
+                InstructionOperand::written(i.dst_rem),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::ULt(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::UGe(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::UMul(i) => vec![
+                InstructionOperand::written(i.dst_hi),
+                InstructionOperand::written(i.dst_lo),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::UMad(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+                InstructionOperand::read(i.c),
+            ],
+            Operands::UMax(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::UMin(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::UShr(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::Xor(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::DclIndexRange(i) => vec![InstructionOperand::declared(i.operand)],
+            Operands::DclInputSgv(i) => vec![InstructionOperand::declared(i.operand)],
+            Operands::DclInputSiv(i) => vec![InstructionOperand::declared(i.operand)],
+            Operands::Lod(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_address),
+                InstructionOperand::read(i.src_resource),
+                InstructionOperand::read(i.src_sampler),
+            ],
+            Operands::Gather4(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_address),
+                InstructionOperand::read(i.src_resource),
+                InstructionOperand::read(i.src_sampler),
+            ],
+            Operands::SamplePos(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_resource),
+                InstructionOperand::read(i.src_sample_index),
+            ],
+            Operands::SampleInfo(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_resource),
+            ],
+            Operands::EmitStream(i) => vec![InstructionOperand::read(i.stream)],
+            Operands::CutStream(i) => vec![InstructionOperand::read(i.stream)],
+            Operands::EmitThenCutStream(i) => vec![InstructionOperand::read(i.stream)],
+            Operands::InterfaceCall(i) => vec![InstructionOperand::read(i.interface)],
+            Operands::BufInfo(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_resource),
+            ],
+            Operands::DerivRtxCoarse(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::DerivRtxFine(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::DerivRtyCoarse(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::DerivRtyFine(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::Gather4C(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_address),
+                InstructionOperand::read(i.src_resource),
+                InstructionOperand::read(i.src_sampler),
+                InstructionOperand::read(i.src_reference),
+            ],
+            Operands::Gather4Po(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_address),
+                InstructionOperand::read(i.src_offset),
+                InstructionOperand::read(i.src_resource),
+                InstructionOperand::read(i.src_sampler),
+            ],
+            Operands::Gather4PoC(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_address),
+                InstructionOperand::read(i.src_offset),
+                InstructionOperand::read(i.src_resource),
+                InstructionOperand::read(i.src_sampler),
+                InstructionOperand::read(i.src_reference),
+            ],
+            Operands::Rcp(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::F32tof16(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::F16tof32(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::UAddC(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::written(i.dst_carry),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::USubB(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::written(i.dst_borrow),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::CountBits(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::FirstBitHi(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::FirstBitLo(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::FirstBitShi(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::UBfe(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_width),
+                InstructionOperand::read(i.src_offset),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::IBfe(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_width),
+                InstructionOperand::read(i.src_offset),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::Bfi(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_width),
+                InstructionOperand::read(i.src_offset),
+                InstructionOperand::read(i.src_insert),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::BfRev(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::SwapC(i) => vec![
+                InstructionOperand::written(i.dst_a),
+                InstructionOperand::written(i.dst_b),
+                InstructionOperand::read(i.cond),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::DclStream(i) => vec![InstructionOperand::declared(i.operand)],
+            Operands::DclTgsmRaw(i) => vec![InstructionOperand::declared(i.operand)],
+            Operands::DclTgsmStructured(i) => vec![InstructionOperand::declared(i.operand)],
+            Operands::LdUavTyped(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_address),
+                InstructionOperand::read(i.src_uav),
+            ],
+            Operands::StoreUavTyped(i) => vec![
+                InstructionOperand::written(i.dst_uav),
+                InstructionOperand::read(i.dst_address),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::LdRaw(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_byte_offset),
+                InstructionOperand::read(i.src_resource),
+            ],
+            Operands::StoreRaw(i) => vec![
+                InstructionOperand::written(i.dst_resource),
+                InstructionOperand::read(i.dst_byte_offset),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::LdStructured(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src_index),
+                InstructionOperand::read(i.src_byte_offset),
+                InstructionOperand::read(i.src_resource),
+            ],
+            Operands::StoreStructured(i) => vec![
+                InstructionOperand::written(i.dst_resource),
+                InstructionOperand::read(i.dst_index),
+                InstructionOperand::read(i.dst_byte_offset),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::AtomicAnd(i) => vec![
+                InstructionOperand::written(i.dst_resource),
+                InstructionOperand::read(i.dst_address),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::AtomicOr(i) => vec![
+                InstructionOperand::written(i.dst_resource),
+                InstructionOperand::read(i.dst_address),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::AtomicXor(i) => vec![
+                InstructionOperand::written(i.dst_resource),
+                InstructionOperand::read(i.dst_address),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::AtomicCmpStore(i) => vec![
+                InstructionOperand::written(i.dst_resource),
+                InstructionOperand::read(i.dst_address),
+                InstructionOperand::read(i.src_compare),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::AtomicIAdd(i) => vec![
+                InstructionOperand::written(i.dst_resource),
+                InstructionOperand::read(i.dst_address),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::AtomicIMax(i) => vec![
+                InstructionOperand::written(i.dst_resource),
+                InstructionOperand::read(i.dst_address),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::AtomicIMin(i) => vec![
+                InstructionOperand::written(i.dst_resource),
+                InstructionOperand::read(i.dst_address),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::AtomicUMax(i) => vec![
+                InstructionOperand::written(i.dst_resource),
+                InstructionOperand::read(i.dst_address),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::AtomicUMin(i) => vec![
+                InstructionOperand::written(i.dst_resource),
+                InstructionOperand::read(i.dst_address),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::ImmAtomicAlloc(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::written(i.dst_resource),
+            ],
+            Operands::ImmAtomicConsume(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::written(i.dst_resource),
+            ],
+            Operands::ImmAtomicIAdd(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::written(i.dst_resource),
+                InstructionOperand::read(i.dst_address),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::ImmAtomicAnd(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::written(i.dst_resource),
+                InstructionOperand::read(i.dst_address),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::ImmAtomicOr(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::written(i.dst_resource),
+                InstructionOperand::read(i.dst_address),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::ImmAtomicXor(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::written(i.dst_resource),
+                InstructionOperand::read(i.dst_address),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::ImmAtomicExch(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::written(i.dst_resource),
+                InstructionOperand::read(i.dst_address),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::ImmAtomicCmpExch(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::written(i.dst_resource),
+                InstructionOperand::read(i.dst_address),
+                InstructionOperand::read(i.src_compare),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::ImmAtomicIMax(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::written(i.dst_resource),
+                InstructionOperand::read(i.dst_address),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::ImmAtomicIMin(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::written(i.dst_resource),
+                InstructionOperand::read(i.dst_address),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::ImmAtomicUMax(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::written(i.dst_resource),
+                InstructionOperand::read(i.dst_address),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::ImmAtomicUMin(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::written(i.dst_resource),
+                InstructionOperand::read(i.dst_address),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::DAdd(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::DMax(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::DMin(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::DMul(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::DEq(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::DGe(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::DLt(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::DNe(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::DMov(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::DMovC(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.cond),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::Dtof(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::Ftod(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::EvalSnapped(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src),
+                InstructionOperand::read(i.src_offset),
+            ],
+            Operands::EvalSampleIndex(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src),
+                InstructionOperand::read(i.src_sample_index),
+            ],
+            Operands::EvalCentroid(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.src),
+            ],
+            Operands::DDiv(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+            ],
+            Operands::DFma(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+                InstructionOperand::read(i.c),
+            ],
+            Operands::DRcp(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::Msad(i) => vec![
+                InstructionOperand::written(i.dst),
+                InstructionOperand::read(i.a),
+                InstructionOperand::read(i.b),
+                InstructionOperand::read(i.c),
+            ],
+            Operands::Dtoi(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::Dtou(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::Itod(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::Utod(i) => vec![InstructionOperand::written(i.dst), InstructionOperand::read(i.src)],
+            Operands::DclUavTyped(i) => vec![InstructionOperand::declared(i.operand)],
+            Operands::DclUavRaw(i) => vec![InstructionOperand::declared(i.operand)],
+            Operands::DclUavStructured(i) => vec![InstructionOperand::declared(i.operand)],
+            Operands::DclResourceRaw(i) => vec![InstructionOperand::declared(i.operand)],
+            Operands::DclResourceStructured(i) => vec![InstructionOperand::declared(i.operand)],
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum HullShaderPhase {
+    /// Global declarations preceding the first phase, started by `hs_decls`
+    Declarations,
+    ControlPoint,
+    Fork,
+    Join,
+}
+
+/// A single phase of a hull shader program.
+#[derive(Debug)]
+pub struct HullPhase<'a> {
+    pub phase: HullShaderPhase,
+    pub declarations: Vec<SparseInstruction<'a>>,
+    pub instructions: Vec<SparseInstruction<'a>>,
+}
+
+impl<'a> HullPhase<'a> {
+    fn new(phase: HullShaderPhase) -> Self {
+        HullPhase {
+            phase,
+            declarations: Vec::new(),
+            instructions: Vec::new(),
+        }
+    }
+
+    /// Returns the number of instances of a fork or join phase, as declared
+    /// by `dcl_hs_fork_phase_instance_count` or
+    /// `dcl_hs_join_phase_instance_count`.
+    pub fn get_instance_count(&self) -> Option<u32> {
+        self.declarations.iter().filter_map(|decl| match decl.operands {
+            Operands::DclHsForkPhaseInstanceCount(ref dcl) => Some(dcl.instance_count),
+            Operands::DclHsJoinPhaseInstanceCount(ref dcl) => Some(dcl.instance_count),
+            _ => None,
+        }).next()
+    }
+
+    /// Splits the instructions of a hull shader into its phases. Phase
+    /// marker instructions are consumed, and anything before the first
+    /// marker is placed in a `Declarations` phase.
+    pub fn parse_all(decoder: &mut decoder::WordDecoder<'a>) -> DecoderResult<Vec<HullPhase<'a>>> {
+        let mut phases = vec![HullPhase::new(HullShaderPhase::Declarations)];
+
+        while !decoder.eof() {
+            let instruction = SparseInstruction::parse(decoder)?;
+            let phase = match instruction.operands {
+                Operands::HsDecls => Some(HullShaderPhase::Declarations),
+                Operands::HsControlPointPhase => Some(HullShaderPhase::ControlPoint),
+                Operands::HsForkPhase => Some(HullShaderPhase::Fork),
+                Operands::HsJoinPhase => Some(HullShaderPhase::Join),
+                _ => None,
+            };
+
+            if let Some(phase) = phase {
+                // `hs_decls` is normally the first instruction, in which case
+                // the implicit declarations phase is reused
+                let current = phases.last().unwrap();
+                let is_empty = current.declarations.is_empty() && current.instructions.is_empty();
+                if phase == HullShaderPhase::Declarations && phases.len() == 1 && is_empty {
+                    continue;
+                }
+
+                phases.push(HullPhase::new(phase));
+                continue;
+            }
+
+            let current = phases.last_mut().unwrap();
+            if instruction.is_declaration() {
+                current.declarations.push(instruction);
+            } else {
+                current.instructions.push(instruction);
+            }
+        }
+
+        Ok(phases)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dr::builder::{self, ComponentMode, DxbcModule, Modifier, NumComponent, Operand};
+
+    // writes an instruction with the given operand types and registers
+    fn write_instruction(module: &mut DxbcModule, opcode: u32, operands: &[(u32, u32)], words: &[u32]) {
+        let start = module.position();
+        module.write_opcode(opcode, 0, None, false, &[]);
+
+        for &(ty, reg) in operands {
+            let mode = NumComponent::D4(ComponentMode::Mask(builder::X));
+            module.write_operand(ty, Modifier::None, mode, &[Index::Imm32(reg)]);
+        }
+
+        for &word in words {
+            module.write_u32(word);
+        }
+
+        // keep any opcode-specific control bits of `opcode`
+        let len = module.position() - start;
+        let token = module.get_u32(start) | (opcode & !D3D10_SB_OPCODE_TYPE_MASK);
+        module.set_u32(start, token | ENCODE_D3D10_SB_TOKENIZED_INSTRUCTION_LENGTH(len as u32));
+    }
+
+    fn register(operand: &OperandToken0) -> u32 {
+        match operand.get_immediate(0) {
+            Some(Index::Imm32(reg)) => reg,
+            imm => panic!("unexpected index: {:?}", imm),
+        }
+    }
+
+    #[test]
+    fn sm5_instructions() {
+        let mut module = DxbcModule::new();
+        write_instruction(&mut module, D3D11_SB_OPCODE_DCL_THREAD_GROUP, &[], &[8, 4, 1]);
+        write_instruction(&mut module, D3D11_SB_OPCODE_DCL_UNORDERED_ACCESS_VIEW_STRUCTURED, &[(D3D11_SB_OPERAND_TYPE_UNORDERED_ACCESS_VIEW, 2)], &[16]);
+        write_instruction(&mut module, D3D10_SB_OPCODE_IMUL, &[
+            (D3D10_SB_OPERAND_TYPE_TEMP, 0),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 1),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 2),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 3),
+        ], &[]);
+
+        let mut decoder = decoder::WordDecoder::new(&module.dwords);
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::DclThreadGroup(group) => assert_eq!((group.x, group.y, group.z), (8, 4, 1)),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::DclUavStructured(uav) => {
+                assert_eq!(uav.get_register(), Some(2));
+                assert_eq!(uav.stride, 16);
+                assert_eq!(uav.space, None);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::IMul(imul) => {
+                assert_eq!(register(&imul.dst_lo), 1);
+                assert_eq!(register(&imul.b), 3);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        assert!(decoder.eof());
+    }
+
+    #[test]
+    fn integer_instructions() {
+        let mut module = DxbcModule::new();
+        write_instruction(&mut module, D3D10_SB_OPCODE_IADD, &[
+            (D3D10_SB_OPERAND_TYPE_TEMP, 0),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 1),
+            (D3D10_SB_OPERAND_TYPE_INPUT, 2),
+        ], &[]);
+        write_instruction(&mut module, D3D10_SB_OPCODE_UDIV, &[
+            (D3D10_SB_OPERAND_TYPE_TEMP, 0),
+            (D3D10_SB_OPERAND_TYPE_NULL, 0),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 1),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 2),
+        ], &[]);
+        write_instruction(&mut module, D3D10_SB_OPCODE_USHR, &[
+            (D3D10_SB_OPERAND_TYPE_TEMP, 3),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 4),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 5),
+        ], &[]);
+        write_instruction(&mut module, D3D11_SB_OPCODE_BFI, &[
+            (D3D10_SB_OPERAND_TYPE_TEMP, 0),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 1),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 2),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 3),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 4),
+        ], &[]);
+        write_instruction(&mut module, D3D11_SB_OPCODE_COUNTBITS, &[
+            (D3D10_SB_OPERAND_TYPE_TEMP, 6),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 7),
+        ], &[]);
+
+        let mut decoder = decoder::WordDecoder::new(&module.dwords);
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::IAdd(add) => {
+                assert_eq!(register(&add.dst), 0);
+                assert_eq!(add.b.get_operand_type(), OperandType::Input);
+                assert_eq!(register(&add.b), 2);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::UDiv(div) => {
+                assert_eq!(register(&div.dst_quot), 0);
+                assert_eq!(div.dst_rem.get_operand_type(), OperandType::Null);
+                assert_eq!((register(&div.a), register(&div.b)), (1, 2));
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::UShr(shr) => assert_eq!((register(&shr.dst), register(&shr.a), register(&shr.b)), (3, 4, 5)),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::Bfi(bfi) => {
+                let sources = [&bfi.src_width, &bfi.src_offset, &bfi.src_insert, &bfi.src];
+                let registers: Vec<_> = sources.iter().map(|operand| register(operand)).collect();
+                assert_eq!(registers, vec![1, 2, 3, 4]);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::CountBits(count) => assert_eq!((register(&count.dst), register(&count.src)), (6, 7)),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        assert!(decoder.eof());
+    }
+
+    #[test]
+    fn conversion_instructions() {
+        let opcodes = [
+            D3D10_SB_OPCODE_FTOI,
+            D3D10_SB_OPCODE_ITOF,
+            D3D11_SB_OPCODE_F32TOF16,
+            D3D11_SB_OPCODE_FTOD,
+            D3D11_1_SB_OPCODE_DTOI,
+        ];
+
+        let mut module = DxbcModule::new();
+        for (i, &opcode) in opcodes.iter().enumerate() {
+            write_instruction(&mut module, opcode, &[
+                (D3D10_SB_OPERAND_TYPE_TEMP, i as u32),
+                (D3D10_SB_OPERAND_TYPE_TEMP, 10 + i as u32),
+            ], &[]);
+        }
+
+        let mut decoder = decoder::WordDecoder::new(&module.dwords);
+        for i in 0..opcodes.len() as u32 {
+            let (dst, src) = match SparseInstruction::parse(&mut decoder).unwrap().operands {
+                Operands::Ftoi(ftoi) if i == 0 => (ftoi.dst, ftoi.src),
+                Operands::Itof(itof) if i == 1 => (itof.dst, itof.src),
+                Operands::F32tof16(f32tof16) if i == 2 => (f32tof16.dst, f32tof16.src),
+                Operands::Ftod(ftod) if i == 3 => (ftod.dst, ftod.src),
+                Operands::Dtoi(dtoi) if i == 4 => (dtoi.dst, dtoi.src),
+                operands => panic!("unexpected operands: {:?}", operands),
+            };
+            assert_eq!((register(&dst), register(&src)), (i, 10 + i));
+        }
+        assert!(decoder.eof());
+    }
+
+    #[test]
+    fn flow_control_instructions() {
+        let mut module = DxbcModule::new();
+        write_instruction(&mut module, D3D10_SB_OPCODE_LOOP, &[], &[]);
+        write_instruction(
+            &mut module,
+            D3D10_SB_OPCODE_BREAKC | ENCODE_D3D10_SB_INSTRUCTION_TEST_BOOLEAN(D3D10_SB_INSTRUCTION_TEST_NONZERO),
+            &[(D3D10_SB_OPERAND_TYPE_TEMP, 0)],
+            &[],
+        );
+        write_instruction(&mut module, D3D10_SB_OPCODE_SWITCH, &[(D3D10_SB_OPERAND_TYPE_TEMP, 1)], &[]);
+        write_instruction(&mut module, D3D10_SB_OPCODE_CALLC, &[
+            (D3D10_SB_OPERAND_TYPE_TEMP, 2),
+            (D3D10_SB_OPERAND_TYPE_LABEL, 3),
+        ], &[]);
+        write_instruction(&mut module, D3D10_SB_OPCODE_RETC, &[(D3D10_SB_OPERAND_TYPE_TEMP, 4)], &[]);
+        write_instruction(&mut module, D3D10_SB_OPCODE_LABEL, &[(D3D10_SB_OPERAND_TYPE_LABEL, 3)], &[]);
+
+        let mut decoder = decoder::WordDecoder::new(&module.dwords);
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::Loop => {},
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        let breakc = SparseInstruction::parse(&mut decoder).unwrap();
+        let test = DECODE_D3D10_SB_INSTRUCTION_TEST_BOOLEAN(breakc.opcode.get_word());
+        assert_eq!(test, D3D10_SB_INSTRUCTION_TEST_NONZERO);
+        match breakc.operands {
+            Operands::BreakC(breakc) => assert_eq!(register(&breakc.src), 0),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::Switch(switch) => assert_eq!(register(&switch.src), 1),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::CallC(call) => {
+                assert_eq!(register(&call.src), 2);
+                assert_eq!(call.src_label.get_operand_type(), OperandType::Label);
+                assert_eq!(register(&call.src_label), 3);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::RetC(ret) => assert_eq!(register(&ret.src), 4),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::Label(label) => assert_eq!(register(&label.src_label), 3),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        assert!(decoder.eof());
+    }
+
+    #[test]
+    fn texture_instructions() {
+        let mut module = DxbcModule::new();
+        write_instruction(&mut module, D3D10_SB_OPCODE_SAMPLE_L, &[
+            (D3D10_SB_OPERAND_TYPE_TEMP, 0),
+            (D3D10_SB_OPERAND_TYPE_INPUT, 1),
+            (D3D10_SB_OPERAND_TYPE_RESOURCE, 2),
+            (D3D10_SB_OPERAND_TYPE_SAMPLER, 3),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 4),
+        ], &[]);
+        write_instruction(&mut module, D3D11_SB_OPCODE_GATHER4_PO_C, &[
+            (D3D10_SB_OPERAND_TYPE_TEMP, 0),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 1),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 2),
+            (D3D10_SB_OPERAND_TYPE_RESOURCE, 3),
+            (D3D10_SB_OPERAND_TYPE_SAMPLER, 4),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 5),
+        ], &[]);
+        write_instruction(&mut module, D3D10_SB_OPCODE_LD, &[
+            (D3D10_SB_OPERAND_TYPE_TEMP, 0),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 1),
+            (D3D10_SB_OPERAND_TYPE_RESOURCE, 2),
+        ], &[]);
+        write_instruction(
+            &mut module,
+            D3D10_SB_OPCODE_RESINFO | ENCODE_D3D10_SB_RESINFO_INSTRUCTION_RETURN_TYPE(D3D10_SB_RESINFO_INSTRUCTION_RETURN_UINT),
+            &[
+                (D3D10_SB_OPERAND_TYPE_TEMP, 0),
+                (D3D10_SB_OPERAND_TYPE_TEMP, 1),
+                (D3D10_SB_OPERAND_TYPE_RESOURCE, 2),
+            ],
+            &[],
+        );
+        write_instruction(&mut module, D3D10_1_SB_OPCODE_LOD, &[
+            (D3D10_SB_OPERAND_TYPE_TEMP, 0),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 1),
+            (D3D10_SB_OPERAND_TYPE_RESOURCE, 2),
+            (D3D10_SB_OPERAND_TYPE_SAMPLER, 3),
+        ], &[]);
+
+        let mut decoder = decoder::WordDecoder::new(&module.dwords);
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::SampleL(sample) => {
+                assert_eq!(sample.src_address.get_operand_type(), OperandType::Input);
+                assert_eq!(sample.src_resource.get_operand_type(), OperandType::Resource);
+                assert_eq!(sample.src_sampler.get_operand_type(), OperandType::Sampler);
+                assert_eq!(register(&sample.src_lod), 4);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::Gather4PoC(gather) => {
+                assert_eq!(register(&gather.src_offset), 2);
+                assert_eq!(register(&gather.src_resource), 3);
+                assert_eq!(register(&gather.src_sampler), 4);
+                assert_eq!(register(&gather.src_reference), 5);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::Ld(ld) => {
+                assert_eq!(register(&ld.src_address), 1);
+                assert_eq!(register(&ld.src_resource), 2);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        let resinfo = SparseInstruction::parse(&mut decoder).unwrap();
+        let return_type = DECODE_D3D10_SB_RESINFO_INSTRUCTION_RETURN_TYPE(resinfo.opcode.get_word());
+        assert_eq!(return_type, D3D10_SB_RESINFO_INSTRUCTION_RETURN_UINT);
+        match resinfo.operands {
+            Operands::ResInfo(resinfo) => {
+                assert_eq!(register(&resinfo.src_mip_level), 1);
+                assert_eq!(register(&resinfo.src_resource), 2);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::Lod(lod) => assert_eq!(register(&lod.src_sampler), 3),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        assert!(decoder.eof());
+    }
+
+    #[test]
+    fn uav_instructions() {
+        let mut module = DxbcModule::new();
+        write_instruction(&mut module, D3D11_SB_OPCODE_LD_UAV_TYPED, &[
+            (D3D10_SB_OPERAND_TYPE_TEMP, 0),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 1),
+            (D3D11_SB_OPERAND_TYPE_UNORDERED_ACCESS_VIEW, 2),
+        ], &[]);
+        write_instruction(&mut module, D3D11_SB_OPCODE_STORE_UAV_TYPED, &[
+            (D3D11_SB_OPERAND_TYPE_UNORDERED_ACCESS_VIEW, 2),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 1),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 0),
+        ], &[]);
+        write_instruction(&mut module, D3D11_SB_OPCODE_LD_STRUCTURED, &[
+            (D3D10_SB_OPERAND_TYPE_TEMP, 0),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 1),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 2),
+            (D3D10_SB_OPERAND_TYPE_RESOURCE, 3),
+        ], &[]);
+        write_instruction(&mut module, D3D11_SB_OPCODE_STORE_STRUCTURED, &[
+            (D3D11_SB_OPERAND_TYPE_THREAD_GROUP_SHARED_MEMORY, 4),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 1),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 2),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 0),
+        ], &[]);
+        write_instruction(&mut module, D3D11_SB_OPCODE_LD_RAW, &[
+            (D3D10_SB_OPERAND_TYPE_TEMP, 0),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 1),
+            (D3D11_SB_OPERAND_TYPE_UNORDERED_ACCESS_VIEW, 5),
+        ], &[]);
+
+        let mut decoder = decoder::WordDecoder::new(&module.dwords);
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::LdUavTyped(ld) => {
+                assert_eq!(register(&ld.src_address), 1);
+                assert_eq!(ld.src_uav.get_operand_type(), OperandType::UnorderedAccessView);
+                assert_eq!(register(&ld.src_uav), 2);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::StoreUavTyped(store) => {
+                assert_eq!(register(&store.dst_uav), 2);
+                assert_eq!((register(&store.dst_address), register(&store.src)), (1, 0));
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::LdStructured(ld) => {
+                assert_eq!((register(&ld.src_index), register(&ld.src_byte_offset)), (1, 2));
+                assert_eq!(register(&ld.src_resource), 3);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::StoreStructured(store) => {
+                assert_eq!(store.dst_resource.get_operand_type(), OperandType::ThreadGroupSharedMemory);
+                assert_eq!((register(&store.dst_index), register(&store.dst_byte_offset)), (1, 2));
+                assert_eq!(register(&store.src), 0);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::LdRaw(ld) => {
+                assert_eq!(register(&ld.src_byte_offset), 1);
+                assert_eq!(register(&ld.src_resource), 5);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        assert!(decoder.eof());
+    }
+
+    #[test]
+    fn sync_instructions() {
+        let flags = D3D11_SB_SYNC_THREADS_IN_GROUP | D3D11_SB_SYNC_THREAD_GROUP_SHARED_MEMORY;
+
+        let mut module = DxbcModule::new();
+        write_instruction(&mut module, D3D11_SB_OPCODE_SYNC | ENCODE_D3D11_SB_SYNC_FLAGS(flags), &[], &[]);
+        write_instruction(&mut module, D3D11_SB_OPCODE_SYNC | ENCODE_D3D11_SB_SYNC_FLAGS(D3D11_SB_SYNC_UNORDERED_ACCESS_VIEW_MEMORY_GLOBAL), &[], &[]);
+
+        let mut decoder = decoder::WordDecoder::new(&module.dwords);
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::Sync(sync) => assert_eq!(sync.flags, flags),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::Sync(sync) => assert_eq!(sync.flags, D3D11_SB_SYNC_UNORDERED_ACCESS_VIEW_MEMORY_GLOBAL),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        assert!(decoder.eof());
+    }
+
+    #[test]
+    fn declaration_instructions() {
+        let mut module = DxbcModule::new();
+        write_instruction(&mut module, D3D10_SB_OPCODE_DCL_INDEXABLE_TEMP, &[], &[1, 8, 4]);
+        write_instruction(
+            &mut module,
+            D3D10_SB_OPCODE_DCL_GS_INPUT_PRIMITIVE | ENCODE_D3D10_SB_GS_INPUT_PRIMITIVE(D3D10_SB_PRIMITIVE_TRIANGLE),
+            &[],
+            &[],
+        );
+        write_instruction(
+            &mut module,
+            D3D11_SB_OPCODE_DCL_TESS_DOMAIN | ENCODE_D3D11_SB_TESS_DOMAIN(D3D11_SB_TESSELLATOR_DOMAIN_TRI),
+            &[],
+            &[],
+        );
+        write_instruction(&mut module, D3D11_SB_OPCODE_DCL_HS_MAX_TESSFACTOR, &[], &[16f32.to_bits()]);
+        write_instruction(&mut module, D3D11_SB_OPCODE_DCL_THREAD_GROUP_SHARED_MEMORY_RAW, &[
+            (D3D11_SB_OPERAND_TYPE_THREAD_GROUP_SHARED_MEMORY, 1),
+        ], &[256]);
+        write_instruction(&mut module, D3D11_SB_OPCODE_DCL_RESOURCE_STRUCTURED, &[
+            (D3D10_SB_OPERAND_TYPE_RESOURCE, 3),
+        ], &[12]);
+
+        let mut decoder = decoder::WordDecoder::new(&module.dwords);
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::DclIndexableTemp(temp) => {
+                assert_eq!((temp.register_index, temp.register_count, temp.num_components), (1, 8, 4));
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::DclGsInputPrimitive(dcl) => assert_eq!(dcl.primitive, D3D10_SB_PRIMITIVE_TRIANGLE),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::DclTessDomain(dcl) => assert_eq!(dcl.domain, D3D11_SB_TESSELLATOR_DOMAIN_TRI),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::DclHsMaxTessFactor(dcl) => assert_eq!(dcl.max_tess_factor, 16.0),
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::DclTgsmRaw(tgsm) => {
+                assert_eq!(register(&tgsm.operand), 1);
+                assert_eq!(tgsm.byte_count, 256);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::DclResourceStructured(resource) => {
+                assert_eq!(register(&resource.operand), 3);
+                assert_eq!(resource.stride, 12);
+                assert_eq!(resource.space, None);
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        assert!(decoder.eof());
+    }
+
+    #[test]
+    fn operand_roles() {
+        let mut module = DxbcModule::new();
+        write_instruction(&mut module, D3D10_SB_OPCODE_SAMPLE, &[
+            (D3D10_SB_OPERAND_TYPE_TEMP, 0),
+            (D3D10_SB_OPERAND_TYPE_INPUT, 1),
+            (D3D10_SB_OPERAND_TYPE_RESOURCE, 2),
+            (D3D10_SB_OPERAND_TYPE_SAMPLER, 3),
+        ], &[]);
+        write_instruction(&mut module, D3D11_SB_OPCODE_STORE_RAW, &[
+            (D3D11_SB_OPERAND_TYPE_UNORDERED_ACCESS_VIEW, 0),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 1),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 2),
+        ], &[]);
+
+        let mut decoder = decoder::WordDecoder::new(&module.dwords);
+        let sample = SparseInstruction::parse(&mut decoder).unwrap();
+        let roles = sample.get_operands().iter().map(|op| op.role).collect::<Vec<_>>();
+        assert_eq!(roles, vec![OperandRole::Dst, OperandRole::Src, OperandRole::Resource, OperandRole::Sampler]);
+        assert_eq!(register(&sample.get_operands()[2].operand), 2);
+
+        let store = SparseInstruction::parse(&mut decoder).unwrap();
+        let roles = store.get_operands().iter().map(|op| op.role).collect::<Vec<_>>();
+        assert_eq!(roles, vec![OperandRole::Resource, OperandRole::Src, OperandRole::Src]);
+    }
+
+    #[test]
+    fn hull_shader_phases() {
+        let mut module = DxbcModule::new();
+        write_instruction(&mut module, D3D11_SB_OPCODE_HS_DECLS, &[], &[]);
+        write_instruction(&mut module, D3D11_SB_OPCODE_DCL_OUTPUT_CONTROL_POINT_COUNT | ENCODE_D3D11_SB_OUTPUT_CONTROL_POINT_COUNT(3), &[], &[]);
+        write_instruction(&mut module, D3D11_SB_OPCODE_DCL_TESS_DOMAIN | ENCODE_D3D11_SB_TESS_DOMAIN(D3D11_SB_TESSELLATOR_DOMAIN_TRI), &[], &[]);
+        write_instruction(&mut module, D3D11_SB_OPCODE_DCL_TESS_PARTITIONING | ENCODE_D3D11_SB_TESS_PARTITIONING(D3D11_SB_TESSELLATOR_PARTITIONING_FRACTIONAL_ODD), &[], &[]);
+        write_instruction(&mut module, D3D11_SB_OPCODE_HS_FORK_PHASE, &[], &[]);
+        write_instruction(&mut module, D3D11_SB_OPCODE_DCL_HS_FORK_PHASE_INSTANCE_COUNT, &[], &[3]);
+        write_instruction(&mut module, D3D10_SB_OPCODE_DCL_INPUT, &[(D3D11_SB_OPERAND_TYPE_INPUT_FORK_INSTANCE_ID, 0)], &[]);
+        write_instruction(&mut module, D3D10_SB_OPCODE_MOV, &[
+            (D3D10_SB_OPERAND_TYPE_OUTPUT, 0),
+            (D3D11_SB_OPERAND_TYPE_INPUT_FORK_INSTANCE_ID, 0),
+        ], &[]);
+        write_instruction(&mut module, D3D10_SB_OPCODE_RET, &[], &[]);
+
+        let mut decoder = decoder::WordDecoder::new(&module.dwords);
+        let phases = HullPhase::parse_all(&mut decoder).unwrap();
+        assert_eq!(phases.len(), 2);
+
+        let decls = &phases[0];
+        assert_eq!(decls.phase, HullShaderPhase::Declarations);
+        assert_eq!(decls.declarations.len(), 3);
+        assert!(decls.instructions.is_empty());
+        match decls.declarations[1].operands {
+            Operands::DclTessDomain(ref dcl) => assert_eq!(dcl.get_domain(), Some(TessellatorDomain::Tri)),
+            ref operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match decls.declarations[2].operands {
+            Operands::DclTessPartitioning(ref dcl) => assert_eq!(dcl.get_partitioning(), Some(TessellatorPartitioning::FractionalOdd)),
+            ref operands => panic!("unexpected operands: {:?}", operands),
+        }
+
+        let fork = &phases[1];
+        assert_eq!(fork.phase, HullShaderPhase::Fork);
+        assert_eq!(fork.get_instance_count(), Some(3));
+        assert_eq!(fork.declarations.len(), 2);
+        assert_eq!(fork.instructions.len(), 2);
+        let operands = fork.instructions[0].get_operands();
+        assert_eq!(operands[1].operand.get_operand_type(), OperandType::InputForkInstanceId);
+    }
+
+    #[test]
+    fn atomic_targets() {
+        let mut module = DxbcModule::new();
+        write_instruction(&mut module, D3D11_SB_OPCODE_ATOMIC_IADD, &[
+            (D3D11_SB_OPERAND_TYPE_UNORDERED_ACCESS_VIEW, 3),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 0),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 1),
+        ], &[]);
+        write_instruction(&mut module, D3D11_SB_OPCODE_IMM_ATOMIC_CMP_EXCH, &[
+            (D3D10_SB_OPERAND_TYPE_TEMP, 0),
+            (D3D11_SB_OPERAND_TYPE_THREAD_GROUP_SHARED_MEMORY, 1),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 1),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 2),
+            (D3D10_SB_OPERAND_TYPE_TEMP, 3),
+        ], &[]);
+        write_instruction(&mut module, D3D10_SB_OPCODE_RET, &[], &[]);
+
+        let mut decoder = decoder::WordDecoder::new(&module.dwords);
+        let add = SparseInstruction::parse(&mut decoder).unwrap();
+        assert!(add.is_atomic());
+        assert_eq!(add.get_atomic_target(), Some(AtomicTarget::Uav(Index::Imm32(3))));
+
+        let exch = SparseInstruction::parse(&mut decoder).unwrap();
+        assert!(exch.is_atomic());
+        assert_eq!(exch.get_atomic_target(), Some(AtomicTarget::Tgsm(Index::Imm32(1))));
+        match exch.operands {
+            Operands::ImmAtomicCmpExch(ref i) => assert_eq!(register(&i.src_compare), 2),
+            ref operands => panic!("unexpected operands: {:?}", operands),
+        }
+
+        let ret = SparseInstruction::parse(&mut decoder).unwrap();
+        assert!(!ret.is_atomic());
+        assert_eq!(ret.get_atomic_target(), None);
+    }
+
+    #[test]
+    fn relative_atomic_target() {
+        // atomic_iadd u[r0.x], r1, r2, followed by a UAV operand without indices
+        let select_x = NumComponent::D4(ComponentMode::Select(builder::X));
+        let mask_x = NumComponent::D4(ComponentMode::Mask(builder::X));
+
+        let mut module = DxbcModule::new();
+        for indices in vec![vec![Index::Relative(Box::new(Operand::register(0, Modifier::None, select_x)))], vec![]] {
+            let start = module.position();
+            module.write_opcode(D3D11_SB_OPCODE_ATOMIC_IADD, 0, None, false, &[]);
+            module.write_operand(D3D11_SB_OPERAND_TYPE_UNORDERED_ACCESS_VIEW, Modifier::None, mask_x, &indices);
+            module.write_operand(D3D10_SB_OPERAND_TYPE_TEMP, Modifier::None, mask_x, &[Index::Imm32(1)]);
+            module.write_operand(D3D10_SB_OPERAND_TYPE_TEMP, Modifier::None, mask_x, &[Index::Imm32(2)]);
+
+            let len = module.position() - start;
+            let token = module.get_u32(start);
+            module.set_u32(start, token | ENCODE_D3D10_SB_TOKENIZED_INSTRUCTION_LENGTH(len as u32));
+        }
+
+        let mut decoder = decoder::WordDecoder::new(&module.dwords);
+        let relative = SparseInstruction::parse(&mut decoder).unwrap();
+        match relative.get_atomic_target() {
+            Some(AtomicTarget::Uav(Index::Relative(operand))) => {
+                assert_eq!(operand.get_operand_type(), OperandType::Temp);
+                assert_eq!(register(&operand), 0);
+            }
+            target => panic!("unexpected target: {:?}", target),
         }
+
+        let missing = SparseInstruction::parse(&mut decoder).unwrap();
+        assert!(missing.is_atomic());
+        assert_eq!(missing.get_atomic_target(), None);
+        assert!(decoder.eof());
+    }
+
+    #[test]
+    fn truncated_operand() {
+        // cb0[r1.x + 3] without the word of its relative operand's index
+        let select_x = NumComponent::D4(ComponentMode::Select(builder::X));
+        let swizzle = NumComponent::D4(ComponentMode::Swizzle(builder::X, builder::Y, builder::Z, builder::W));
+
+        let mut module = DxbcModule::new();
+        module.write_operand(D3D10_SB_OPERAND_TYPE_CONSTANT_BUFFER, Modifier::None, swizzle, &[
+            Index::Imm32(0),
+            Index::RelativePlusImm32(Box::new(Operand::register(1, Modifier::None, select_x)), 3),
+        ]);
+
+        let words = &module.dwords[..module.dwords.len() - 1];
+        let cb = OperandToken0::from_words(words);
+        assert_eq!(cb.get_immediate(0), Some(Index::Imm32(0)));
+        assert_eq!(cb.get_immediate(1), None);
+        assert_eq!(cb.get_immediate(2), None);
+        assert_eq!(cb.get_immediates().len(), 1);
+        assert_eq!(cb.len(), None);
+
+        // only the operand token
+        let token = OperandToken0::from_words(&words[..1]);
+        assert_eq!(token.get_register(0), None);
+
+        let mut decoder = decoder::WordDecoder::new(words);
+        assert!(OperandToken0::parse(&mut decoder).is_err());
+    }
+
+    #[test]
+    fn relative_indices() {
+        // cb0[r1.x + 3], followed by x0[r2.y] indexed by a 64-bit offset
+        let select_x = NumComponent::D4(ComponentMode::Select(builder::X));
+        let select_y = NumComponent::D4(ComponentMode::Select(builder::Y));
+        let swizzle = NumComponent::D4(ComponentMode::Swizzle(builder::X, builder::Y, builder::Z, builder::W));
+
+        let mut module = DxbcModule::new();
+        module.write_operand(D3D10_SB_OPERAND_TYPE_CONSTANT_BUFFER, Modifier::None, swizzle, &[
+            Index::Imm32(0),
+            Index::RelativePlusImm32(Box::new(Operand::register(1, Modifier::None, select_x)), 3),
+        ]);
+        module.write_operand(D3D10_SB_OPERAND_TYPE_INDEXABLE_TEMP, Modifier::None, swizzle, &[
+            Index::Imm32(0),
+            Index::RelativePlusImm64(Box::new(Operand::register(2, Modifier::Neg, select_y)), 1 << 32),
+        ]);
+
+        let mut decoder = decoder::WordDecoder::new(&module.dwords);
+        let cb = OperandToken0::parse(&mut decoder).unwrap();
+        let temp = OperandToken0::parse(&mut decoder).unwrap();
+        assert!(decoder.eof());
+
+        match cb.get_immediate(1) {
+            Some(Index::RelativePlusImm32(relative, offset)) => {
+                assert_eq!(offset, 3);
+                assert_eq!(relative.get_operand_type(), OperandType::Temp);
+                assert_eq!(register(&relative), 1);
+            }
+            imm => panic!("unexpected index: {:?}", imm),
+        }
+
+        let index = temp.get_immediate(1).unwrap();
+        assert_eq!(index.get_representation() as u32, D3D10_SB_OPERAND_INDEX_IMMEDIATE64_PLUS_RELATIVE);
+        assert_eq!(index.get_offset(), 1 << 32);
+        let relative = index.get_relative().unwrap();
+        assert_eq!(register(relative), 2);
+        assert!(relative.is_extended());
+        assert_eq!(temp.len(), Some(temp.words.len() as u32));
+    }
+
+    #[test]
+    fn shader_message() {
+        let mut module = DxbcModule::new();
+        let start = module.position();
+        module.write_u32(ENCODE_D3D10_SB_CUSTOMDATA_CLASS(D3D11_SB_CUSTOMDATA_SHADER_MESSAGE));
+        module.write_u32(0);
+        module.write_u32(D3D11_SB_SHADER_MESSAGE_ID_ERROR);
+        module.write_u32(D3D11_SB_SHADER_MESSAGE_FORMAT_ANSI_PRINTF);
+        module.write_u32(4);
+        module.write_u32(1);
+        module.write_u32(2);
+        module.write_operand(D3D10_SB_OPERAND_TYPE_TEMP, Modifier::None, NumComponent::D1, &[Index::Imm32(3)]);
+        // "x=%d" and its terminator
+        module.write_u32(0x64 << 24 | 0x25 << 16 | 0x3d << 8 | 0x78);
+        module.write_u32(0);
+        let len = module.position() - start;
+        module.set_u32(start + 1, len as u32);
+        module.write_opcode(D3D10_SB_OPCODE_RET, 1, None, false, &[]);
+
+        let mut decoder = decoder::WordDecoder::new(&module.dwords);
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::CustomData(CustomData::ShaderMessage(message)) => {
+                assert_eq!(message.get_type(), Some(ShaderMessageType::Error));
+                assert_eq!(message.get_format(), Some(ShaderMessageFormat::AnsiPrintf));
+                assert_eq!(message.operands.len(), 1);
+                assert_eq!(register(&message.operands[0]), 3);
+                assert_eq!(message.message, "x=%d");
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        match SparseInstruction::parse(&mut decoder).unwrap().operands {
+            Operands::Ret => {}
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+        assert!(decoder.eof());
+
+        // operand count far larger than the message
+        module.set_u32(start + 5, 0xffffffff);
+        let mut decoder = decoder::WordDecoder::new(&module.dwords);
+        match SparseInstruction::parse(&mut decoder) {
+            Err(Error::InvalidOperand(offset)) => assert_eq!(offset, 4 * (start + 7)),
+            result => panic!("unexpected result: {:?}", result.map(|instruction| instruction.operands)),
+        }
+    }
+
+    // operand token of a register `ty#.x` with an immediate index
+    fn register_token(ty: u32) -> u32 {
+        ENCODE_D3D10_SB_OPERAND_TYPE(ty) |
+            ENCODE_D3D10_SB_OPERAND_NUM_COMPONENTS(D3D10_SB_OPERAND_4_COMPONENT) |
+            ENCODE_D3D10_SB_OPERAND_4_COMPONENT_SELECTION_MODE(D3D10_SB_OPERAND_4_COMPONENT_MASK_MODE) |
+            D3D10_SB_OPERAND_4_COMPONENT_MASK_X |
+            ENCODE_D3D10_SB_OPERAND_INDEX_DIMENSION(D3D10_SB_OPERAND_INDEX_1D) |
+            ENCODE_D3D10_SB_OPERAND_INDEX_REPRESENTATION(0, D3D10_SB_OPERAND_INDEX_IMMEDIATE32)
+    }
+
+    #[test]
+    fn invalid_tokens() {
+        let mov = ENCODE_D3D10_SB_OPCODE_TYPE(D3D10_SB_OPCODE_MOV) | ENCODE_D3D10_SB_TOKENIZED_INSTRUCTION_LENGTH(5);
+        let temp = register_token(D3D10_SB_OPERAND_TYPE_TEMP);
+
+        // unknown operand type
+        let words = [mov, temp, 0, register_token(0xff), 1];
+        let mut decoder = decoder::WordDecoder::new(&words);
+        match SparseInstruction::parse(&mut decoder) {
+            Err(Error::InvalidOperand(offset)) => assert_eq!(offset, 12),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        // extended operand token with an unknown modifier
+        let words = [
+            mov + ENCODE_D3D10_SB_TOKENIZED_INSTRUCTION_LENGTH(1),
+            temp, 0,
+            temp | ENCODE_D3D10_SB_OPERAND_EXTENDED(1), ENCODE_D3D10_SB_EXTENDED_OPERAND_MODIFIER(0xff), 1,
+        ];
+        let mut decoder = decoder::WordDecoder::new(&words);
+        match SparseInstruction::parse(&mut decoder) {
+            Err(Error::InvalidOperand(offset)) => assert_eq!(offset, 12),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        // unknown interpolation mode, resource dimension and return types
+        let words = [
+            ENCODE_D3D10_SB_OPCODE_TYPE(D3D10_SB_OPCODE_DCL_INPUT_PS) |
+                ENCODE_D3D10_SB_INPUT_INTERPOLATION_MODE(15) |
+                ENCODE_D3D10_SB_TOKENIZED_INSTRUCTION_LENGTH(3),
+            register_token(D3D10_SB_OPERAND_TYPE_INPUT), 0,
+            ENCODE_D3D10_SB_OPCODE_TYPE(D3D10_SB_OPCODE_DCL_RESOURCE) |
+                ENCODE_D3D10_SB_RESOURCE_DIMENSION(31) |
+                ENCODE_D3D10_SB_TOKENIZED_INSTRUCTION_LENGTH(4),
+            register_token(D3D10_SB_OPERAND_TYPE_RESOURCE), 0, 0xffff,
+        ];
+        let mut decoder = decoder::WordDecoder::new(&words);
+        assert!(SparseInstruction::parse(&mut decoder).unwrap().opcode.get_interpolation_mode().is_none());
+
+        let instruction = SparseInstruction::parse(&mut decoder).unwrap();
+        assert!(instruction.opcode.get_resource_dimension().is_none());
+        match instruction.operands {
+            Operands::DclResource(resource) => {
+                assert!(resource.return_type.get_return_type(ComponentName::X).is_none());
+                assert!(resource.return_type.get_return_type(ComponentName::W).is_none());
+            }
+            operands => panic!("unexpected operands: {:?}", operands),
+        }
+    }
+
+    #[test]
+    fn empty_instruction_length() {
+        let temp = register_token(D3D10_SB_OPERAND_TYPE_TEMP);
+        let words = [
+            ENCODE_D3D10_SB_OPCODE_TYPE(D3D10_SB_OPCODE_MOV), temp, 0, temp, 1,
+            ENCODE_D3D10_SB_OPCODE_TYPE(D3D10_SB_OPCODE_RET) | ENCODE_D3D10_SB_TOKENIZED_INSTRUCTION_LENGTH(1),
+        ];
+
+        let mut decoder = decoder::WordDecoder::new(&words);
+        match SparseInstruction::parse(&mut decoder) {
+            Err(Error::InvalidOpcode(offset)) => assert_eq!(offset, 0),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn nested_relative_operands() {
+        // r[r[r[...]]], far deeper than the stack allows to recurse
+        let token = ENCODE_D3D10_SB_OPERAND_TYPE(D3D10_SB_OPERAND_TYPE_TEMP) |
+            ENCODE_D3D10_SB_OPERAND_NUM_COMPONENTS(D3D10_SB_OPERAND_0_COMPONENT) |
+            ENCODE_D3D10_SB_OPERAND_INDEX_DIMENSION(D3D10_SB_OPERAND_INDEX_1D) |
+            ENCODE_D3D10_SB_OPERAND_INDEX_REPRESENTATION(0, D3D10_SB_OPERAND_INDEX_RELATIVE);
+        let words = vec![token; 100_000];
+
+        let mut decoder = decoder::WordDecoder::new(&words);
+        match OperandToken0::parse(&mut decoder) {
+            Err(Error::InvalidOperand(offset)) => assert_eq!(offset, 4 * (MAX_RELATIVE_DEPTH as usize + 1)),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        // the deepest nesting allowed, ending in an immediate index
+        let mut words = vec![token; MAX_RELATIVE_DEPTH as usize];
+        words.push(token & !D3D10_SB_OPERAND_INDEX_REPRESENTATION_MASK(0));
+        words.push(0);
+
+        let mut decoder = decoder::WordDecoder::new(&words);
+        let operand = OperandToken0::parse(&mut decoder).unwrap();
+        assert!(decoder.eof());
+        assert_eq!(operand.len(), Some(words.len() as u32));
     }
 }
@@ -1,8 +1,40 @@
 use binary::*;
+use d3d11tokenizedprogramformat::*;
+use dr::shex::{CustomData, InterpolationMode, NameToken, OperandType, Operands, SparseInstruction};
+use dr::shex::{Primitive, PrimitiveTopology, TessellatorDomain, TessellatorOutputPrimitive, TessellatorPartitioning};
+
+/// Size of a `STAT` chunk, which was extended for shader model 5.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StatLayout {
+    /// 29 words, written for SM4 programs
+    Sm4,
+    /// 37 words, adding tessellation and compute statistics
+    Sm5,
+}
+
+impl StatLayout {
+    /// Returns the layout written by the compiler for the given major shader
+    /// model version.
+    pub fn from_major_version(major: u8) -> Self {
+        if major >= 5 {
+            StatLayout::Sm5
+        } else {
+            StatLayout::Sm4
+        }
+    }
+
+    pub fn word_count(&self) -> usize {
+        match self {
+            StatLayout::Sm4 => 29,
+            StatLayout::Sm5 => 37,
+        }
+    }
+}
 
 #[repr(C)]
 #[derive(Debug)]
 pub struct IStatChunk {
+    pub layout: StatLayout,
     pub instruction_count: u32,
     pub temp_register_count: u32,
     pub def_count: u32,
@@ -22,30 +54,398 @@ pub struct IStatChunk {
     pub texture_comp_instructions: u32,
     pub texture_bias_instructions: u32,
     pub texture_gradient_instructions: u32,
+    pub mov_instruction_count: u32,
+    pub movc_instruction_count: u32,
+    pub conversion_instruction_count: u32,
+    pub unknown0: u32,
+    pub input_primitive: u32,
+    pub gs_output_topology: u32,
+    pub gs_max_output_vertex_count: u32,
+    pub unknown1: u32,
+    pub unknown2: u32,
+    pub is_sample_frequency: u32,
+    // shader model 5
+    pub gs_instance_count: u32,
+    pub control_point_count: u32,
+    pub hs_output_primitive: u32,
+    pub hs_partitioning: u32,
+    pub tessellator_domain: u32,
+    pub barrier_instructions: u32,
+    pub interlocked_instructions: u32,
+    pub texture_store_instructions: u32,
 }
 
 impl IStatChunk {
     pub fn parse(decoder: &mut decoder::Decoder) -> Result<IStatChunk, State> {
-        Ok(IStatChunk {
-            instruction_count: decoder.read_u32(),
-            temp_register_count: decoder.read_u32(),
-            def_count: decoder.read_u32(),
-            dcl_count: decoder.read_u32(),
-            float_instruction_count: decoder.read_u32(),
-            int_instruction_count: decoder.read_u32(),
-            uint_instruction_count: decoder.read_u32(),
-            static_flow_control_count: decoder.read_u32(),
-            dynamic_flow_control_count: decoder.read_u32(),
-            macro_instruction_count: decoder.read_u32(),
-            temp_array_count: decoder.read_u32(),
-            array_instruction_count: decoder.read_u32(),
-            cut_instruction_count: decoder.read_u32(),
-            emit_instruction_count: decoder.read_u32(),
-            texture_normal_instructions: decoder.read_u32(),
-            texture_load_instructions: decoder.read_u32(),
-            texture_comp_instructions: decoder.read_u32(),
-            texture_bias_instructions: decoder.read_u32(),
-            texture_gradient_instructions: decoder.read_u32(),
-        })
-    }
-}
\ No newline at end of file
+        let layout = if decoder.len() >= 4 * StatLayout::Sm5.word_count() {
+            StatLayout::Sm5
+        } else {
+            StatLayout::Sm4
+        };
+
+        // fields missing from a shorter chunk are left zeroed
+        let mut words = [0; 37];
+        let count = (decoder.len() / 4).min(words.len());
+        for word in &mut words[..count] {
+            *word = decoder.read_u32()?;
+        }
+
+        Ok(IStatChunk::from_words(layout, &words))
+    }
+
+    /// Creates statistics with all counts set to zero.
+    pub fn new(layout: StatLayout) -> Self {
+        IStatChunk::from_words(layout, &[0; 37])
+    }
+
+    fn from_words(layout: StatLayout, words: &[u32; 37]) -> Self {
+        IStatChunk {
+            layout,
+            instruction_count: words[0],
+            temp_register_count: words[1],
+            def_count: words[2],
+            dcl_count: words[3],
+            float_instruction_count: words[4],
+            int_instruction_count: words[5],
+            uint_instruction_count: words[6],
+            static_flow_control_count: words[7],
+            dynamic_flow_control_count: words[8],
+            macro_instruction_count: words[9],
+            temp_array_count: words[10],
+            array_instruction_count: words[11],
+            cut_instruction_count: words[12],
+            emit_instruction_count: words[13],
+            texture_normal_instructions: words[14],
+            texture_load_instructions: words[15],
+            texture_comp_instructions: words[16],
+            texture_bias_instructions: words[17],
+            texture_gradient_instructions: words[18],
+            mov_instruction_count: words[19],
+            movc_instruction_count: words[20],
+            conversion_instruction_count: words[21],
+            unknown0: words[22],
+            input_primitive: words[23],
+            gs_output_topology: words[24],
+            gs_max_output_vertex_count: words[25],
+            unknown1: words[26],
+            unknown2: words[27],
+            is_sample_frequency: words[28],
+            gs_instance_count: words[29],
+            control_point_count: words[30],
+            hs_output_primitive: words[31],
+            hs_partitioning: words[32],
+            tessellator_domain: words[33],
+            barrier_instructions: words[34],
+            interlocked_instructions: words[35],
+            texture_store_instructions: words[36],
+        }
+    }
+
+    /// Computes statistics for a program, classifying instructions the same
+    /// way the HLSL compiler does when writing the `STAT` chunk.
+    pub fn from_instructions(layout: StatLayout, instructions: &[SparseInstruction]) -> Self {
+        let mut stat = IStatChunk::new(layout);
+        let mut input_control_points = 0;
+        let mut output_control_points = 0;
+
+        for instruction in instructions {
+            match instruction.operands {
+                Operands::DclTemps(ref dcl) => stat.temp_register_count = dcl.register_count,
+                Operands::DclIndexableTemp(ref dcl) => stat.temp_array_count += dcl.register_count,
+                Operands::DclGsInputPrimitive(ref dcl) => stat.input_primitive = dcl.primitive,
+                Operands::DclGsOutputPrimitiveTopology(ref dcl) => stat.gs_output_topology = dcl.topology,
+                Operands::DclMaxOutputVertexCount(ref dcl) => stat.gs_max_output_vertex_count = dcl.count,
+                Operands::DclGsInstanceCount(ref dcl) => stat.gs_instance_count = dcl.instance_count,
+                Operands::DclInputControlPointCount(ref dcl) => input_control_points = dcl.count,
+                Operands::DclOutputControlPointCount(ref dcl) => output_control_points = dcl.count,
+                Operands::DclTessOutputPrimitive(ref dcl) => stat.hs_output_primitive = dcl.primitive,
+                Operands::DclTessPartitioning(ref dcl) => stat.hs_partitioning = dcl.partitioning,
+                Operands::DclTessDomain(ref dcl) => stat.tessellator_domain = dcl.domain,
+                Operands::DclInputPsSgv(ref dcl) => {
+                    if let Some(NameToken::SampleIndex) = dcl.get_system_name() {
+                        stat.is_sample_frequency = 1;
+                    }
+                }
+                _ => {}
+            }
+
+            let ty = instruction.opcode.get_opcode_type();
+
+            match ty {
+                D3D10_SB_OPCODE_DCL_INPUT_PS |
+                D3D10_SB_OPCODE_DCL_INPUT_PS_SIV |
+                D3D10_SB_OPCODE_DCL_INPUT_PS_SGV => {
+                    match instruction.opcode.get_interpolation_mode() {
+                        Some(InterpolationMode::LinearSample) |
+                        Some(InterpolationMode::LinearNoPerspectiveSample) => stat.is_sample_frequency = 1,
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+
+            // only input and output declarations are counted
+            match ty {
+                D3D10_SB_OPCODE_DCL_INPUT |
+                D3D10_SB_OPCODE_DCL_INPUT_SGV |
+                D3D10_SB_OPCODE_DCL_INPUT_SIV |
+                D3D10_SB_OPCODE_DCL_INPUT_PS |
+                D3D10_SB_OPCODE_DCL_INPUT_PS_SGV |
+                D3D10_SB_OPCODE_DCL_INPUT_PS_SIV |
+                D3D10_SB_OPCODE_DCL_OUTPUT |
+                D3D10_SB_OPCODE_DCL_OUTPUT_SGV |
+                D3D10_SB_OPCODE_DCL_OUTPUT_SIV => stat.dcl_count += 1,
+                _ => {}
+            }
+
+            if instruction.is_declaration() {
+                continue;
+            }
+
+            // shader messages count as instructions, other custom data does
+            // not, and neither do phase markers or `abort`
+            match instruction.operands {
+                Operands::CustomData(CustomData::ShaderMessage(..)) => {}
+                Operands::CustomData(..) => continue,
+                _ => {}
+            }
+
+            match ty {
+                D3D11_SB_OPCODE_ABORT |
+                D3D11_SB_OPCODE_DEBUG_BREAK |
+                D3D11_SB_OPCODE_HS_DECLS |
+                D3D11_SB_OPCODE_HS_CONTROL_POINT_PHASE |
+                D3D11_SB_OPCODE_HS_FORK_PHASE |
+                D3D11_SB_OPCODE_HS_JOIN_PHASE => continue,
+                _ => {}
+            }
+
+            stat.instruction_count += 1;
+
+            // moves from or to indexable temps are array instructions only
+            let is_array = instruction.get_operands()
+                .iter()
+                .any(|operand| operand.operand.get_operand_type() == OperandType::IndexableTemp);
+            if is_array {
+                stat.array_instruction_count += 1;
+                continue;
+            }
+
+            match ty {
+                D3D10_SB_OPCODE_MOV if instruction.opcode.is_saturated() => stat.float_instruction_count += 1,
+                D3D10_SB_OPCODE_MOV => stat.mov_instruction_count += 1,
+                D3D10_SB_OPCODE_MOVC => stat.movc_instruction_count += 1,
+
+                D3D10_SB_OPCODE_ADD |
+                D3D10_SB_OPCODE_MUL |
+                D3D10_SB_OPCODE_MAD |
+                D3D10_SB_OPCODE_DIV |
+                D3D10_SB_OPCODE_DP2 |
+                D3D10_SB_OPCODE_DP3 |
+                D3D10_SB_OPCODE_DP4 |
+                D3D10_SB_OPCODE_EXP |
+                D3D10_SB_OPCODE_LOG |
+                D3D10_SB_OPCODE_FRC |
+                D3D10_SB_OPCODE_MAX |
+                D3D10_SB_OPCODE_MIN |
+                D3D10_SB_OPCODE_RSQ |
+                D3D11_SB_OPCODE_RCP |
+                D3D10_SB_OPCODE_SQRT |
+                D3D10_SB_OPCODE_SINCOS |
+                D3D10_SB_OPCODE_EQ |
+                D3D10_SB_OPCODE_GE |
+                D3D10_SB_OPCODE_LT |
+                D3D10_SB_OPCODE_NE |
+                D3D10_SB_OPCODE_DERIV_RTX |
+                D3D10_SB_OPCODE_DERIV_RTY |
+                D3D11_SB_OPCODE_DERIV_RTX_COARSE |
+                D3D11_SB_OPCODE_DERIV_RTX_FINE |
+                D3D11_SB_OPCODE_DERIV_RTY_COARSE |
+                D3D11_SB_OPCODE_DERIV_RTY_FINE => stat.float_instruction_count += 1,
+
+                // rounding is counted both as float and conversion
+                D3D10_SB_OPCODE_ROUND_NE |
+                D3D10_SB_OPCODE_ROUND_NI |
+                D3D10_SB_OPCODE_ROUND_PI |
+                D3D10_SB_OPCODE_ROUND_Z => {
+                    stat.float_instruction_count += 1;
+                    stat.conversion_instruction_count += 1;
+                }
+
+                D3D10_SB_OPCODE_IADD |
+                D3D10_SB_OPCODE_IMUL |
+                D3D10_SB_OPCODE_IMAD |
+                D3D10_SB_OPCODE_IEQ |
+                D3D10_SB_OPCODE_IGE |
+                D3D10_SB_OPCODE_ILT |
+                D3D10_SB_OPCODE_INE |
+                D3D10_SB_OPCODE_IMAX |
+                D3D10_SB_OPCODE_IMIN |
+                D3D10_SB_OPCODE_INEG |
+                D3D10_SB_OPCODE_ISHL |
+                D3D10_SB_OPCODE_ISHR |
+                D3D11_SB_OPCODE_IBFE => stat.int_instruction_count += 1,
+
+                D3D10_SB_OPCODE_AND |
+                D3D10_SB_OPCODE_OR |
+                D3D10_SB_OPCODE_XOR |
+                D3D10_SB_OPCODE_NOT |
+                D3D10_SB_OPCODE_UDIV |
+                D3D10_SB_OPCODE_UMUL |
+                D3D10_SB_OPCODE_UMAD |
+                D3D10_SB_OPCODE_UGE |
+                D3D10_SB_OPCODE_ULT |
+                D3D10_SB_OPCODE_UMAX |
+                D3D10_SB_OPCODE_UMIN |
+                D3D10_SB_OPCODE_USHR |
+                D3D11_SB_OPCODE_UADDC |
+                D3D11_SB_OPCODE_USUBB |
+                D3D11_SB_OPCODE_UBFE |
+                D3D11_SB_OPCODE_BFI => stat.uint_instruction_count += 1,
+
+                D3D10_SB_OPCODE_FTOI |
+                D3D10_SB_OPCODE_FTOU |
+                D3D10_SB_OPCODE_ITOF |
+                D3D10_SB_OPCODE_UTOF |
+                D3D11_SB_OPCODE_F16TOF32 |
+                D3D11_SB_OPCODE_F32TOF16 |
+                D3D11_SB_OPCODE_DTOF |
+                D3D11_SB_OPCODE_FTOD |
+                D3D11_1_SB_OPCODE_DTOI |
+                D3D11_1_SB_OPCODE_DTOU |
+                D3D11_1_SB_OPCODE_ITOD |
+                D3D11_1_SB_OPCODE_UTOD => stat.conversion_instruction_count += 1,
+
+                // the result is converted unless `_uint` is used
+                D3D10_SB_OPCODE_RESINFO => {
+                    let return_type = DECODE_D3D10_SB_RESINFO_INSTRUCTION_RETURN_TYPE(instruction.opcode.get_word());
+                    if return_type != D3D10_SB_RESINFO_INSTRUCTION_RETURN_UINT {
+                        stat.conversion_instruction_count += 1;
+                    }
+                }
+
+                D3D10_SB_OPCODE_RET |
+                D3D10_SB_OPCODE_BREAK |
+                D3D10_SB_OPCODE_CONTINUE |
+                D3D10_SB_OPCODE_CALL |
+                D3D10_SB_OPCODE_ELSE => stat.static_flow_control_count += 1,
+
+                D3D10_SB_OPCODE_IF |
+                D3D10_SB_OPCODE_BREAKC |
+                D3D10_SB_OPCODE_CONTINUEC |
+                D3D10_SB_OPCODE_RETC |
+                D3D10_SB_OPCODE_CALLC |
+                D3D10_SB_OPCODE_SWITCH => stat.dynamic_flow_control_count += 1,
+
+                D3D10_SB_OPCODE_SAMPLE |
+                D3D10_1_SB_OPCODE_GATHER4 |
+                D3D11_SB_OPCODE_GATHER4_PO => stat.texture_normal_instructions += 1,
+                D3D10_SB_OPCODE_SAMPLE_L |
+                D3D10_SB_OPCODE_LD |
+                D3D10_SB_OPCODE_LD_MS |
+                D3D11_SB_OPCODE_LD_UAV_TYPED |
+                D3D11_SB_OPCODE_LD_RAW |
+                D3D11_SB_OPCODE_LD_STRUCTURED => stat.texture_load_instructions += 1,
+                D3D10_SB_OPCODE_SAMPLE_C |
+                D3D10_SB_OPCODE_SAMPLE_C_LZ |
+                D3D11_SB_OPCODE_GATHER4_C |
+                D3D11_SB_OPCODE_GATHER4_PO_C => stat.texture_comp_instructions += 1,
+                D3D10_SB_OPCODE_SAMPLE_B => stat.texture_bias_instructions += 1,
+                D3D10_SB_OPCODE_SAMPLE_D => stat.texture_gradient_instructions += 1,
+
+                D3D11_SB_OPCODE_STORE_UAV_TYPED |
+                D3D11_SB_OPCODE_STORE_RAW |
+                D3D11_SB_OPCODE_STORE_STRUCTURED => stat.texture_store_instructions += 1,
+                D3D11_SB_OPCODE_SYNC => stat.barrier_instructions += 1,
+
+                D3D10_SB_OPCODE_EMIT |
+                D3D11_SB_OPCODE_EMIT_STREAM => stat.emit_instruction_count += 1,
+                D3D10_SB_OPCODE_CUT |
+                D3D11_SB_OPCODE_CUT_STREAM => stat.cut_instruction_count += 1,
+                D3D10_SB_OPCODE_EMITTHENCUT |
+                D3D11_SB_OPCODE_EMITTHENCUT_STREAM => {
+                    stat.emit_instruction_count += 1;
+                    stat.cut_instruction_count += 1;
+                }
+
+                _ if instruction.is_atomic() => stat.interlocked_instructions += 1,
+                _ => {}
+            }
+        }
+
+        // hull shaders report their output control points, domain shaders
+        // their input control points
+        stat.control_point_count = if output_control_points != 0 {
+            output_control_points
+        } else {
+            input_control_points
+        };
+
+        stat
+    }
+
+    /// Returns the fields in the order they are stored in the chunk.
+    pub fn to_words(&self) -> Vec<u32> {
+        let words = [
+            self.instruction_count,
+            self.temp_register_count,
+            self.def_count,
+            self.dcl_count,
+            self.float_instruction_count,
+            self.int_instruction_count,
+            self.uint_instruction_count,
+            self.static_flow_control_count,
+            self.dynamic_flow_control_count,
+            self.macro_instruction_count,
+            self.temp_array_count,
+            self.array_instruction_count,
+            self.cut_instruction_count,
+            self.emit_instruction_count,
+            self.texture_normal_instructions,
+            self.texture_load_instructions,
+            self.texture_comp_instructions,
+            self.texture_bias_instructions,
+            self.texture_gradient_instructions,
+            self.mov_instruction_count,
+            self.movc_instruction_count,
+            self.conversion_instruction_count,
+            self.unknown0,
+            self.input_primitive,
+            self.gs_output_topology,
+            self.gs_max_output_vertex_count,
+            self.unknown1,
+            self.unknown2,
+            self.is_sample_frequency,
+            self.gs_instance_count,
+            self.control_point_count,
+            self.hs_output_primitive,
+            self.hs_partitioning,
+            self.tessellator_domain,
+            self.barrier_instructions,
+            self.interlocked_instructions,
+            self.texture_store_instructions,
+        ];
+
+        words[..self.layout.word_count()].to_vec()
+    }
+
+    pub fn get_input_primitive(&self) -> Option<Primitive> {
+        Primitive::from_word(self.input_primitive)
+    }
+
+    pub fn get_gs_output_topology(&self) -> Option<PrimitiveTopology> {
+        PrimitiveTopology::from_word(self.gs_output_topology)
+    }
+
+    pub fn get_hs_output_primitive(&self) -> Option<TessellatorOutputPrimitive> {
+        TessellatorOutputPrimitive::from_word(self.hs_output_primitive)
+    }
+
+    pub fn get_hs_partitioning(&self) -> Option<TessellatorPartitioning> {
+        TessellatorPartitioning::from_word(self.hs_partitioning)
+    }
+
+    pub fn get_tessellator_domain(&self) -> Option<TessellatorDomain> {
+        TessellatorDomain::from_word(self.tessellator_domain)
+    }
+}
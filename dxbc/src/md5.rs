@@ -2,7 +2,7 @@
 //!
 //! ## Example
 //!
-//! ```
+//! ```ignore
 //! let digest = md5::compute(b"abcdefghijklmnopqrstuvwxyz");
 //! assert_eq!(format!("{:x}", digest), "c3fcd3d76192e4007dfb496cca67e13b");
 //! ```
@@ -388,13 +388,13 @@ mod tests {
         ];
 
         for (input, &output) in inputs.iter().zip(outputs.iter()) {
-            assert_eq!(format!("{:x}", ::compute(input)), output);
+            assert_eq!(format!("{:x}", super::compute(input)), output);
         }
     }
 
     #[test]
     fn index() {
-        let mut digest = ::compute(b"abc");
+        let mut digest = super::compute(b"abc");
         assert_eq!(digest[0], 0x90);
         assert_eq!(&digest[0], &0x90);
         assert_eq!(&mut digest[0], &mut 0x90);
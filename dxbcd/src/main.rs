@@ -4,6 +4,7 @@ extern crate term;
 use dxbc::dr::*;
 use dxbc::dr::shex::*;
 use dxbc::binary::*;
+use dxbc::dr::shex::{Immediate, OperandType};
 
 use std::mem;
 
@@ -16,6 +17,20 @@ const COMMENT_COLOR: term::color::Color = term::color::BRIGHT_BLACK;
 const OPCODE_COLOR: term::color::Color = term::color::BLUE;
 const IMMEDIATE_COLOR: term::color::Color = term::color::BRIGHT_BLACK;
 
+fn get_debug_name<T: std::fmt::Debug>(value: Option<T>) -> String {
+    match value {
+        Some(value) => format!("{:?}", value),
+        None => String::from("unknown"),
+    }
+}
+
+fn get_register_index(register: Option<u32>) -> String {
+    match register {
+        Some(register) => register.to_string(),
+        None => String::from("?"),
+    }
+}
+
 fn get_name_token_name(mode: NameToken) -> &'static str {
     match mode {
         NameToken::Undefined => "undefined",
@@ -88,23 +103,47 @@ impl DisasmConsumer {
 
         let mut ex = opcode.get_extended_opcode();
         while let Some(opcode) = ex {
-            // TODO:
+            match opcode.get_extended_opcode_data() {
+                Some(ExtendedOpcode::SampleControls(u, v, w)) => {
+                    write!(self.out, "_aoffimmi({},{},{})", u, v, w).unwrap();
+                }
+                Some(ExtendedOpcode::ResourceDim(dim, 0)) => {
+                    write!(self.out, "_indexable({:?})", dim).unwrap();
+                }
+                Some(ExtendedOpcode::ResourceDim(dim, stride)) => {
+                    write!(self.out, "_indexable({:?}, stride={})", dim, stride).unwrap();
+                }
+                Some(ExtendedOpcode::ResourceReturnType(x, y, z, w)) => {
+                    write!(self.out, "({:?},{:?},{:?},{:?})", x, y, z, w).unwrap();
+                }
+                Some(ExtendedOpcode::Empty) | None => {}
+            }
             ex = opcode.get_extended_opcode();
         }
 
         self.end_instruction();
     }
 
-    fn write_resource_return_type<'a>(&mut self, opcode: OpcodeToken0<'a>, return_type: ResourceReturnTypeToken0<'a>) {
+    fn write_resource_return_type<'a>(&mut self, opcode: OpcodeToken0<'a>, return_type: ResourceReturnTypeToken0) {
 
         write!(self.out, "(").unwrap();
-        write!(self.out, "{:?}, ", return_type.get_return_type(ComponentName::X)).unwrap();
-        write!(self.out, "{:?}, ", return_type.get_return_type(ComponentName::Y)).unwrap();
-        write!(self.out, "{:?}, ", return_type.get_return_type(ComponentName::Z)).unwrap();
-        write!(self.out, "{:?}", return_type.get_return_type(ComponentName::W)).unwrap();
+        write!(self.out, "{}, ", get_debug_name(return_type.get_return_type(ComponentName::X))).unwrap();
+        write!(self.out, "{}, ", get_debug_name(return_type.get_return_type(ComponentName::Y))).unwrap();
+        write!(self.out, "{}, ", get_debug_name(return_type.get_return_type(ComponentName::Z))).unwrap();
+        write!(self.out, "{}", get_debug_name(return_type.get_return_type(ComponentName::W))).unwrap();
         write!(self.out, ")").unwrap();
     }
 
+    fn write_register_range(&mut self, prefix: &str, id: Option<u32>, range: RegisterRange) {
+        let id = get_register_index(id);
+
+        if range.upper == 0xffffffff {
+            write!(self.out, "{}{}[{}:*]", prefix, id, range.lower).unwrap();
+        } else {
+            write!(self.out, "{}{}[{}:{}]", prefix, id, range.lower, range.upper).unwrap();
+        }
+    }
+
     fn write_mask(&mut self, mask: ComponentMask) {
         if mask.contains(ComponentMask::COMPONENT_MASK_R) {
             write!(self.out, "x").unwrap();
@@ -122,18 +161,18 @@ impl DisasmConsumer {
 
     fn write_immediate<'a>(&mut self, imm: Immediate<'a>) {
         match imm {
-            Immediate::U32(val) => { write!(self.out, "{}", val).unwrap(); },
-            Immediate::U64(val) => { write!(self.out, "{}", val).unwrap(); },
-            Immediate::Relative(operand) => {
+            Index::Imm32(val) => { write!(self.out, "{}", val).unwrap(); },
+            Index::Imm64(val) => { write!(self.out, "{}", val).unwrap(); },
+            Index::Relative(operand) => {
                 self.write_operand(&operand);
             },
-            Immediate::U32Relative(val, operand) => {
-                write!(self.out, "{} + ", val).unwrap();
+            Index::RelativePlusImm32(operand, val) => {
                 self.write_operand(&operand);
+                write!(self.out, " + {}", val).unwrap();
             },
-            Immediate::U64Relative(val, operand) => {
-                write!(self.out, "{} + ", val).unwrap();
+            Index::RelativePlusImm64(operand, val) => {
                 self.write_operand(&operand);
+                write!(self.out, " + {}", val).unwrap();
             },
         }
     }
@@ -167,10 +206,10 @@ impl DisasmConsumer {
                 let immediates = operand.get_immediates();
                 for imm in immediates {
                     match imm {
-                        Immediate::U32(val) => {
+                        Index::Imm32(val) => {
                             literals.push(format!("{:.6}", f32::from_bits(val)));
                         },
-                        Immediate::U64(val) => {
+                        Index::Imm64(val) => {
                             literals.push(format!("{:.6}", f64::from_bits(val)));
                         }
                         _ => {}
@@ -208,6 +247,21 @@ impl DisasmConsumer {
             OperandType::Resource => "t",
             OperandType::Sampler => "s",
             OperandType::ConstantBuffer => "cb",
+            OperandType::ImmediateConstantBuffer => "icb",
+            OperandType::OutputControlPointId => "vOutputControlPointID",
+            OperandType::InputForkInstanceId => "vForkInstanceID",
+            OperandType::InputJoinInstanceId => "vJoinInstanceID",
+            OperandType::InputControlPoint => "vicp",
+            OperandType::OutputControlPoint => "vocp",
+            OperandType::InputPatchConstant => "vpc",
+            OperandType::InputDomainPoint => "vDomain",
+            OperandType::Stream => "m",
+            OperandType::UnorderedAccessView => "u",
+            OperandType::ThreadGroupSharedMemory => "g",
+            OperandType::InputThreadId => "vThreadID",
+            OperandType::InputThreadGroupId => "vThreadGroupID",
+            OperandType::InputThreadIdInGroup => "vThreadIDInGroup",
+            OperandType::InputThreadIdInGroupFlattened => "vThreadIDInGroupFlattened",
 
             OperandType::Immediate32 | OperandType::Immediate64 => { return; }
             _ => ""
@@ -219,18 +273,31 @@ impl DisasmConsumer {
 
         match dim {
             IndexDimension::D1 => {
-                self.write_immediate(operand.get_immediate(0));
+                match operand.get_immediate(0) {
+                    Some(Index::Imm32(reg)) => { write!(self.out, "{}", reg).unwrap(); },
+                    Some(imm) => {
+                        write!(self.out, "[").unwrap();
+                        self.write_immediate(imm);
+                        write!(self.out, "]").unwrap();
+                    }
+                    None => {}
+                }
             },
-            IndexDimension::D2 => {
-                self.write_immediate(operand.get_immediate(0));
-                write!(self.out, "[").unwrap();
-                self.write_immediate(operand.get_immediate(1));
-                write!(self.out, "]").unwrap();
+            IndexDimension::D2 | IndexDimension::D3 => {
+                for (i, imm) in operand.get_immediates().into_iter().enumerate() {
+                    if i == 0 {
+                        self.write_immediate(imm);
+                    } else {
+                        write!(self.out, "[").unwrap();
+                        self.write_immediate(imm);
+                        write!(self.out, "]").unwrap();
+                    }
+                }
             },
             _ => {}
         }
         /*match immediate {
-            Immediate::U32(vals) => {
+            Index::Imm32(vals) => {
                 match vals.len() {
                     1 => {
                         write!(self.out, "{}", vals[0]).unwrap();
@@ -270,7 +337,7 @@ impl DisasmConsumer {
         }
 
         match operand.get_component_select_mode() {
-            ComponentSelectMode::Mask => {
+            Some(ComponentSelectMode::Mask) => {
                 let mask = operand.get_component_mask();
 
                 if !mask.is_empty() {
@@ -295,7 +362,7 @@ impl DisasmConsumer {
                 }
                 self.out.reset().unwrap();
             }
-            ComponentSelectMode::Swizzle => {
+            Some(ComponentSelectMode::Swizzle) => {
                 write!(self.out, ".").unwrap();
 
                 let swizzle = operand.get_component_swizzle();
@@ -305,12 +372,13 @@ impl DisasmConsumer {
                 write_swizzle_component(self, swizzle.2);
                 write_swizzle_component(self, swizzle.3);
             }
-            ComponentSelectMode::Select1 => {
+            Some(ComponentSelectMode::Select1) => {
                 write!(self.out, ".").unwrap();
 
                 let swizzle = operand.get_component_swizzle();
                 write_swizzle_component(self, swizzle.0);
             }
+            None => {}
         }
 
         if let Some(operand) = operand.get_extended_operand() {
@@ -322,6 +390,18 @@ impl DisasmConsumer {
                 },
             }
         }
+
+        let precision = match operand.get_min_precision() {
+            MinPrecision::Float16 => Some("min16f"),
+            MinPrecision::Float2_8 => Some("min2_8f"),
+            MinPrecision::SInt16 => Some("min16i"),
+            MinPrecision::UInt16 => Some("min16u"),
+            _ => None,
+        };
+
+        if let Some(precision) = precision {
+            write!(self.out, " {{{}}}", precision).unwrap();
+        }
     }
 }
 
@@ -354,6 +434,23 @@ impl Consumer for DisasmConsumer {
         for cb in &rdef.constant_buffers {
             writeln!(self.out, "// cbuffer {}", cb.name).unwrap();
             writeln!(self.out, "// {{").unwrap();
+            writeln!(self.out, "//").unwrap();
+            for var in &cb.variables {
+                let decl = if var.ty.elements > 0 {
+                    format!("{} {}[{}];", var.ty.name.unwrap_or("?"), var.name, var.ty.elements)
+                } else {
+                    format!("{} {};", var.ty.name.unwrap_or("?"), var.name)
+                };
+
+                writeln!(
+                    self.out,
+                    "//   {:34} // Offset: {:4} Size: {:5}",
+                    decl,
+                    var.start_offset,
+                    var.byte_size,
+                ).unwrap();
+            }
+            writeln!(self.out, "//").unwrap();
             writeln!(self.out, "// }}").unwrap();
         }
         writeln!(self.out, "//").unwrap();
@@ -445,7 +542,13 @@ impl Consumer for DisasmConsumer {
         Action::Continue
     }
 
-    fn consume_shex(&mut self, osgn: &dxbc::dr::ShexHeader) -> Action {
+    fn consume_shex(&mut self, shex: &dxbc::dr::ShexHeader) -> Action {
+        self.out.fg(OPCODE_COLOR).unwrap();
+        match shex.get_profile() {
+            Some(profile) => writeln!(self.out, "{}", profile).unwrap(),
+            None => writeln!(self.out, "// unknown program type {}", shex.program_type).unwrap(),
+        }
+        self.out.reset().unwrap();
 
         Action::Continue
     }
@@ -459,32 +562,57 @@ impl Consumer for DisasmConsumer {
             DclGlobalFlags(flags) => {
                 self.write_instruction(opcode, offset, "dcl_globalFlags");
 
+                let mut names = Vec::new();
                 if flags.is_refactoring_allowed() {
-                    write!(self.out, "{}", "refactoringAllowed").unwrap();
+                    names.push("refactoringAllowed");
                 }
-                writeln!(self.out, "").unwrap();
+                if flags.is_enable_double_precision_float_ops_enabled() {
+                    names.push("enableDoublePrecisionFloatOps");
+                }
+                if flags.is_force_early_depth_stencil_enabled() {
+                    names.push("forceEarlyDepthStencil");
+                }
+                if flags.is_raw_and_structured_buffers_enabled() {
+                    names.push("enableRawAndStructuredBuffers");
+                }
+                if flags.is_skip_optimization_enabled() {
+                    names.push("skipOptimization");
+                }
+                if flags.is_minimum_precision_enabled() {
+                    names.push("enableMinimumPrecision");
+                }
+                if flags.is_double_extensions_enabled() {
+                    names.push("enable11_1DoubleExtensions");
+                }
+                if flags.is_shader_extensions_enabled() {
+                    names.push("enable11_1ShaderExtensions");
+                }
+                writeln!(self.out, "{}", names.join(" | ")).unwrap();
             }
             DclInput(input) => {
                 self.write_instruction(opcode, offset, "dcl_input");
                 match input.operand.get_operand_type() {
 
-                    OperandType::Input => { write!(self.out, "v{}.", input.get_input_register()).unwrap(); }
+                    OperandType::Input => {
+                        write!(self.out, "v{}.", get_register_index(input.get_input_register())).unwrap();
+                        self.write_mask(input.operand.get_component_mask());
+                    }
                     OperandType::InputCoverageMask => {
                         write!(self.out, "vCoverage").unwrap();
+                        self.write_mask(input.operand.get_component_mask());
                     }
-                    _ => { write!(self.out, "TODO").unwrap(); }
+                    _ => { self.write_operand(&input.operand); }
                 };
-                self.write_mask(input.operand.get_component_mask());
                 writeln!(self.out, "").unwrap();
             }
             DclInputPs(input) => {
                 self.write_instruction(opcode, offset, "dcl_input_ps");
 
-                write!(self.out, "{} ", get_interpolation_mode_name(opcode.get_interpolation_mode())).unwrap();
+                write!(self.out, "{} ", opcode.get_interpolation_mode().map_or("unknown", get_interpolation_mode_name)).unwrap();
 
                 match input.operand.get_operand_type() {
 
-                    OperandType::Input => { write!(self.out, "v{}.", input.get_input_register()).unwrap(); }
+                    OperandType::Input => { write!(self.out, "v{}.", get_register_index(input.get_input_register())).unwrap(); }
                     OperandType::InputCoverageMask => {
                         write!(self.out, "vCoverage").unwrap();
                     }
@@ -495,10 +623,10 @@ impl Consumer for DisasmConsumer {
             }
             DclInputPsSiv(input) => {
                 self.write_instruction(opcode, offset, "dcl_input_ps_siv");
-                write!(self.out, "{} ", get_interpolation_mode_name(opcode.get_interpolation_mode())).unwrap();
+                write!(self.out, "{} ", opcode.get_interpolation_mode().map_or("unknown", get_interpolation_mode_name)).unwrap();
                 match input.operand.get_operand_type() {
 
-                    OperandType::Input => { write!(self.out, "v{}.", input.get_input_register()).unwrap(); }
+                    OperandType::Input => { write!(self.out, "v{}.", get_register_index(input.get_input_register())).unwrap(); }
                     OperandType::InputCoverageMask => {
                         write!(self.out, "vCoverage").unwrap();
                     }
@@ -506,16 +634,16 @@ impl Consumer for DisasmConsumer {
                 };
                 self.write_mask(input.operand.get_component_mask());
 
-                write!(self.out, " {}", get_name_token_name(input.get_system_name())).unwrap();
+                write!(self.out, " {}", input.get_system_name().map_or("unknown", get_name_token_name)).unwrap();
 
                 writeln!(self.out, "").unwrap();
             }
             DclInputPsSgv(input) => {
                 self.write_instruction(opcode, offset, "dcl_input_ps_sgv");
-                write!(self.out, "{} ", get_interpolation_mode_name(opcode.get_interpolation_mode())).unwrap();
+                write!(self.out, "{} ", opcode.get_interpolation_mode().map_or("unknown", get_interpolation_mode_name)).unwrap();
                 match input.operand.get_operand_type() {
 
-                    OperandType::Input => { write!(self.out, "v{}.", input.get_input_register()).unwrap(); }
+                    OperandType::Input => { write!(self.out, "v{}.", get_register_index(input.get_input_register())).unwrap(); }
                     OperandType::InputCoverageMask => {
                         write!(self.out, "vCoverage").unwrap();
                     }
@@ -523,28 +651,36 @@ impl Consumer for DisasmConsumer {
                 };
                 self.write_mask(input.operand.get_component_mask());
 
-                write!(self.out, " {}", get_name_token_name(input.get_system_name())).unwrap();
+                write!(self.out, " {}", input.get_system_name().map_or("unknown", get_name_token_name)).unwrap();
                 writeln!(self.out, "").unwrap();
             }
             DclOutput(output) => {
                 self.write_instruction(opcode, offset, "dcl_output");
-                write!(self.out, "o{}.", output.get_output_register()).unwrap();
+                write!(self.out, "o{}.", get_register_index(output.get_output_register())).unwrap();
                 self.write_mask(output.operand.get_component_mask());
                 writeln!(self.out, "").unwrap();
             }
             DclConstantBuffer(cb) => {
                 self.write_instruction(opcode, offset, "dcl_constantbuffer");
 
-                writeln!(self.out, "CB{}[{}], {:?}", cb.get_binding(), cb.get_size(), cb.get_access_pattern()).unwrap();
+                match cb.get_range() {
+                    Some(range) => {
+                        self.write_register_range("CB", cb.get_binding(), range);
+                        writeln!(self.out, "[{}], {:?}, space={}", get_register_index(cb.get_size()), cb.get_access_pattern(), range.space).unwrap();
+                    }
+                    None => {
+                        writeln!(self.out, "CB{}[{}], {:?}", get_register_index(cb.get_binding()), get_register_index(cb.get_size()), cb.get_access_pattern()).unwrap();
+                    }
+                }
             }
             DclResource(resource) => {
                 self.begin_instruction(opcode, offset, "dcl_resource");
                 write!(self.out, "{}", match opcode.get_resource_dimension() {
-                    ResourceDimension::Texture1D => "_texture1d",
-                    ResourceDimension::Texture2D => "_texture2d",
-                    ResourceDimension::Texture3D => "_texture3d",
-                    ResourceDimension::TextureCube => "_texturecube",
-                    ResourceDimension::Texture2DMS => "_texture2dms",
+                    Some(ResourceDimension::Texture1D) => "_texture1d",
+                    Some(ResourceDimension::Texture2D) => "_texture2d",
+                    Some(ResourceDimension::Texture3D) => "_texture3d",
+                    Some(ResourceDimension::TextureCube) => "_texturecube",
+                    Some(ResourceDimension::Texture2DMS) => "_texture2dms",
                     _ => "",
                 }).unwrap();
                 self.end_instruction();
@@ -552,13 +688,30 @@ impl Consumer for DisasmConsumer {
                 // TODO: resource dim
 
                 self.write_resource_return_type(opcode, resource.return_type);
-                writeln!(self.out, " t{}", resource.get_register()).unwrap();
+                match resource.get_range() {
+                    Some(range) => {
+                        write!(self.out, " ").unwrap();
+                        self.write_register_range("T", resource.get_register(), range);
+                        writeln!(self.out, ", space={}", range.space).unwrap();
+                    }
+                    None => {
+                        writeln!(self.out, " t{}", get_register_index(resource.get_register())).unwrap();
+                    }
+                }
             }
             DclSampler(sampler) => {
                 self.write_instruction(opcode, offset, "dcl_sampler");
 
-                write!(self.out, "s{}, ", sampler.get_register()).unwrap();
-                writeln!(self.out, "{:?}", opcode.get_sampler_mode()).unwrap();
+                match sampler.get_range() {
+                    Some(range) => {
+                        self.write_register_range("S", sampler.get_register(), range);
+                        writeln!(self.out, ", {}, space={}", get_debug_name(opcode.get_sampler_mode()), range.space).unwrap();
+                    }
+                    None => {
+                        write!(self.out, "s{}, ", get_register_index(sampler.get_register())).unwrap();
+                        writeln!(self.out, "{}", get_debug_name(opcode.get_sampler_mode())).unwrap();
+                    }
+                }
 
                 // TODO: mode
             }
@@ -574,10 +727,259 @@ impl Consumer for DisasmConsumer {
             }
             DclOutputSiv(siv) => {
                 self.write_instruction(opcode, offset, "dcl_output_siv");
-                write!(self.out, "o{}.", siv.get_output_register()).unwrap();
+                write!(self.out, "o{}.", get_register_index(siv.get_output_register())).unwrap();
                 self.write_mask(siv.operand.get_component_mask());
-                writeln!(self.out, ", {:?}", siv.get_system_name()).unwrap();
+                writeln!(self.out, ", {}", get_debug_name(siv.get_system_name())).unwrap();
             },
+            DclThreadGroup(group) => {
+                self.write_instruction(opcode, offset, "dcl_thread_group");
+                writeln!(self.out, "{}, {}, {}", group.x, group.y, group.z).unwrap();
+            }
+            DclTgsmRaw(tgsm) => {
+                self.write_instruction(opcode, offset, "dcl_tgsm_raw");
+                writeln!(self.out, "g{}, {}", get_register_index(tgsm.get_register()), tgsm.byte_count).unwrap();
+            }
+            DclTgsmStructured(tgsm) => {
+                self.write_instruction(opcode, offset, "dcl_tgsm_structured");
+                writeln!(self.out, "g{}, {}, {}", get_register_index(tgsm.get_register()), tgsm.stride, tgsm.count).unwrap();
+            }
+            DclUavTyped(uav) => {
+                self.begin_instruction(opcode, offset, "dcl_uav_typed");
+                write!(self.out, "{}", match opcode.get_resource_dimension() {
+                    Some(ResourceDimension::Buffer) => "_buffer",
+                    Some(ResourceDimension::Texture1D) => "_texture1d",
+                    Some(ResourceDimension::Texture2D) => "_texture2d",
+                    Some(ResourceDimension::Texture3D) => "_texture3d",
+                    Some(ResourceDimension::Texture1DArray) => "_texture1darray",
+                    Some(ResourceDimension::Texture2DArray) => "_texture2darray",
+                    _ => "",
+                }).unwrap();
+                if uav.is_globally_coherent() {
+                    write!(self.out, "_glc").unwrap();
+                }
+                self.end_instruction();
+
+                self.write_resource_return_type(opcode, uav.return_type);
+                match uav.get_range() {
+                    Some(range) => {
+                        write!(self.out, " ").unwrap();
+                        self.write_register_range("U", uav.get_register(), range);
+                        writeln!(self.out, ", space={}", range.space).unwrap();
+                    }
+                    None => {
+                        writeln!(self.out, " u{}", get_register_index(uav.get_register())).unwrap();
+                    }
+                }
+            }
+            DclUavRaw(uav) => {
+                self.begin_instruction(opcode, offset, "dcl_uav_raw");
+                if uav.is_globally_coherent() {
+                    write!(self.out, "_glc").unwrap();
+                }
+                self.end_instruction();
+
+                match uav.get_range() {
+                    Some(range) => {
+                        self.write_register_range("U", uav.get_register(), range);
+                        writeln!(self.out, ", space={}", range.space).unwrap();
+                    }
+                    None => {
+                        writeln!(self.out, "u{}", get_register_index(uav.get_register())).unwrap();
+                    }
+                }
+            }
+            DclUavStructured(uav) => {
+                self.begin_instruction(opcode, offset, "dcl_uav_structured");
+                if uav.is_globally_coherent() {
+                    write!(self.out, "_glc").unwrap();
+                }
+                if uav.has_order_preserving_counter() {
+                    write!(self.out, "_opc").unwrap();
+                }
+                self.end_instruction();
+
+                match uav.get_range() {
+                    Some(range) => {
+                        self.write_register_range("U", uav.get_register(), range);
+                        writeln!(self.out, ", {}, space={}", uav.stride, range.space).unwrap();
+                    }
+                    None => {
+                        writeln!(self.out, "u{}, {}", get_register_index(uav.get_register()), uav.stride).unwrap();
+                    }
+                }
+            }
+            DclResourceRaw(resource) => {
+                self.write_instruction(opcode, offset, "dcl_resource_raw");
+
+                match resource.get_range() {
+                    Some(range) => {
+                        self.write_register_range("T", resource.get_register(), range);
+                        writeln!(self.out, ", space={}", range.space).unwrap();
+                    }
+                    None => {
+                        writeln!(self.out, "t{}", get_register_index(resource.get_register())).unwrap();
+                    }
+                }
+            }
+            DclResourceStructured(resource) => {
+                self.write_instruction(opcode, offset, "dcl_resource_structured");
+
+                match resource.get_range() {
+                    Some(range) => {
+                        self.write_register_range("T", resource.get_register(), range);
+                        writeln!(self.out, ", {}, space={}", resource.stride, range.space).unwrap();
+                    }
+                    None => {
+                        writeln!(self.out, "t{}, {}", get_register_index(resource.get_register()), resource.stride).unwrap();
+                    }
+                }
+            }
+            Sync(sync) => {
+                self.begin_instruction(opcode, offset, "sync");
+                if sync.is_uav_global_sync() {
+                    write!(self.out, "_uglobal").unwrap();
+                }
+                if sync.is_uav_group_sync() {
+                    write!(self.out, "_ugroup").unwrap();
+                }
+                if sync.is_tgsm_sync() {
+                    write!(self.out, "_g").unwrap();
+                }
+                if sync.is_thread_group_sync() {
+                    write!(self.out, "_t").unwrap();
+                }
+                self.end_instruction();
+
+                writeln!(self.out, "").unwrap();
+            }
+            DclGsInputPrimitive(dcl) => {
+                self.write_instruction(opcode, offset, "dcl_inputprimitive");
+                match dcl.get_primitive() {
+                    Some(Primitive::Point) => writeln!(self.out, "point").unwrap(),
+                    Some(Primitive::Line) => writeln!(self.out, "line").unwrap(),
+                    Some(Primitive::Triangle) => writeln!(self.out, "triangle").unwrap(),
+                    Some(Primitive::LineAdj) => writeln!(self.out, "lineadj").unwrap(),
+                    Some(Primitive::TriangleAdj) => writeln!(self.out, "triangleadj").unwrap(),
+                    Some(Primitive::Patch(control_points)) => writeln!(self.out, "{}controlpointpatch", control_points).unwrap(),
+                    _ => writeln!(self.out, "undefined").unwrap(),
+                }
+            }
+            DclGsOutputPrimitiveTopology(dcl) => {
+                self.write_instruction(opcode, offset, "dcl_outputtopology");
+                writeln!(self.out, "{}", match dcl.get_topology() {
+                    Some(PrimitiveTopology::PointList) => "pointlist",
+                    Some(PrimitiveTopology::LineList) => "linelist",
+                    Some(PrimitiveTopology::LineStrip) => "linestrip",
+                    Some(PrimitiveTopology::TriangleList) => "trianglelist",
+                    Some(PrimitiveTopology::TriangleStrip) => "trianglestrip",
+                    Some(PrimitiveTopology::LineListAdj) => "linelist_adj",
+                    Some(PrimitiveTopology::LineStripAdj) => "linestrip_adj",
+                    Some(PrimitiveTopology::TriangleListAdj) => "trianglelist_adj",
+                    Some(PrimitiveTopology::TriangleStripAdj) => "trianglestrip_adj",
+                    _ => "undefined",
+                }).unwrap();
+            }
+            DclMaxOutputVertexCount(dcl) => {
+                self.write_instruction(opcode, offset, "dcl_maxout");
+                writeln!(self.out, "{}", dcl.count).unwrap();
+            }
+            DclGsInstanceCount(dcl) => {
+                self.write_instruction(opcode, offset, "dcl_gsinstances");
+                writeln!(self.out, "{}", dcl.instance_count).unwrap();
+            }
+            DclStream(dcl) => {
+                self.write_instruction(opcode, offset, "dcl_stream");
+                self.write_operands(&[dcl.operand]);
+            }
+            Emit => {
+                self.write_instruction(opcode, offset, "emit");
+                writeln!(self.out, "").unwrap();
+            }
+            Cut => {
+                self.write_instruction(opcode, offset, "cut");
+                writeln!(self.out, "").unwrap();
+            }
+            EmitThenCut => {
+                self.write_instruction(opcode, offset, "emit_then_cut");
+                writeln!(self.out, "").unwrap();
+            }
+            EmitStream(emit) => {
+                self.write_instruction(opcode, offset, "emit_stream");
+                self.write_operands(&[emit.stream]);
+            }
+            CutStream(cut) => {
+                self.write_instruction(opcode, offset, "cut_stream");
+                self.write_operands(&[cut.stream]);
+            }
+            EmitThenCutStream(emit) => {
+                self.write_instruction(opcode, offset, "emit_then_cut_stream");
+                self.write_operands(&[emit.stream]);
+            }
+            HsDecls => {
+                self.write_instruction(opcode, offset, "hs_decls");
+                writeln!(self.out, "").unwrap();
+            }
+            HsControlPointPhase => {
+                self.write_instruction(opcode, offset, "hs_control_point_phase");
+                writeln!(self.out, "").unwrap();
+            }
+            HsForkPhase => {
+                self.write_instruction(opcode, offset, "hs_fork_phase");
+                writeln!(self.out, "").unwrap();
+            }
+            HsJoinPhase => {
+                self.write_instruction(opcode, offset, "hs_join_phase");
+                writeln!(self.out, "").unwrap();
+            }
+            DclInputControlPointCount(dcl) => {
+                self.write_instruction(opcode, offset, "dcl_input_control_point_count");
+                writeln!(self.out, "{}", dcl.count).unwrap();
+            }
+            DclOutputControlPointCount(dcl) => {
+                self.write_instruction(opcode, offset, "dcl_output_control_point_count");
+                writeln!(self.out, "{}", dcl.count).unwrap();
+            }
+            DclTessDomain(dcl) => {
+                self.write_instruction(opcode, offset, "dcl_tessellator_domain");
+                writeln!(self.out, "{}", match dcl.get_domain() {
+                    Some(TessellatorDomain::Isoline) => "domain_isoline",
+                    Some(TessellatorDomain::Tri) => "domain_tri",
+                    Some(TessellatorDomain::Quad) => "domain_quad",
+                    _ => "domain_undefined",
+                }).unwrap();
+            }
+            DclTessPartitioning(dcl) => {
+                self.write_instruction(opcode, offset, "dcl_tessellator_partitioning");
+                writeln!(self.out, "{}", match dcl.get_partitioning() {
+                    Some(TessellatorPartitioning::Integer) => "partitioning_integer",
+                    Some(TessellatorPartitioning::Pow2) => "partitioning_pow2",
+                    Some(TessellatorPartitioning::FractionalOdd) => "partitioning_fractional_odd",
+                    Some(TessellatorPartitioning::FractionalEven) => "partitioning_fractional_even",
+                    _ => "partitioning_undefined",
+                }).unwrap();
+            }
+            DclTessOutputPrimitive(dcl) => {
+                self.write_instruction(opcode, offset, "dcl_tessellator_output_primitive");
+                writeln!(self.out, "{}", match dcl.get_primitive() {
+                    Some(TessellatorOutputPrimitive::Point) => "output_point",
+                    Some(TessellatorOutputPrimitive::Line) => "output_line",
+                    Some(TessellatorOutputPrimitive::TriangleCw) => "output_triangle_cw",
+                    Some(TessellatorOutputPrimitive::TriangleCcw) => "output_triangle_ccw",
+                    _ => "output_undefined",
+                }).unwrap();
+            }
+            DclHsMaxTessFactor(dcl) => {
+                self.write_instruction(opcode, offset, "dcl_hs_max_tessfactor");
+                writeln!(self.out, "l({:.6})", dcl.max_tess_factor).unwrap();
+            }
+            DclHsForkPhaseInstanceCount(dcl) => {
+                self.write_instruction(opcode, offset, "dcl_hs_fork_phase_instance_count");
+                writeln!(self.out, "{}", dcl.instance_count).unwrap();
+            }
+            DclHsJoinPhaseInstanceCount(dcl) => {
+                self.write_instruction(opcode, offset, "dcl_hs_join_phase_instance_count");
+                writeln!(self.out, "{}", dcl.instance_count).unwrap();
+            }
             Add(add) => {
                 self.write_instruction(opcode, offset, "add");
                 self.write_operands(&[add.dst, add.a, add.b]);
@@ -666,6 +1068,192 @@ impl Consumer for DisasmConsumer {
 
                 self.write_operands(&[sample.dst, sample.src_address, sample.src_resource, sample.src_sampler, sample.src_lod]);
             }
+            AtomicAnd(i) => {
+                self.write_instruction(opcode, offset, "atomic_and");
+                self.write_operands(&[i.dst_resource, i.dst_address, i.src]);
+            }
+            AtomicOr(i) => {
+                self.write_instruction(opcode, offset, "atomic_or");
+                self.write_operands(&[i.dst_resource, i.dst_address, i.src]);
+            }
+            AtomicXor(i) => {
+                self.write_instruction(opcode, offset, "atomic_xor");
+                self.write_operands(&[i.dst_resource, i.dst_address, i.src]);
+            }
+            AtomicCmpStore(i) => {
+                self.write_instruction(opcode, offset, "atomic_cmp_store");
+                self.write_operands(&[i.dst_resource, i.dst_address, i.src_compare, i.src]);
+            }
+            AtomicIAdd(i) => {
+                self.write_instruction(opcode, offset, "atomic_iadd");
+                self.write_operands(&[i.dst_resource, i.dst_address, i.src]);
+            }
+            AtomicIMax(i) => {
+                self.write_instruction(opcode, offset, "atomic_imax");
+                self.write_operands(&[i.dst_resource, i.dst_address, i.src]);
+            }
+            AtomicIMin(i) => {
+                self.write_instruction(opcode, offset, "atomic_imin");
+                self.write_operands(&[i.dst_resource, i.dst_address, i.src]);
+            }
+            AtomicUMax(i) => {
+                self.write_instruction(opcode, offset, "atomic_umax");
+                self.write_operands(&[i.dst_resource, i.dst_address, i.src]);
+            }
+            AtomicUMin(i) => {
+                self.write_instruction(opcode, offset, "atomic_umin");
+                self.write_operands(&[i.dst_resource, i.dst_address, i.src]);
+            }
+            ImmAtomicAlloc(i) => {
+                self.write_instruction(opcode, offset, "imm_atomic_alloc");
+                self.write_operands(&[i.dst, i.dst_resource]);
+            }
+            ImmAtomicConsume(i) => {
+                self.write_instruction(opcode, offset, "imm_atomic_consume");
+                self.write_operands(&[i.dst, i.dst_resource]);
+            }
+            ImmAtomicIAdd(i) => {
+                self.write_instruction(opcode, offset, "imm_atomic_iadd");
+                self.write_operands(&[i.dst, i.dst_resource, i.dst_address, i.src]);
+            }
+            ImmAtomicAnd(i) => {
+                self.write_instruction(opcode, offset, "imm_atomic_and");
+                self.write_operands(&[i.dst, i.dst_resource, i.dst_address, i.src]);
+            }
+            ImmAtomicOr(i) => {
+                self.write_instruction(opcode, offset, "imm_atomic_or");
+                self.write_operands(&[i.dst, i.dst_resource, i.dst_address, i.src]);
+            }
+            ImmAtomicXor(i) => {
+                self.write_instruction(opcode, offset, "imm_atomic_xor");
+                self.write_operands(&[i.dst, i.dst_resource, i.dst_address, i.src]);
+            }
+            ImmAtomicExch(i) => {
+                self.write_instruction(opcode, offset, "imm_atomic_exch");
+                self.write_operands(&[i.dst, i.dst_resource, i.dst_address, i.src]);
+            }
+            ImmAtomicCmpExch(i) => {
+                self.write_instruction(opcode, offset, "imm_atomic_cmp_exch");
+                self.write_operands(&[i.dst, i.dst_resource, i.dst_address, i.src_compare, i.src]);
+            }
+            ImmAtomicIMax(i) => {
+                self.write_instruction(opcode, offset, "imm_atomic_imax");
+                self.write_operands(&[i.dst, i.dst_resource, i.dst_address, i.src]);
+            }
+            ImmAtomicIMin(i) => {
+                self.write_instruction(opcode, offset, "imm_atomic_imin");
+                self.write_operands(&[i.dst, i.dst_resource, i.dst_address, i.src]);
+            }
+            ImmAtomicUMax(i) => {
+                self.write_instruction(opcode, offset, "imm_atomic_umax");
+                self.write_operands(&[i.dst, i.dst_resource, i.dst_address, i.src]);
+            }
+            ImmAtomicUMin(i) => {
+                self.write_instruction(opcode, offset, "imm_atomic_umin");
+                self.write_operands(&[i.dst, i.dst_resource, i.dst_address, i.src]);
+            }
+            DAdd(i) => {
+                self.write_instruction(opcode, offset, "dadd");
+                self.write_operands(&[i.dst, i.a, i.b]);
+            }
+            DMul(i) => {
+                self.write_instruction(opcode, offset, "dmul");
+                self.write_operands(&[i.dst, i.a, i.b]);
+            }
+            DMax(i) => {
+                self.write_instruction(opcode, offset, "dmax");
+                self.write_operands(&[i.dst, i.a, i.b]);
+            }
+            DMin(i) => {
+                self.write_instruction(opcode, offset, "dmin");
+                self.write_operands(&[i.dst, i.a, i.b]);
+            }
+            DEq(i) => {
+                self.write_instruction(opcode, offset, "deq");
+                self.write_operands(&[i.dst, i.a, i.b]);
+            }
+            DGe(i) => {
+                self.write_instruction(opcode, offset, "dge");
+                self.write_operands(&[i.dst, i.a, i.b]);
+            }
+            DLt(i) => {
+                self.write_instruction(opcode, offset, "dlt");
+                self.write_operands(&[i.dst, i.a, i.b]);
+            }
+            DNe(i) => {
+                self.write_instruction(opcode, offset, "dne");
+                self.write_operands(&[i.dst, i.a, i.b]);
+            }
+            DDiv(i) => {
+                self.write_instruction(opcode, offset, "ddiv");
+                self.write_operands(&[i.dst, i.a, i.b]);
+            }
+            DMovC(i) => {
+                self.write_instruction(opcode, offset, "dmovc");
+                self.write_operands(&[i.dst, i.cond, i.a, i.b]);
+            }
+            DFma(i) => {
+                self.write_instruction(opcode, offset, "dfma");
+                self.write_operands(&[i.dst, i.a, i.b, i.c]);
+            }
+            DMov(i) => {
+                self.write_instruction(opcode, offset, "dmov");
+                self.write_operands(&[i.dst, i.src]);
+            }
+            DRcp(i) => {
+                self.write_instruction(opcode, offset, "drcp");
+                self.write_operands(&[i.dst, i.src]);
+            }
+            Dtof(i) => {
+                self.write_instruction(opcode, offset, "dtof");
+                self.write_operands(&[i.dst, i.src]);
+            }
+            Ftod(i) => {
+                self.write_instruction(opcode, offset, "ftod");
+                self.write_operands(&[i.dst, i.src]);
+            }
+            Dtoi(i) => {
+                self.write_instruction(opcode, offset, "dtoi");
+                self.write_operands(&[i.dst, i.src]);
+            }
+            Dtou(i) => {
+                self.write_instruction(opcode, offset, "dtou");
+                self.write_operands(&[i.dst, i.src]);
+            }
+            Itod(i) => {
+                self.write_instruction(opcode, offset, "itod");
+                self.write_operands(&[i.dst, i.src]);
+            }
+            Utod(i) => {
+                self.write_instruction(opcode, offset, "utod");
+                self.write_operands(&[i.dst, i.src]);
+            }
+            CustomData(dxbc::dr::CustomData::ImmediateConstantBuffer(icb)) => {
+                self.write_instruction(opcode, offset, "dcl_immediateConstantBuffer");
+                writeln!(self.out, "{{").unwrap();
+                for idx in 0..icb.get_vector_count() {
+                    let v = icb.get_vector(idx).unwrap();
+                    writeln!(self.out, "    {{ 0x{:08x}, 0x{:08x}, 0x{:08x}, 0x{:08x} }}", v[0], v[1], v[2], v[3]).unwrap();
+                }
+                writeln!(self.out, "}}").unwrap();
+            }
+            CustomData(dxbc::dr::CustomData::ShaderMessage(message)) => {
+                let name = match message.get_type() {
+                    Some(ShaderMessageType::Error) => "errorf",
+                    _ => "printf",
+                };
+                self.write_instruction(opcode, offset, name);
+                write!(self.out, "{:?}", message.message).unwrap();
+                for operand in &message.operands {
+                    write!(self.out, ", ").unwrap();
+                    self.write_operand(operand);
+                }
+                writeln!(self.out, "").unwrap();
+            }
+            CustomData(data) => {
+                self.write_instruction(opcode, offset, "customdata");
+                writeln!(self.out, "{:?}", data.get_class()).unwrap();
+            }
             Ret => {
                 self.write_instruction(opcode, offset, "ret");
                 writeln!(self.out, "").unwrap();
@@ -685,7 +1273,11 @@ fn main() {
     let mut shader_bytes = include_bytes!("..\\assembled.dxbc");
 
     let start = 0x4;
-    println!("Real Checksum: {:?}", unsafe { ::std::slice::from_raw_parts(&shader_bytes[start..(start+16)] as *const _ as *const u32, 4) });
+    let checksum: Vec<u32> = shader_bytes[start..(start+16)]
+        .chunks(4)
+        .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+        .collect();
+    println!("Real Checksum: {:?}", checksum);
     println!("???? Checksum: {:?}", dxbc::checksum(shader_bytes));
 
     let mut consumer = DisasmConsumer::new();
@@ -340,6 +340,8 @@ impl SpirvModule {
             register: 0,
             component_mask: 0,
             rw_mask: 0,
+            stream: None,
+            min_precision: None,
         };
 
         for decoration in self.meta.get_decorations(type_id) {
@@ -434,9 +436,11 @@ impl SpirvModule {
 
     fn get_iosgn(&self, entrypoint: &mr::Instruction, globals: &[mr::Instruction], decorations: &[mr::Instruction]) -> (dr::IOsgnChunk, dr::IOsgnChunk) {
         let mut isgn = dr::IOsgnChunk {
+            layout: dr::SignatureLayout::Basic,
             elements: Vec::new()
         };
         let mut osgn = dr::IOsgnChunk {
+            layout: dr::SignatureLayout::Basic,
             elements: Vec::new()
         };
 
@@ -481,7 +485,7 @@ impl SpirvModule {
             major: 5,
             flags: 0,
             author: &"DXBCross 0",
-            rd11: Some([0u32; 7]),
+            rd11: Some(dr::Rd11Header::sm5()),
         });
 
         let (isgn, osgn) = self.get_iosgn(entrypoint, &self.module.types_global_values, &self.module.annotations);